@@ -0,0 +1,38 @@
+//! Picker filtering, which runs on every keystroke and should stay well under a millisecond.
+//! Run with `cargo bench --bench picker`.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+
+use ptrui::fuzzy::{FuzzyCandidate, query_graphemes};
+use ptrui::languages::filtered_language_indices;
+
+fn language_picker(c: &mut Criterion) {
+    for query in ["g", "ger", "chinese zh", "ñ"] {
+        c.bench_function(&format!("filter languages {:?}", query), |b| {
+            b.iter(|| filtered_language_indices(black_box(query)))
+        });
+    }
+}
+
+// Provider language lists run to hundreds of entries once regional variants are included.
+fn large_language_list(c: &mut Criterion) {
+    let candidates: Vec<FuzzyCandidate> = (0..500)
+        .map(|index| {
+            FuzzyCandidate::new(&format!("Language variant {} (Region) xx-{}", index, index))
+        })
+        .collect();
+    let query = query_graphemes("var reg");
+    c.bench_function("filter 500 prepared candidates", |b| {
+        b.iter(|| {
+            candidates
+                .iter()
+                .filter_map(|candidate| candidate.score(black_box(&query)))
+                .count()
+        })
+    });
+}
+
+criterion_group!(benches, language_picker, large_language_list);
+criterion_main!(benches);
@@ -0,0 +1,131 @@
+use crate::api::TranslateOptions;
+use crate::formality::{Formality, supports_formality};
+use crate::tone::Tone;
+
+/// One candidate translation and the settings that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alternative {
+    pub label: String,
+    pub text: String,
+}
+
+/// Candidate translations of the active pane, shown under the target pane.
+pub struct AlternativePicker {
+    pub candidates: Vec<Alternative>,
+    pub selected: usize,
+    // Source version the candidates were translated from.
+    pub version: u64,
+    // Variations still being translated.
+    pub pending: usize,
+}
+
+impl AlternativePicker {
+    /// Adds a candidate unless it reads the same as an earlier one.
+    pub fn add(&mut self, candidate: Alternative) {
+        if !self
+            .candidates
+            .iter()
+            .any(|seen| seen.text.trim() == candidate.text.trim())
+        {
+            self.candidates.push(candidate);
+        }
+    }
+
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % self.candidates.len().max(1);
+    }
+
+    pub fn previous(&mut self) {
+        let len = self.candidates.len().max(1);
+        self.selected = (self.selected + len - 1) % len;
+    }
+
+    pub fn current(&self) -> Option<&Alternative> {
+        self.candidates.get(self.selected)
+    }
+}
+
+/// The API returns one translation per text, so candidates come from re-requesting with
+/// varied settings: the current ones first, then each formality level the target supports
+/// and the formal and casual tones.
+pub fn variations(
+    current: TranslateOptions<'static>,
+    target_lang: &str,
+) -> Vec<(String, TranslateOptions<'static>)> {
+    let mut variations = vec![("current settings".to_string(), current)];
+    if supports_formality(target_lang) {
+        for formality in [Formality::More, Formality::Less] {
+            variations.push((
+                formality.label().to_string(),
+                TranslateOptions {
+                    formality: formality.parameter(target_lang),
+                    ..current
+                },
+            ));
+        }
+    }
+    for tone in [Tone::Formal, Tone::Casual] {
+        variations.push((
+            format!("{} tone", tone.label()),
+            TranslateOptions {
+                tone: tone.instruction(),
+                ..current
+            },
+        ));
+    }
+    // A variation matching the current settings (e.g. the formal tone, when that's already
+    // on) would only cost another request for the same text.
+    let mut unique: Vec<(String, TranslateOptions<'static>)> = Vec::new();
+    for (label, options) in variations {
+        if !unique.iter().any(|(_, seen)| *seen == options) {
+            unique.push((label, options));
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varies_formality_only_where_supported() {
+        let labels = |lang| -> Vec<String> {
+            variations(TranslateOptions::default(), lang)
+                .into_iter()
+                .map(|(label, _)| label)
+                .collect()
+        };
+        assert_eq!(
+            labels("DE"),
+            [
+                "current settings",
+                "formal",
+                "informal",
+                "formal tone",
+                "casual tone"
+            ]
+        );
+        assert_eq!(
+            labels("EN"),
+            ["current settings", "formal tone", "casual tone"]
+        );
+
+        let candidate = |label: &str, text: &str| Alternative {
+            label: label.to_string(),
+            text: text.to_string(),
+        };
+        let mut picker = AlternativePicker {
+            candidates: Vec::new(),
+            selected: 0,
+            version: 0,
+            pending: 0,
+        };
+        picker.add(candidate("current settings", "Hallo"));
+        picker.add(candidate("formal", "Guten Tag"));
+        picker.add(candidate("informal", "Hallo "));
+        assert_eq!(picker.candidates.len(), 2);
+        picker.previous();
+        assert_eq!(picker.current().map(|c| c.label.as_str()), Some("formal"));
+    }
+}
@@ -1,13 +1,60 @@
 use std::env;
-use std::time::Duration;
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
 
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use reqwest::header::{CONTENT_ENCODING, CONTENT_TYPE};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::audit::AuditLog;
+use crate::config::{Config, TimeoutsConfig};
+use crate::languages::{FetchedLanguage, language_name};
+use crate::provider::TranslationProvider;
+use crate::quota::ApiQuota;
+
+// Prefix of errors where the API could not be reached at all, as opposed to rejecting a request.
+const UNREACHABLE_ERROR: &str = "Translation API unreachable";
+
+/// Whether an error means the network or API host is down, so retrying later can succeed.
+pub fn is_unreachable(message: &str) -> bool {
+    message.starts_with(UNREACHABLE_ERROR)
+}
+
 #[derive(Debug, Serialize)]
 struct TranslateRequest<'a> {
     text: Vec<&'a str>,
     source_lang: &'a str,
     target_lang: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formality: Option<&'a str>,
+}
+
+/// Per-request knobs that go beyond the language pair.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TranslateOptions<'a> {
+    // Extra guidance that influences the translation without being translated itself.
+    pub context: Option<&'a str>,
+    // A tone preset's instruction; only chat models can follow it, so the translation API
+    // never sees it.
+    pub tone: Option<&'a str>,
+    // `more` or `less`, only for target languages that support it.
+    pub formality: Option<&'a str>,
+    // Replaces the provider's deadline for this call, e.g. a shorter one for lookups.
+    pub deadline: Option<Duration>,
+}
+
+impl TranslateOptions<'_> {
+    /// For lookups the UI waits on, which give up long before a document would.
+    pub fn lookup() -> Self {
+        Self {
+            deadline: Some(LOOKUP_DEADLINE),
+            ..Self::default()
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -20,20 +67,125 @@ struct TranslationItem {
     text: String,
 }
 
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    // Ollama streams by default; one JSON answer is what every endpoint can give.
+    stream: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatReply,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatReply {
+    content: String,
+}
+
+/// How long a provider may take: to connect, between two reads of an answer, and for a whole
+/// call (one request per chunk of a long document).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    pub connect: Duration,
+    pub read: Duration,
+    pub deadline: Duration,
+}
+
+impl Timeouts {
+    /// `defaults` with the seconds set under `[api.timeouts]` or `[llm.timeouts]`.
+    pub fn from_config(config: &TimeoutsConfig, defaults: Timeouts) -> Self {
+        let seconds = |value: Option<f64>, default| value.map_or(default, Duration::from_secs_f64);
+        Self {
+            connect: seconds(config.connect, defaults.connect),
+            read: seconds(config.read, defaults.read),
+            deadline: seconds(config.deadline, defaults.deadline),
+        }
+    }
+}
+
+// Smaller request bodies aren't worth compressing.
+const GZIP_MIN_BYTES: usize = 4096;
+// Idle connections are kept this long so chunked documents reuse one TLS session.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+// Short connects keep interactive feedback quick when offline, the read timeout catches a
+// stalled server early and the deadline allows slow document chunks.
+pub const TRANSLATION_API_TIMEOUTS: Timeouts = Timeouts {
+    connect: Duration::from_secs(5),
+    read: Duration::from_secs(30),
+    deadline: Duration::from_secs(120),
+};
+// Chat models write long completions token by token, and without streaming say nothing until
+// they are done, so they get longer.
+pub const LLM_TIMEOUTS: Timeouts = Timeouts {
+    connect: Duration::from_secs(5),
+    read: Duration::from_secs(120),
+    deadline: Duration::from_secs(300),
+};
+// Deadline of glosses, quality estimates and other lookups the UI waits on.
+pub const LOOKUP_DEADLINE: Duration = Duration::from_secs(15);
+
 pub struct PtruiApi {
     pub client: reqwest::blocking::Client,
     pub url: String,
+    pub timeouts: Timeouts,
+    // Gzip large request bodies; only for APIs that accept `Content-Encoding: gzip`.
+    pub gzip_requests: bool,
     pub auth_header: Option<String>,
     pub auth_value: Option<String>,
+    // Where outbound calls are accounted for, when enabled.
+    pub audit: Option<AuditLog>,
 }
 
 impl PtruiApi {
-    pub fn from_env() -> Result<Self, String> {
-        let url = env::var("TRANSLATION_API_URL")
-            .map_err(|_| "Missing TRANSLATION_API_URL environment variable".to_string())?;
-        let auth_key = env::var("TRANSLATION_API_KEY").ok();
-        let auth_header = env::var("TRANSLATION_API_AUTH_HEADER").ok();
+    /// The endpoint, key and timeouts from the config (`TRANSLATION_API_URL` and friends
+    /// override them); gzip and auditing are still set through the environment.
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let timeouts = Timeouts::from_config(&config.api.timeouts, TRANSLATION_API_TIMEOUTS);
+        Self::with_timeouts(config, timeouts)
+    }
+
+    fn with_timeouts(config: &Config, timeouts: Timeouts) -> Result<Self, String> {
+        let url = config.api.url.clone().ok_or_else(|| {
+            "Missing TRANSLATION_API_URL environment variable (or `url` under [api] in the config file)"
+                .to_string()
+        })?;
+        let mut api = Self::new(
+            url,
+            config.api.key.clone(),
+            config.api.auth_header.clone(),
+            timeouts,
+        )?;
+        api.gzip_requests = env::var("TRANSLATION_API_GZIP")
+            .is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes"));
+        api.audit = AuditLog::from_env();
+        Ok(api)
+    }
 
+    /// A client for `url`; the key goes in `auth_header` (`Authorization` by default, as a
+    /// DeepL auth key).
+    pub fn new(
+        url: String,
+        auth_key: Option<String>,
+        auth_header: Option<String>,
+        timeouts: Timeouts,
+    ) -> Result<Self, String> {
         let (header_name, header_value) = match auth_key {
             Some(key) => {
                 let header = auth_header.unwrap_or_else(|| "Authorization".to_string());
@@ -47,52 +199,513 @@ impl PtruiApi {
             None => (None, None),
         };
 
+        // The blocking client's timeout bounds each wait: for the response head and for every
+        // read of the body. `post_json` adds the deadline on top.
         let client = reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(15))
+            .connect_timeout(timeouts.connect)
+            .timeout(timeouts.read)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .pool_max_idle_per_host(4)
+            .tcp_keepalive(Duration::from_secs(60))
             .build()
             .map_err(|err| format!("Failed to build HTTP client: {}", err))?;
 
         Ok(Self {
             client,
             url,
+            timeouts,
+            gzip_requests: false,
             auth_header: header_name,
             auth_value: header_value,
+            audit: None,
+        })
+    }
+
+    /// Short provider label for history and logs: the API host, e.g. `api.deepl.com`.
+    pub fn provider_name(&self) -> String {
+        reqwest::Url::parse(&self.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| self.url.clone())
+    }
+}
+
+impl TranslationProvider for PtruiApi {
+    fn translate_batch(
+        &self,
+        texts: &[&str],
+        source_lang: &str,
+        target_lang: &str,
+        options: &TranslateOptions,
+    ) -> Result<Vec<String>, String> {
+        translate_batch_via_api(self, texts, source_lang, target_lang, options)
+    }
+
+    fn name(&self) -> String {
+        self.provider_name()
+    }
+
+    fn http_client(&self) -> Option<reqwest::blocking::Client> {
+        Some(self.client.clone())
+    }
+
+    fn supported_languages(&self) -> Result<Vec<FetchedLanguage>, String> {
+        let response = self
+            .get_sibling("languages")?
+            .query(&[("type", "target")])
+            .send()
+            .map_err(|err| format!("Failed to fetch languages: {}", err))?;
+        if !response.status().is_success() {
+            return Err(format!("Languages endpoint error ({})", response.status()));
+        }
+        response
+            .json()
+            .map_err(|err| format!("Invalid languages response: {}", err))
+    }
+
+    fn quota(&self) -> Result<Option<ApiQuota>, String> {
+        let response = self
+            .get_sibling("usage")?
+            .send()
+            .map_err(|err| format!("Failed to fetch usage: {}", err))?;
+        // LibreTranslate and most self-hosted servers don't meter characters.
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("Usage endpoint error ({})", response.status()));
+        }
+        response
+            .json()
+            .map(Some)
+            .map_err(|err| format!("Invalid usage response: {}", err))
+    }
+}
+
+impl PtruiApi {
+    /// A GET to an endpoint next to the translate one, authenticated the same way.
+    fn get_sibling(&self, endpoint: &str) -> Result<reqwest::blocking::RequestBuilder, String> {
+        let mut request = self.client.get(sibling_url(&self.url, endpoint)?);
+        if let (Some(header), Some(value)) = (&self.auth_header, &self.auth_value) {
+            request = request.header(header, value);
+        }
+        Ok(request)
+    }
+}
+
+/// An endpoint next to a translate endpoint: DeepL's `/v2/translate` pairs with
+/// `/v2/languages` and `/v2/usage`, LibreTranslate's `/translate` with `/languages`.
+fn sibling_url(url: &str, endpoint: &str) -> Result<reqwest::Url, String> {
+    let mut url = reqwest::Url::parse(url).map_err(|err| format!("Invalid API URL: {}", err))?;
+    url.set_query(None);
+    let path = url.path().trim_end_matches('/');
+    let base = path.strip_suffix("/translate").unwrap_or(path).to_string();
+    url.set_path(&format!("{}/{}", base, endpoint));
+    Ok(url)
+}
+
+/// Translates several independent texts in one request, preserving their order.
+pub fn translate_batch_via_api(
+    api: &PtruiApi,
+    texts: &[&str],
+    source_lang: &str,
+    target_lang: &str,
+    options: &TranslateOptions,
+) -> Result<Vec<String>, String> {
+    audited(api, texts, source_lang, target_lang, || {
+        post_translations(api, texts, source_lang, target_lang, options)
+    })
+}
+
+// Used when the config doesn't set a prompt. `{source}` and `{target}` become language
+// names; `{instructions}` becomes the formality, tone and context, if any.
+pub const DEFAULT_LLM_PROMPT: &str = "You are a translation engine. Translate the user's \
+     message from {source} to {target}.{instructions} Reply with the translation only, \
+     keeping the original formatting and line breaks.";
+
+/// A chat-completions endpoint (OpenAI, Anthropic's OpenAI-compatible API, or a local
+/// Ollama server) used as a translator.
+pub struct LlmApi {
+    // Endpoint, key, timeouts and audit log; the key is sent as a bearer token.
+    pub http: PtruiApi,
+    pub model: String,
+    pub prompt: String,
+    pub temperature: Option<f64>,
+}
+
+impl LlmApi {
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let timeouts = Timeouts::from_config(&config.llm.timeouts, LLM_TIMEOUTS);
+        let mut http = PtruiApi::with_timeouts(config, timeouts)?;
+        if let (Some(header), Some(key)) = (&http.auth_header, &config.api.key)
+            && header.eq_ignore_ascii_case("Authorization")
+        {
+            http.auth_value = Some(format!("Bearer {}", key));
+        }
+        Ok(Self {
+            http,
+            model: config.llm.model.clone().unwrap_or_default(),
+            prompt: config
+                .llm
+                .prompt
+                .clone()
+                .unwrap_or_else(|| DEFAULT_LLM_PROMPT.to_string()),
+            temperature: config.llm.temperature,
         })
     }
+
+    /// The system prompt for one language pair.
+    fn system_prompt(
+        &self,
+        source_lang: &str,
+        target_lang: &str,
+        options: &TranslateOptions,
+    ) -> String {
+        let mut instructions = String::new();
+        match options.formality {
+            Some("more") => instructions.push_str(" Use a formal register."),
+            Some("less") => instructions.push_str(" Use an informal register."),
+            _ => {}
+        }
+        if let Some(tone) = options.tone {
+            instructions.push(' ');
+            instructions.push_str(tone);
+        }
+        if let Some(context) = options.context {
+            instructions.push_str(&format!(" Context (do not translate it): {}", context));
+        }
+        self.prompt
+            .replace("{source}", language_name(source_lang))
+            .replace("{target}", language_name(target_lang))
+            .replace("{instructions}", &instructions)
+    }
 }
 
-pub fn translate_via_api(
+impl TranslationProvider for LlmApi {
+    fn translate_batch(
+        &self,
+        texts: &[&str],
+        source_lang: &str,
+        target_lang: &str,
+        options: &TranslateOptions,
+    ) -> Result<Vec<String>, String> {
+        let system = self.system_prompt(source_lang, target_lang, options);
+        audited(&self.http, texts, source_lang, target_lang, || {
+            let deadline = options.deadline.unwrap_or(self.http.timeouts.deadline);
+            // Chat models answer one message at a time, so a batch is one call per text.
+            let mut status = None;
+            let mut translations = Vec::with_capacity(texts.len());
+            for text in texts {
+                let request = ChatRequest {
+                    model: &self.model,
+                    messages: [
+                        ChatMessage {
+                            role: "system",
+                            content: &system,
+                        },
+                        ChatMessage {
+                            role: "user",
+                            content: text,
+                        },
+                    ],
+                    temperature: self.temperature,
+                    stream: false,
+                };
+                let (code, result) = post_json::<ChatResponse>(&self.http, &request, deadline);
+                status = code;
+                let reply = result.and_then(|response| {
+                    response
+                        .choices
+                        .into_iter()
+                        .next()
+                        .map(|choice| choice.message.content.trim().to_string())
+                        .ok_or_else(|| "API response missing translations".to_string())
+                });
+                match reply {
+                    Ok(reply) => translations.push(reply),
+                    Err(message) => return (status, Err(message)),
+                }
+            }
+            (status, Ok(translations))
+        })
+    }
+
+    fn name(&self) -> String {
+        format!("{}/{}", self.http.provider_name(), self.model)
+    }
+
+    fn http_client(&self) -> Option<reqwest::blocking::Client> {
+        Some(self.http.client.clone())
+    }
+}
+
+/// Runs one provider call and records it in the audit log, when that's enabled.
+fn audited(
     api: &PtruiApi,
-    text: &str,
+    texts: &[&str],
     source_lang: &str,
     target_lang: &str,
-) -> Result<String, String> {
+    call: impl FnOnce() -> (Option<u16>, Result<Vec<String>, String>),
+) -> Result<Vec<String>, String> {
+    let started = Instant::now();
+    let (status, result) = call();
+    if let Some(audit) = &api.audit {
+        // An unaccounted call is reported rather than silently shown; API errors still win.
+        let logged = audit.record(
+            &api.url,
+            source_lang,
+            target_lang,
+            texts,
+            status,
+            started.elapsed(),
+        );
+        if let (Ok(_), Err(message)) = (&result, logged) {
+            return Err(message);
+        }
+    }
+    result
+}
+
+/// A serialized request body, gzipped when that was worth it.
+struct EncodedBody {
+    bytes: Vec<u8>,
+    gzipped: bool,
+}
+
+/// Serializes a request as JSON, gzipping large documents when the API accepts compressed bodies.
+fn encode_body(payload: &impl Serialize, gzip: bool) -> Result<EncodedBody, String> {
+    let json = serde_json::to_vec(payload)
+        .map_err(|err| format!("Failed to encode the request: {}", err))?;
+    if !gzip || json.len() < GZIP_MIN_BYTES {
+        return Ok(EncodedBody {
+            bytes: json,
+            gzipped: false,
+        });
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder
+        .write_all(&json)
+        .and_then(|_| encoder.finish())
+        .map(|bytes| EncodedBody {
+            bytes,
+            gzipped: true,
+        })
+        .map_err(|err| format!("Failed to compress the request: {}", err))
+}
+
+/// Sends one translation request, returning the HTTP status alongside the outcome.
+fn post_translations(
+    api: &PtruiApi,
+    texts: &[&str],
+    source_lang: &str,
+    target_lang: &str,
+    options: &TranslateOptions,
+) -> (Option<u16>, Result<Vec<String>, String>) {
     let payload = TranslateRequest {
-        text: vec![text],
+        text: texts.to_vec(),
         source_lang,
         target_lang,
+        context: options.context,
+        formality: options.formality,
     };
-    let mut request = api.client.post(&api.url).json(&payload);
+    let deadline = options.deadline.unwrap_or(api.timeouts.deadline);
+    let (status, result) = post_json::<TranslateResponse>(api, &payload, deadline);
+    let result = result.and_then(|response| {
+        if response.translations.len() != texts.len() {
+            return Err("API response missing translations".to_string());
+        }
+        Ok(response
+            .translations
+            .into_iter()
+            .map(|item| item.text)
+            .collect())
+    });
+    (status, result)
+}
+
+/// Posts a JSON body to the API and decodes the JSON answer, returning the HTTP status
+/// alongside the outcome. The call fails when the server goes quiet for the read timeout or
+/// the whole exchange outlasts `deadline`.
+fn post_json<T: DeserializeOwned>(
+    api: &PtruiApi,
+    payload: &impl Serialize,
+    deadline: Duration,
+) -> (Option<u16>, Result<T, String>) {
+    let started = Instant::now();
+    let body = match encode_body(payload, api.gzip_requests) {
+        Ok(body) => body,
+        Err(message) => return (None, Err(message)),
+    };
+    let mut request = api
+        .client
+        .post(&api.url)
+        .header(CONTENT_TYPE, "application/json")
+        .timeout(api.timeouts.read.min(deadline));
+    if body.gzipped {
+        request = request.header(CONTENT_ENCODING, "gzip");
+    }
+    request = request.body(body.bytes);
     if let (Some(header), Some(value)) = (&api.auth_header, &api.auth_value) {
         request = request.header(header, value);
     }
-    let response = request
-        .send()
-        .map_err(|err| format!("Failed to call translation API: {}", err))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
+    let response = match request.send() {
+        Ok(response) => response,
+        Err(err) if err.is_connect() => {
+            return (None, Err(format!("{}: {}", UNREACHABLE_ERROR, err)));
+        }
+        Err(err) if err.is_timeout() => {
+            return (None, Err(timeout_error(api, started, deadline)));
+        }
+        Err(err) => {
+            return (
+                None,
+                Err(format!("Failed to call translation API: {}", err)),
+            );
+        }
+    };
+    let status = response.status();
+    if !status.is_success() {
         let body = response.text().unwrap_or_default();
-        return Err(format!("Translation API error ({}): {}", status, body));
-    }
-
-    let response: TranslateResponse = response
-        .json()
-        .map_err(|err| format!("Invalid API response: {}", err))?;
-    response
-        .translations
-        .into_iter()
-        .next()
-        .map(|item| item.text)
-        .ok_or_else(|| "API response missing translations".to_string())
+        return (
+            Some(status.as_u16()),
+            Err(format!("Translation API error ({}): {}", status, body)),
+        );
+    }
+
+    let result = read_body(response, started, deadline)
+        .map_err(|err| {
+            if is_timeout(&err) {
+                timeout_error(api, started, deadline)
+            } else {
+                format!("Failed to read the API response: {}", err)
+            }
+        })
+        .and_then(|body| {
+            serde_json::from_slice(&body).map_err(|err| format!("Invalid API response: {}", err))
+        });
+    (Some(status.as_u16()), result)
+}
+
+// Size of each read of a response body; every read gets the full read timeout.
+const READ_CHUNK_BYTES: usize = 16 * 1024;
+
+/// Reads a response body read by read, so a server that keeps answering is only stopped by
+/// the deadline, checked between reads.
+fn read_body(
+    mut response: reqwest::blocking::Response,
+    started: Instant,
+    deadline: Duration,
+) -> io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut chunk = vec![0; READ_CHUNK_BYTES];
+    loop {
+        let read = response.read(&mut chunk)?;
+        if read == 0 {
+            return Ok(body);
+        }
+        if started.elapsed() > deadline {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+}
+
+/// Whether a body read ran into the read timeout or the deadline.
+fn is_timeout(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::TimedOut
+        || err
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<reqwest::Error>())
+            .is_some_and(reqwest::Error::is_timeout)
+}
+
+/// Names whichever limit a timed-out call ran into.
+fn timeout_error(api: &PtruiApi, started: Instant, deadline: Duration) -> String {
+    if started.elapsed() >= deadline {
+        format!(
+            "Translation API took longer than the {}s deadline",
+            deadline.as_secs_f64()
+        )
+    } else {
+        format!(
+            "Translation API sent nothing for {}s",
+            api.timeouts.read.as_secs_f64()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tone::Tone;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn gzips_only_large_bodies() {
+        let small = encode_body(&["hola"], true).unwrap();
+        assert!(!small.gzipped);
+        let document = vec!["una frase bastante larga"; 500];
+        let large = encode_body(&document, true).unwrap();
+        assert!(large.gzipped);
+        let mut json = String::new();
+        GzDecoder::new(large.bytes.as_slice())
+            .read_to_string(&mut json)
+            .unwrap();
+        assert_eq!(json, serde_json::to_string(&document).unwrap());
+        assert!(!encode_body(&document, false).unwrap().gzipped);
+    }
+
+    #[test]
+    fn finds_the_languages_endpoint() {
+        let url = |api: &str| sibling_url(api, "languages").unwrap().to_string();
+        assert_eq!(
+            url("https://api.deepl.com/v2/translate"),
+            "https://api.deepl.com/v2/languages"
+        );
+        assert_eq!(
+            url("http://localhost:5000/translate/"),
+            "http://localhost:5000/languages"
+        );
+        let fetched: Vec<FetchedLanguage> = serde_json::from_str(
+            r#"[{ "language": "BG", "name": "Bulgarian", "supports_formality": false },
+                { "code": "ga", "name": "Irish", "targets": ["en"] }]"#,
+        )
+        .unwrap();
+        assert_eq!(fetched[0].code, "BG");
+        assert_eq!(fetched[1].name, "Irish");
+    }
+
+    #[test]
+    fn fills_in_the_prompt_template() {
+        let llm = LlmApi {
+            http: PtruiApi::new(
+                "http://localhost:11434/v1/chat/completions".to_string(),
+                None,
+                None,
+                TRANSLATION_API_TIMEOUTS,
+            )
+            .unwrap(),
+            model: "llama3".to_string(),
+            prompt: "{source} -> {target}.{instructions}".to_string(),
+            temperature: None,
+        };
+        let options = TranslateOptions {
+            formality: Some("more"),
+            ..TranslateOptions::default()
+        };
+        assert_eq!(
+            llm.system_prompt("ES", "DE", &options),
+            "Spanish -> German. Use a formal register."
+        );
+        let options = TranslateOptions {
+            tone: Tone::Eli5.instruction(),
+            ..TranslateOptions::default()
+        };
+        assert_eq!(
+            llm.system_prompt("ES", "DE", &options),
+            "Spanish -> German. Use very simple words, as if explaining to a five-year-old."
+        );
+        assert_eq!(llm.name(), "localhost/llama3");
+    }
 }
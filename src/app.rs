@@ -1,18 +1,95 @@
+use std::collections::BTreeMap;
+use std::env;
 use std::io::{self, Stdout};
+use std::path::Path;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
-use tui_textarea::{Input, TextArea};
+use tui_textarea::{CursorMove, Input, TextArea};
 
-use crate::api::{PtruiApi, translate_via_api};
-use crate::languages::{LANGUAGES, filtered_language_indices, find_language_index};
-use crate::textarea::{set_textarea_text, textarea_input_from_key, textarea_text};
-use crate::ui::draw_ui;
+use crate::alternatives::{Alternative, AlternativePicker, variations};
+use crate::api::{TranslateOptions, is_unreachable};
+use crate::browser::{WebTranslator, open_url};
+use crate::cache::{CACHE_PROVIDER, CacheKey, TranslationCache, translate_cached};
+use crate::cli::TuiOptions;
+use crate::clipboard::Clipboard;
+use crate::command::{ExCommand, parse_ex};
+use crate::config::Config;
+use crate::dates::{day_number, unix_now};
+use crate::debounce::{Debouncer, ends_sentence};
+use crate::dictionary::{DictionaryPanel, lookup_wiktionary};
+use crate::diff::{DiffView, word_diff};
+use crate::draft::Draft;
+use crate::editor::edit_in_editor;
+use crate::export::{ExportPair, write_export};
+use crate::formality::{Formality, supports_formality};
+use crate::frequency::FrequencyList;
+use crate::gloss::{GlossEntry, build_gloss};
+use crate::history::{HistoryEntry, HistoryFinder, HistoryStore, parse_tags};
+use crate::keylog::{KeyRecorder, KeyReplay};
+use crate::keys::normalize_key;
+use crate::languages::{
+    filtered_language_indices, find_language_index, languages, refresh_languages,
+};
+use crate::layout::PaneLayout;
+use crate::lock::PrivacyLock;
+use crate::mock::{MOCK_NAME, MOCK_URL};
+use crate::notify::Notifier;
+use crate::ocr::{OcrSettings, recognize_text};
+use crate::phrasebook::{PhraseEntry, append_phrase, load_phrases};
+use crate::presentation::Presentation;
+use crate::profanity::ProfanityFilter;
+use crate::provider::{self, TranslationProvider};
+use crate::qr::render_qr;
+use crate::quality::{QualityMarks, estimate_quality};
+use crate::quickkeys::{FunctionKeys, QuickAction};
+use crate::quota::{ApiQuota, QuotaWatcher};
+use crate::review::Review;
+use crate::romanize::{ReadingAid, romanize};
+use crate::session::{Session, SessionPicker};
+use crate::speech::{SpeechSettings, speak};
+use crate::stats::PracticeStats;
+use crate::textarea::{
+    replace_textarea_text, selected_text, set_textarea_text, textarea_input_from_key,
+    textarea_text, word_at_cursor,
+};
+use crate::theme::{THEMES, Theme, ThemePicker};
+use crate::thesaurus::{SuggestionPicker, suggest_synonyms};
+use crate::tmux;
+use crate::tone::Tone;
+use crate::transcribe::{Recording, TranscribeSettings, discard_recording, transcribe};
+use crate::translator::{
+    LookupDone, LookupJob, Lookups, TranslationDone, TranslationJob, Translator,
+};
+use crate::ui::{draw_ui, style_panes};
+use crate::usage::UsageSummary;
 use crate::vim::{Mode, Transition, Vim};
+use crate::webhook::{TranslationEvent, Webhook};
+use crate::webpage::fetch_readable_text;
+use crate::wordofday::{WordOfTheDay, pick_word_of_the_day, word_of_the_day_enabled};
 
-const TRANSLATION_DEBOUNCE: Duration = Duration::from_millis(350);
+// How many recent history entries the finder searches.
+const HISTORY_FINDER_LIMIT: usize = 1000;
+// How many recent history entries presentation mode steps through when none are pinned.
+const PRESENTATION_LIMIT: usize = 100;
+const DRAFT_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+// How often a queued translation retries while the API is unreachable.
+const OFFLINE_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+// How often the spinner turns while a translation is in flight.
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+// How long a toast stays in the status area.
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+// Target lines converted for the reading-aid panel, which shows a few rows at most.
+const READING_AID_LINES: usize = 8;
+// How long the text stays unchanged before its translation counts as settled and is logged.
+const SETTLE_DELAY: Duration = Duration::from_secs(3);
+// Characters of source text before live translation pauses.
+const DEFAULT_SOFT_LIMIT: usize = 100_000;
+// Extra target languages stacked under the target pane; more would leave each a line or two.
+const MAX_EXTRA_TARGETS: usize = 4;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActiveSide {
@@ -20,11 +97,40 @@ pub enum ActiveSide {
     Right,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl ActiveSide {
+    pub fn other(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AppAction {
     None,
     Quit,
     NativeizeBoth,
+    Gloss,
+    Speak,
+    Dictionary,
+    Synonyms,
+    Alternatives,
+    Quality,
+    // Download a web page into the left pane.
+    Fetch(String),
+    // The pane layout changed and should be saved to the config file.
+    SaveLayout,
+    // A theme was picked and should be saved to the config file.
+    SaveTheme,
+    // Suspend the TUI and edit the active pane in `$EDITOR`.
+    ExternalEdit,
+    // Translate the target pane back into the source language and diff it with the original.
+    BackTranslate,
+    // Switch to the next available provider.
+    CycleProvider,
+    // A session saved with another provider was opened; switch to it if it's available.
+    SessionProvider { session: String, provider: String },
 }
 
 pub struct App {
@@ -40,36 +146,321 @@ pub struct App {
     pub right_language: usize,
     pub pending_translation: bool,
     last_edit: Option<Instant>,
+    // Set while the API is unreachable; pending translations wait for the connection.
+    pub offline_since: Option<Instant>,
+    // Set while the worker is translating, to animate the spinner.
+    pub translating_since: Option<Instant>,
+    // Round trip of the last translation request, shown in the status bar.
+    pub last_latency: Option<Duration>,
+    // Chunk being translated and the chunk count, while a long text goes out in pieces.
+    pub chunk_progress: Option<(usize, usize)>,
+    pub quota: Option<ApiQuota>,
+    // When a live translation was last sent, to space out retries while offline.
+    last_attempt: Instant,
+    // Bumped whenever the text or settings to translate change; responses carry the version
+    // they were requested at.
+    source_version: u64,
+    // How long after `last_edit` the pending translation runs.
+    translation_delay: Duration,
+    debouncer: Debouncer,
     pub error: Option<String>,
+    // Short informational message shown in the status line until the next edit.
+    pub notice: Option<String>,
     pub picker: Option<LanguagePicker>,
+    // Optional "fit into N characters" limit for the translated side.
+    pub length_limit: Option<usize>,
+    // Source size above which live translation pauses until `:translate`.
+    pub soft_limit: Option<usize>,
+    // Register preset applied to outgoing translations.
+    pub tone: Tone,
+    // Formality of translations written into each pane.
+    pub left_formality: Formality,
+    pub right_formality: Formality,
+    pub theme: Theme,
+    // The config's `[colors]`, applied on top of whichever theme is picked.
+    colors: BTreeMap<String, String>,
+    pub theme_picker: Option<ThemePicker>,
+    pub layout: PaneLayout,
+    // Quick actions bound to F1–F12.
+    pub function_keys: FunctionKeys,
+    // Startup panel with a word to study; any key dismisses it.
+    pub word_of_the_day: Option<WordOfTheDay>,
+    // Idle lock that hides the panes, when configured.
+    pub lock: Option<PrivacyLock>,
+    // Word and character counts of each pane for the status bar, kept current by
+    // `refresh_counts` whenever the text changes.
+    pub left_counts: TextCounts,
+    pub right_counts: TextCounts,
+    // Last machine translation written into each pane, to tell hand edits apart from it.
+    left_machine_text: Option<String>,
+    right_machine_text: Option<String>,
+    // Finished translation held back because it would replace hand edits; Alt+y applies it.
+    pub held_translation: Option<HeldTranslation>,
+    // Frozen panes hold back every translation into them, edited or not.
+    left_frozen: bool,
+    right_frozen: bool,
+    // Interlinear word-by-word gloss of the active pane, shown as a learning view.
+    pub gloss: Option<Vec<GlossEntry>>,
+    // Romanization or ruby readings shown under non-Latin translations.
+    pub reading_aid: ReadingAid,
+    // Dictionary panel for the word under the cursor or the visual selection.
+    pub dictionary: Option<DictionaryPanel>,
+    // Alternative phrasings for the visual selection, waiting to replace it.
+    pub suggestions: Option<SuggestionPicker>,
+    // Candidate translations of the active pane, waiting to replace the target.
+    pub alternatives: Option<AlternativePicker>,
+    // Frequency list for the target language while word-difficulty highlighting is on.
+    pub frequency: Option<FrequencyHighlight>,
+    // Daily practice counters persisted in the data directory.
+    pub stats: PracticeStats,
+    // Counters changed since stats.json was last written; saved with the draft autosave.
+    stats_changed: bool,
+    pub show_stats: bool,
+    // `:review` flashcards from the phrasebook.
+    pub review: Option<Review>,
+    // Round-trip quality estimates, marked in the target pane's gutter.
+    pub quality: Option<QualityMarks>,
+    // Wordlist applied to incoming translations while profanity masking is on.
+    pub profanity: Option<ProfanityFilter>,
+    // Persistent log of completed translations; `None` when history is disabled.
+    pub history: Option<HistoryStore>,
+    // Latest live translation, logged to history and the webhook once the text stops changing.
+    unsettled: Option<UnsettledTranslation>,
+    // System clipboard shared with vim's yank and paste; None keeps yanks inside ptrui.
+    pub clipboard: Option<Clipboard>,
+    // Vim's named registers (`"ay`, `"ap`), shared by both panes.
+    pub registers: BTreeMap<char, String>,
+    // `:registers` popup and its scroll offset.
+    pub registers_view: Option<u16>,
+    // Short confirmation in the status area and when it disappears.
+    pub toast: Option<(String, Instant)>,
+    pub history_finder: Option<HistoryFinder>,
+    // Draft left behind by the previous session, waiting for a restore/discard answer.
+    pub draft_prompt: Option<Draft>,
+    // Last draft written to disk, so autosave skips unchanged panes.
+    saved_draft: Option<Draft>,
+    // Path being typed for exporting the current pair; the extension picks the format.
+    pub export_prompt: Option<String>,
+    // Whether exports pair sentences instead of lines; toggled with Tab in the prompt.
+    pub export_sentences: bool,
+    // Vim-style `:` command being typed in normal mode.
+    pub command_line: Option<String>,
+    // Usage dashboard aggregated from history.
+    pub usage: Option<UsageSummary>,
+    // Recent translations, so retyping or switching back doesn't call the API again.
+    pub cache: TranslationCache,
+    // Skip the cache for the next translation (Ctrl+t), replacing what it holds.
+    bypass_cache: bool,
+    // Developer overlay with cache counters and request state.
+    pub show_debug: bool,
+    // Label of the translation provider in use, saved with sessions.
+    pub provider: String,
+    pub session_picker: Option<SessionPicker>,
+    // Compact layout for `tmux display-popup`; pushing to the tmux buffer also closes it.
+    pub popup: bool,
+    // Receives a JSON payload for each completed translation, when configured.
+    pub webhook: Option<Webhook>,
+    // Read-only full-screen view for teaching; replaces the whole layout while open.
+    pub presentation: Option<Presentation>,
+    // Microphone recording in progress for speech input.
+    pub recording: Option<Recording>,
+    // Translation rendered as QR code rows, shown in a popup for scanning with a phone.
+    pub qr_code: Option<Vec<String>>,
+    // Full error message popup, while it is open.
+    pub error_details: Option<ErrorDetails>,
+    // Sentence-aligned view replacing the panes, with its scroll offset.
+    pub aligned: Option<u16>,
+    // Hand edits compared word by word with the machine translation, replacing the panes.
+    pub diff_view: Option<DiffView>,
+    // Source version a back-translation on its way was asked for at.
+    pub back_translating: Option<u64>,
+    // More languages the left pane is translated into, stacked under the target pane.
+    pub extra_targets: Vec<ExtraTarget>,
 }
 
 impl App {
-    pub fn new() -> Self {
-        let left_language = find_language_index("EN").unwrap_or(0);
-        let right_language = find_language_index("ES").unwrap_or(1);
+    /// A fresh session with the languages, debounce, theme and keys from `config`, which
+    /// `Config::load` has already validated.
+    pub fn new(config: &Config) -> Self {
+        let language = |code: &Option<String>, default: &str, fallback: usize| {
+            find_language_index(code.as_deref().unwrap_or(default)).unwrap_or(fallback)
+        };
+        let left_language = language(&config.source_lang, "EN", 0);
+        let right_language = language(&config.target_lang, "ES", 1);
+        let debouncer = Debouncer::new(config.debounce());
+        let pane = || {
+            let mut textarea = TextArea::default();
+            textarea.set_max_histories(config.undo_levels());
+            textarea
+        };
         Self {
             active: ActiveSide::Left,
-            input: TextArea::default(),
-            output: TextArea::default(),
+            input: pane(),
+            output: pane(),
             left_vim: Vim::new(Mode::Normal),
             right_vim: Vim::new(Mode::Normal),
             left_language,
             right_language,
             pending_translation: false,
             last_edit: None,
+            source_version: 0,
+            offline_since: None,
+            translating_since: None,
+            last_latency: None,
+            chunk_progress: None,
+            quota: None,
+            last_attempt: Instant::now(),
+            translation_delay: debouncer.pause_delay(),
+            debouncer,
             error: None,
+            notice: None,
             picker: None,
+            length_limit: None,
+            soft_limit: Some(DEFAULT_SOFT_LIMIT),
+            tone: Tone::default(),
+            left_formality: Formality::default(),
+            right_formality: Formality::default(),
+            theme: config
+                .theme
+                .as_deref()
+                .and_then(Theme::find)
+                .unwrap_or_default()
+                .with_colors(&config.colors)
+                .unwrap_or_default(),
+            colors: config.colors.clone(),
+            theme_picker: None,
+            layout: PaneLayout::new(config.layout.as_deref(), config.split).unwrap_or_default(),
+            function_keys: FunctionKeys::from_bindings(&config.keys).unwrap_or_default(),
+            word_of_the_day: None,
+            lock: None,
+            left_machine_text: None,
+            left_counts: TextCounts::default(),
+            right_counts: TextCounts::default(),
+            right_machine_text: None,
+            held_translation: None,
+            left_frozen: false,
+            right_frozen: false,
+            gloss: None,
+            reading_aid: ReadingAid::default(),
+            dictionary: None,
+            suggestions: None,
+            alternatives: None,
+            frequency: None,
+            stats: PracticeStats::default(),
+            stats_changed: false,
+            show_stats: false,
+            review: None,
+            quality: None,
+            profanity: None,
+            history: None,
+            unsettled: None,
+            clipboard: None,
+            registers: BTreeMap::new(),
+            registers_view: None,
+            toast: None,
+            history_finder: None,
+            draft_prompt: None,
+            saved_draft: None,
+            export_prompt: None,
+            export_sentences: false,
+            command_line: None,
+            usage: None,
+            cache: TranslationCache::new(0),
+            bypass_cache: false,
+            show_debug: false,
+            provider: String::new(),
+            session_picker: None,
+            popup: false,
+            webhook: None,
+            presentation: None,
+            recording: None,
+            qr_code: None,
+            error_details: None,
+            aligned: None,
+            diff_view: None,
+            back_translating: None,
+            extra_targets: Vec::new(),
         }
     }
 
     pub fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
-        if key.kind != KeyEventKind::Press {
+        let Some(key) = normalize_key(key) else {
             return AppAction::None;
+        };
+        if let Some(lock) = self.lock.as_mut() {
+            if lock.is_locked() {
+                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    return AppAction::Quit;
+                }
+                lock.handle_key(key, Instant::now());
+                return AppAction::None;
+            }
+            lock.touch(Instant::now());
+        }
+        if self.presentation.is_some() {
+            return self.handle_presentation_key(key);
         }
         if self.picker.is_some() {
             return self.handle_picker_key(key);
         }
+        if self.draft_prompt.is_some() {
+            return self.handle_draft_prompt_key(key);
+        }
+        if self.word_of_the_day.is_some() {
+            self.word_of_the_day = None;
+            return AppAction::None;
+        }
+        if self.session_picker.is_some() {
+            return self.handle_session_picker_key(key);
+        }
+        if self.theme_picker.is_some() {
+            return self.handle_theme_picker_key(key);
+        }
+        if self.export_prompt.is_some() {
+            return self.handle_export_key(key);
+        }
+        if self.command_line.is_some() {
+            return self.handle_command_line_key(key);
+        }
+        if self.history_finder.is_some() {
+            return self.handle_history_key(key);
+        }
+        if self.gloss.is_some() {
+            return self.handle_gloss_key(key);
+        }
+        if self.dictionary.is_some() {
+            return self.handle_dictionary_key(key);
+        }
+        if self.suggestions.is_some() {
+            return self.handle_suggestion_key(key);
+        }
+        if self.alternatives.is_some() {
+            return self.handle_alternatives_key(key);
+        }
+        if self.show_stats {
+            return self.handle_stats_key(key);
+        }
+        if self.review.is_some() {
+            return self.handle_review_key(key);
+        }
+        if self.usage.is_some() {
+            return self.handle_usage_key(key);
+        }
+        if self.qr_code.is_some() {
+            return self.handle_qr_key(key);
+        }
+        if self.aligned.is_some() {
+            return self.handle_aligned_key(key);
+        }
+        if self.diff_view.is_some() {
+            return self.handle_diff_key(key);
+        }
+        if self.registers_view.is_some() {
+            return self.handle_registers_key(key);
+        }
+        if self.error_details.is_some() {
+            return self.handle_error_details_key(key);
+        }
         match key.code {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => AppAction::Quit,
             KeyCode::Char('h') if key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -83,50 +474,284 @@ impl App {
             KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 AppAction::NativeizeBoth
             }
+            KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.show_debug = !self.show_debug;
+                AppAction::None
+            }
             KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                match self.active {
-                    ActiveSide::Left => self.input = TextArea::default(),
-                    ActiveSide::Right => self.output = TextArea::default(),
-                }
+                set_textarea_text(self.active_textarea_mut(), "");
+                *self.machine_text_mut(self.active) = None;
+                schedule_translation(self);
+                AppAction::None
+            }
+            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.cycle_tone();
+                AppAction::None
+            }
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.cycle_formality();
+                AppAction::None
+            }
+            KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::ALT) => AppAction::Gloss,
+            KeyCode::Char('h') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.open_history_finder();
+                AppAction::None
+            }
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.execute_command("w")
+            }
+            KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.copy_translation()
+            }
+            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                AppAction::ExternalEdit
+            }
+            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.bypass_cache = true;
+                self.execute_command("translate")
+            }
+            // Takes over from vim's one-line scroll up; Ctrl+u and Ctrl+b still scroll.
+            KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.open_history_finder();
+                AppAction::None
+            }
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::ALT) => AppAction::Speak,
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::ALT) => {
+                AppAction::Dictionary
+            }
+            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::ALT) => AppAction::Synonyms,
+            KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::ALT) => {
+                AppAction::Alternatives
+            }
+            KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.send_to_deck();
+                AppAction::None
+            }
+            KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.export_prompt = Some(String::new());
+                AppAction::None
+            }
+            KeyCode::Char('q') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.toggle_quality()
+            }
+            KeyCode::Char('B') if key.modifiers.contains(KeyModifiers::ALT) => {
+                AppAction::BackTranslate
+            }
+            KeyCode::Char('m') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.profanity = match self.profanity {
+                    Some(_) => None,
+                    None => Some(ProfanityFilter::load()),
+                };
                 schedule_translation(self);
                 AppAction::None
             }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.show_stats = true;
+                AppAction::None
+            }
+            KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.layout.rotate();
+                AppAction::SaveLayout
+            }
+            KeyCode::Char('=') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.layout.grow();
+                AppAction::SaveLayout
+            }
+            KeyCode::Char('-') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.layout.shrink();
+                AppAction::SaveLayout
+            }
+            KeyCode::Char('Y') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.copy_translation()
+            }
+            KeyCode::Char('U') if key.modifiers.contains(KeyModifiers::ALT) => {
+                match self.history.as_ref().map(HistoryStore::usage) {
+                    Some(Ok(usage)) => self.usage = Some(usage),
+                    Some(Err(message)) => self.error = Some(message),
+                    None => self.error = Some("History is disabled".to_string()),
+                }
+                AppAction::None
+            }
+            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::ALT) => {
+                if self.frequency.is_some() {
+                    self.frequency = None;
+                } else {
+                    self.refresh_frequency(true);
+                }
+                AppAction::None
+            }
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.reading_aid = self.reading_aid.next();
+                AppAction::None
+            }
+            KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::ALT) => self.push_to_tmux(),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.open_qr_code();
+                AppAction::None
+            }
+            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::ALT) => {
+                if let Some(message) = &self.error {
+                    self.error_details = Some(ErrorDetails {
+                        message: message.clone(),
+                        scroll: 0,
+                    });
+                }
+                AppAction::None
+            }
+            KeyCode::Char('i') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.aligned = Some(0);
+                AppAction::None
+            }
+            KeyCode::Char('D') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.open_diff_view();
+                AppAction::None
+            }
+            KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.open_in_web_translator();
+                AppAction::None
+            }
+            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.toggle_recording();
+                AppAction::None
+            }
+            KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.swap_text();
+                AppAction::None
+            }
+            KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.accept_held_translation();
+                AppAction::None
+            }
+            KeyCode::Char('z') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.toggle_frozen();
+                AppAction::None
+            }
+            KeyCode::F(number) if self.function_keys.action(number).is_some() => self
+                .function_keys
+                .action(number)
+                .map_or(AppAction::None, |action| self.run_quick_action(action)),
             KeyCode::Tab => {
                 // Switch which side gets input.
-                self.active = match self.active {
-                    ActiveSide::Left => ActiveSide::Right,
-                    ActiveSide::Right => ActiveSide::Left,
-                };
+                self.active = self.active.other();
+                self.refresh_frequency(false);
                 AppAction::None
             }
             KeyCode::Backspace if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 self.open_picker(ActiveSide::Left);
                 AppAction::None
             }
+            KeyCode::Char(':') if self.accepts_command_line() => {
+                self.command_line = Some(String::new());
+                AppAction::None
+            }
             _ => {
                 let input = textarea_input_from_key(key);
+                // Named registers bypass the system clipboard.
+                let register = self.active_vim().register;
+                let (unnamed, yanked) = match register {
+                    Some(name) => (Some(self.load_register(name)), None),
+                    None => (None, self.sync_clipboard_register(&input)),
+                };
                 let modified = match self.active {
-                    ActiveSide::Left => {
-                        let before = textarea_text(&self.input);
-                        let transition = self.left_vim.transition(input, &mut self.input);
-                        self.update_vim_state(ActiveSide::Left, transition);
-                        before != textarea_text(&self.input)
-                    }
-                    ActiveSide::Right => {
-                        let before = textarea_text(&self.output);
-                        let transition = self.right_vim.transition(input, &mut self.output);
-                        self.update_vim_state(ActiveSide::Right, transition);
-                        before != textarea_text(&self.output)
-                    }
+                    ActiveSide::Left => self.left_vim.handle_input(input, &mut self.input),
+                    ActiveSide::Right => self.right_vim.handle_input(input, &mut self.output),
                 };
+                // A yank or cut also fills the unnamed register, as in Vim; a paste leaves it.
+                if let (Some(name), Some(unnamed)) = (register, unnamed)
+                    && !self.store_register(name)
+                {
+                    self.active_textarea_mut().set_yank_text(unnamed);
+                }
+                if let Some(before) = yanked {
+                    self.copy_new_yank(&before);
+                }
                 if modified {
-                    schedule_translation(self);
+                    let ends_sentence = match key.code {
+                        KeyCode::Enter => true,
+                        KeyCode::Char(c) => ends_sentence(c),
+                        _ => false,
+                    };
+                    self.source_edited(ends_sentence);
                 }
                 AppAction::None
             }
         }
     }
 
+    /// Handles a bracketed paste. In insert mode the text goes into the active pane as one
+    /// edit; prompts take its first line; anywhere else it is refused instead of being run as
+    /// a string of commands.
+    pub fn handle_paste(&mut self, text: &str) {
+        if self.lock.as_ref().is_some_and(PrivacyLock::is_locked) {
+            return;
+        }
+        if let Some(prompt) = self.command_line.as_mut().or(self.export_prompt.as_mut()) {
+            prompt.push_str(text.lines().next().unwrap_or_default());
+            return;
+        }
+        if self.overlay_open() {
+            return;
+        }
+        let result = match self.active {
+            ActiveSide::Left => self.left_vim.paste(text, &mut self.input),
+            ActiveSide::Right => self.right_vim.paste(text, &mut self.output),
+        };
+        match result {
+            Ok(true) => self.source_edited(true),
+            Ok(false) => {}
+            Err(hint) => self.error = Some(hint.to_string()),
+        }
+    }
+
+    /// Whether a popup or prompt has the keyboard instead of the panes.
+    fn overlay_open(&self) -> bool {
+        self.presentation.is_some()
+            || self.picker.is_some()
+            || self.draft_prompt.is_some()
+            || self.word_of_the_day.is_some()
+            || self.session_picker.is_some()
+            || self.theme_picker.is_some()
+            || self.history_finder.is_some()
+            || self.gloss.is_some()
+            || self.dictionary.is_some()
+            || self.suggestions.is_some()
+            || self.alternatives.is_some()
+            || self.show_stats
+            || self.review.is_some()
+            || self.usage.is_some()
+            || self.qr_code.is_some()
+            || self.error_details.is_some()
+            || self.aligned.is_some()
+            || self.diff_view.is_some()
+            || self.registers_view.is_some()
+    }
+
+    /// Schedules a translation after the active pane's text changed, unless it grew past the
+    /// soft limit.
+    fn source_edited(&mut self, ends_sentence: bool) {
+        if let Some(limit) = self.soft_limit_exceeded() {
+            // Translating a huge paste on every keystroke would stall the UI.
+            self.refresh_counts();
+            self.pending_translation = false;
+            self.notice = None;
+            self.error = Some(format!(
+                "Text is over {} characters; live translation paused (:translate runs it)",
+                limit
+            ));
+            return;
+        }
+        let delay = self.debouncer.delay_after(Instant::now(), ends_sentence);
+        schedule_translation(self);
+        self.translation_delay = delay;
+    }
+
+    /// Recounts both panes; called when their text changes rather than on every redraw,
+    /// which would walk a long document at each keypress.
+    pub fn refresh_counts(&mut self) {
+        self.left_counts = TextCounts::of(&self.input);
+        self.right_counts = TextCounts::of(&self.output);
+    }
+
     fn open_picker(&mut self, side: ActiveSide) {
         self.picker = Some(LanguagePicker {
             side,
@@ -146,21 +771,30 @@ impl App {
             KeyCode::Esc => {
                 self.picker = None;
             }
+            KeyCode::Tab if picker.side == ActiveSide::Right => {
+                let indices = filtered_language_indices(&picker.query);
+                if let Some(&language_index) = indices.get(picker.selected) {
+                    self.toggle_extra_target(language_index);
+                }
+            }
             KeyCode::Enter => {
                 let indices = filtered_language_indices(&picker.query);
                 if let Some(&language_index) = indices.get(picker.selected) {
                     match picker.side {
                         ActiveSide::Left => self.left_language = language_index,
-                        ActiveSide::Right => self.right_language = language_index,
+                        ActiveSide::Right => {
+                            self.right_language = language_index;
+                            self.extra_targets
+                                .retain(|target| target.language != language_index);
+                        }
                     }
                     schedule_translation(self);
+                    self.refresh_frequency(false);
                 }
                 self.picker = None;
             }
-            KeyCode::Up => {
-                if picker.selected > 0 {
-                    picker.selected -= 1;
-                }
+            KeyCode::Up if picker.selected > 0 => {
+                picker.selected -= 1;
             }
             KeyCode::Down => {
                 let indices = filtered_language_indices(&picker.query);
@@ -172,187 +806,2673 @@ impl App {
                 picker.query.pop();
                 picker.selected = 0;
             }
-            KeyCode::Char(c) => {
-                if !c.is_control() && picker.query.len() < 32 {
-                    picker.query.push(c);
-                    picker.selected = 0;
-                }
+            KeyCode::Char(c) if !c.is_control() && picker.query.len() < 32 => {
+                picker.query.push(c);
+                picker.selected = 0;
             }
             _ => {}
         }
         AppAction::None
     }
 
-    fn update_vim_state(&mut self, side: ActiveSide, transition: Transition) {
-        let vim = match side {
-            ActiveSide::Left => &mut self.left_vim,
-            ActiveSide::Right => &mut self.right_vim,
-        };
-        match transition {
-            Transition::Nop => {}
-            Transition::Pending(input) => vim.pending = input,
-            Transition::Mode(mode) => {
-                vim.mode = mode;
-                vim.pending = Input::default();
-            }
+    /// Adds `language` to the stack under the target pane, or removes it if already there.
+    pub fn toggle_extra_target(&mut self, language: usize) {
+        if let Some(index) = self
+            .extra_targets
+            .iter()
+            .position(|target| target.language == language)
+        {
+            self.extra_targets.remove(index);
+        } else if language == self.right_language {
+            self.error = Some("That is already the target language".to_string());
+        } else if self.extra_targets.len() >= MAX_EXTRA_TARGETS {
+            self.error = Some(format!("At most {} extra targets", MAX_EXTRA_TARGETS));
+        } else {
+            self.extra_targets.push(ExtraTarget {
+                language,
+                text: String::new(),
+                error: None,
+                version: None,
+            });
         }
     }
 
-    pub fn active_mode(&self) -> Mode {
-        match self.active {
-            ActiveSide::Left => self.left_vim.mode,
-            ActiveSide::Right => self.right_vim.mode,
+    fn open_history_finder(&mut self) {
+        let Some(history) = &self.history else {
+            self.error = Some("History is disabled".to_string());
+            return;
+        };
+        match history.recent(HISTORY_FINDER_LIMIT) {
+            Ok(entries) => self.history_finder = Some(HistoryFinder::new(entries)),
+            Err(message) => self.error = Some(message),
         }
     }
-}
 
-pub struct LanguagePicker {
-    pub side: ActiveSide,
-    pub query: String,
-    pub selected: usize,
-}
-
-pub fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
-    let mut app = App::new();
-    let api =
-        PtruiApi::from_env().map_err(|message| io::Error::new(io::ErrorKind::Other, message))?;
-    let poll_rate = Duration::from_millis(100);
-
-    loop {
-        // Redraw the UI every loop iteration.
-        terminal.draw(|frame| draw_ui(frame, &app))?;
-
-        // Poll for input; this keeps the UI responsive.
-        if event::poll(poll_rate)? {
-            if let Event::Key(key) = event::read()? {
-                match app.handle_key(key) {
-                    AppAction::Quit => return Ok(()),
-                    AppAction::NativeizeBoth => nativeize_both(&mut app, &api),
-                    AppAction::None => {}
+    fn handle_history_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(finder) = self.history_finder.as_mut() else {
+            return AppAction::None;
+        };
+        if finder.tag_edit.is_some() {
+            return self.handle_tag_edit_key(key);
+        }
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc => self.history_finder = None,
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(entry) = finder.selected_entry_mut() {
+                    entry.pinned = !entry.pinned;
+                    let (id, pinned) = (entry.id, entry.pinned);
+                    if let Some(history) = &self.history
+                        && let Err(message) = history.set_pinned(id, pinned)
+                    {
+                        self.error = Some(message);
+                    }
+                }
+            }
+            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let tags = finder.selected_entry().map(|entry| entry.tags.join(" "));
+                finder.tag_edit = tags;
+            }
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(entry) = finder.remove_selected()
+                    && let Some(history) = &self.history
+                    && let Err(message) = history.delete(entry.id)
+                {
+                    self.error = Some(message);
                 }
             }
+            KeyCode::Enter => {
+                if let Some(entry) = finder.selected_entry().cloned() {
+                    self.load_history_entry(&entry);
+                }
+                self.history_finder = None;
+            }
+            KeyCode::Up if finder.selected > 0 => finder.selected -= 1,
+            KeyCode::Down if finder.selected + 1 < finder.matches.len() => finder.selected += 1,
+            KeyCode::Backspace => {
+                finder.query.pop();
+                finder.refilter();
+            }
+            KeyCode::Char(c) if !c.is_control() && finder.query.chars().count() < 64 => {
+                finder.query.push(c);
+                finder.refilter();
+            }
+            _ => {}
         }
-        maybe_translate(&mut app, &api);
-    }
-}
-
-fn schedule_translation(app: &mut App) {
-    app.pending_translation = true;
-    app.last_edit = Some(Instant::now());
-    app.error = None;
-}
-
-fn maybe_translate(app: &mut App, api: &PtruiApi) {
-    if !app.pending_translation {
-        return;
-    }
-    let Some(last_edit) = app.last_edit else {
-        return;
-    };
-    if last_edit.elapsed() < TRANSLATION_DEBOUNCE {
-        return;
+        AppAction::None
     }
 
-    let left_lang = LANGUAGES.get(app.left_language).unwrap_or(&LANGUAGES[0]);
-    let right_lang = LANGUAGES.get(app.right_language).unwrap_or(&LANGUAGES[0]);
-    let (source_text, source_lang, target_lang, target_slot) = match app.active {
-        ActiveSide::Left => (
-            textarea_text(&app.input),
-            left_lang.code,
-            right_lang.code,
-            &mut app.output,
-        ),
-        ActiveSide::Right => (
-            textarea_text(&app.output),
-            right_lang.code,
-            left_lang.code,
-            &mut app.input,
-        ),
-    };
-
-    if source_text.trim().is_empty() {
-        set_textarea_text(target_slot, "");
-        app.pending_translation = false;
-        return;
+    fn handle_tag_edit_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(finder) = self.history_finder.as_mut() else {
+            return AppAction::None;
+        };
+        let Some(text) = finder.tag_edit.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc => finder.tag_edit = None,
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Enter => {
+                let tags = parse_tags(text);
+                finder.tag_edit = None;
+                if let Some(entry) = finder.selected_entry_mut() {
+                    entry.tags = tags;
+                    if let Some(history) = &self.history
+                        && let Err(message) = history.set_tags(entry.id, &entry.tags)
+                    {
+                        self.error = Some(message);
+                    }
+                }
+            }
+            KeyCode::Char(c) if !c.is_control() => text.push(c),
+            _ => {}
+        }
+        AppAction::None
     }
 
-    match translate_via_api(api, &source_text, source_lang, target_lang) {
-        Ok(translated) => {
-            set_textarea_text(target_slot, &translated);
-            app.error = None;
+    /// Puts a past translation back into the panes, source on the left.
+    pub fn load_history_entry(&mut self, entry: &HistoryEntry) {
+        if let Some(index) = find_language_index(&entry.source_lang) {
+            self.left_language = index;
         }
-        Err(message) => {
-            app.error = Some(message);
+        if let Some(index) = find_language_index(&entry.target_lang) {
+            self.right_language = index;
         }
+        set_textarea_text(&mut self.input, &entry.source_text);
+        set_textarea_text(&mut self.output, &entry.target_text);
+        self.left_machine_text = None;
+        self.right_machine_text = Some(textarea_text(&self.output));
+        self.refresh_counts();
+        self.discard_translation();
+        self.active = ActiveSide::Left;
+        self.error = None;
+        self.refresh_frequency(false);
     }
 
-    app.pending_translation = false;
-}
-
-fn nativeize_both(app: &mut App, api: &PtruiApi) {
-    let left_lang = LANGUAGES.get(app.left_language).unwrap_or(&LANGUAGES[0]);
-    let right_lang = LANGUAGES.get(app.right_language).unwrap_or(&LANGUAGES[0]);
-    let left_source = textarea_text(&app.input);
-    let right_source = textarea_text(&app.output);
-    if left_source.trim().is_empty() && right_source.trim().is_empty() {
-        return;
-    }
-
-    let mut new_left = left_source.clone();
-    let mut new_right = right_source.clone();
-    let mut error_message = None;
-
-    if !left_source.trim().is_empty() {
-        match translate_via_api(api, &left_source, left_lang.code, right_lang.code) {
-            Ok(translated) => new_right = translated,
-            Err(message) => error_message = Some(message),
-        }
-    }
-    if !right_source.trim().is_empty() {
-        match translate_via_api(api, &right_source, right_lang.code, left_lang.code) {
-            Ok(translated) => new_left = translated,
-            Err(message) => {
-                if error_message.is_none() {
-                    error_message = Some(message);
+    fn handle_export_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(path) = self.export_prompt.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc => self.export_prompt = None,
+            KeyCode::Tab => self.export_sentences = !self.export_sentences,
+            KeyCode::Backspace => {
+                path.pop();
+            }
+            KeyCode::Enter => {
+                let path = path.trim().to_string();
+                if !path.is_empty() {
+                    self.export_prompt = None;
+                    self.export_pair(&path);
                 }
             }
+            KeyCode::Char(c) if !c.is_control() => path.push(c),
+            _ => {}
         }
+        AppAction::None
     }
 
-    set_textarea_text(&mut app.input, &new_left);
-    set_textarea_text(&mut app.output, &new_right);
-    app.error = error_message;
-    app.pending_translation = false;
-    app.last_edit = None;
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crossterm::event::KeyEvent;
+    /// `:` starts a command only in normal mode with no operator pending (so `f:` still works).
+    fn accepts_command_line(&self) -> bool {
+        let vim = self.active_vim();
+        vim.mode == Mode::Normal && vim.pending == Input::default()
+    }
 
-    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
-        KeyEvent::new(code, modifiers)
+    fn handle_command_line_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(line) = self.command_line.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc => self.command_line = None,
+            // Backspace on an empty line leaves command mode, like Vim.
+            KeyCode::Backspace if line.pop().is_none() => self.command_line = None,
+            KeyCode::Enter => {
+                let line = line.trim().to_string();
+                self.command_line = None;
+                return self.execute_command(&line);
+            }
+            KeyCode::Char(c) if !c.is_control() => line.push(c),
+            _ => {}
+        }
+        AppAction::None
     }
 
-    #[test]
-    fn tab_switches_active_side() {
-        let mut app = App::new();
-        assert_eq!(app.active, ActiveSide::Left);
-        app.handle_key(press(KeyCode::Tab, KeyModifiers::NONE));
+    /// Runs a `:` command, returning an action when it needs the API client.
+    pub fn execute_command(&mut self, line: &str) -> AppAction {
+        if let Some(command) = parse_ex(line) {
+            return self.execute_ex(command);
+        }
+        let (name, argument) = line
+            .split_once(char::is_whitespace)
+            .map_or((line, ""), |(name, argument)| (name, argument.trim()));
+        match name {
+            "" => AppAction::None,
+            "fetch" if argument.is_empty() => {
+                self.error = Some("Usage: :fetch <url>".to_string());
+                AppAction::None
+            }
+            "fetch" => AppAction::Fetch(argument.to_string()),
+            "tmux" => self.push_to_tmux(),
+            "copy" => self.copy_translation(),
+            "edit" => AppAction::ExternalEdit,
+            "translate" => {
+                schedule_translation(self);
+                self.translation_delay = Duration::ZERO;
+                AppAction::None
+            }
+            "review" => {
+                self.open_review();
+                AppAction::None
+            }
+            "qr" => {
+                self.open_qr_code();
+                AppAction::None
+            }
+            "registers" | "reg" | "display" | "di" => {
+                self.registers_view = Some(0);
+                AppAction::None
+            }
+            "diff" => {
+                self.open_diff_view();
+                AppAction::None
+            }
+            "back" | "backtranslate" => AppAction::BackTranslate,
+            "aligned" => {
+                self.aligned = Some(0);
+                AppAction::None
+            }
+            "freeze" => {
+                self.toggle_frozen();
+                AppAction::None
+            }
+            "swaptext" => {
+                self.swap_text();
+                AppAction::None
+            }
+            "listen" if argument.is_empty() => {
+                self.toggle_recording();
+                AppAction::None
+            }
+            "listen" => {
+                self.transcribe_into_source(Path::new(argument), false);
+                AppAction::None
+            }
+            "ocr" if argument.is_empty() => {
+                self.error = Some("Usage: :ocr <image>".to_string());
+                AppAction::None
+            }
+            "ocr" => {
+                self.load_ocr(Path::new(argument));
+                AppAction::None
+            }
+            "present" => {
+                match argument {
+                    "" => self.start_presentation(None),
+                    seconds => match seconds.parse::<u64>() {
+                        Ok(seconds) if seconds > 0 => {
+                            self.start_presentation(Some(Duration::from_secs(seconds)))
+                        }
+                        _ => self.error = Some("Usage: :present [seconds]".to_string()),
+                    },
+                }
+                AppAction::None
+            }
+            "mksession" => {
+                self.save_session(argument);
+                AppAction::None
+            }
+            "w" | "write" => {
+                autosave_draft(self);
+                if self.error.is_none() {
+                    self.notice = Some("Session saved".to_string());
+                }
+                AppAction::None
+            }
+            "session" if argument.is_empty() => {
+                self.open_session_picker();
+                AppAction::None
+            }
+            "session" => match Session::load(argument) {
+                Ok(session) => self.restore_session(&session),
+                Err(message) => {
+                    self.error = Some(message);
+                    AppAction::None
+                }
+            },
+            "sessions" => {
+                self.open_session_picker();
+                AppAction::None
+            }
+            "theme" if argument.is_empty() => {
+                self.open_theme_picker();
+                AppAction::None
+            }
+            "theme" => match Theme::named(argument) {
+                Ok(theme) => {
+                    self.set_theme(theme);
+                    AppAction::SaveTheme
+                }
+                Err(message) => {
+                    self.error = Some(message);
+                    AppAction::None
+                }
+            },
+            _ => {
+                self.error = Some(format!("Unknown command: {}", name));
+                AppAction::None
+            }
+        }
+    }
+
+    /// Opens presentation mode over history entries, or over the live panes without history.
+    pub fn start_presentation(&mut self, advance_every: Option<Duration>) {
+        let entries = match self
+            .history
+            .as_ref()
+            .map(|history| history.recent(PRESENTATION_LIMIT))
+        {
+            Some(Ok(entries)) => entries,
+            Some(Err(message)) => {
+                self.error = Some(message);
+                return;
+            }
+            None => Vec::new(),
+        };
+        self.presentation = Some(Presentation::new(entries, advance_every));
+    }
+
+    fn handle_presentation_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(presentation) = self.presentation.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.presentation = None,
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('n') | KeyCode::PageDown => {
+                presentation.next()
+            }
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('N') | KeyCode::PageUp => {
+                presentation.previous()
+            }
+            KeyCode::Char(' ') => presentation.paused = !presentation.paused,
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    /// Takes the active pane's text back from `$EDITOR`; the change is one undo step and is
+    /// translated like typing.
+    pub fn apply_external_edit(&mut self, text: &str) {
+        // Windows editors may save CRLF line endings, and editors add a final newline the
+        // pane never had.
+        let text = text.replace("\r\n", "\n");
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+        if text == self.active_text() {
+            return;
+        }
+        replace_textarea_text(self.active_textarea_mut(), text);
+        *self.machine_text_mut(self.active) = None;
+        schedule_translation(self);
+        self.notice = Some("Loaded the edited text".to_string());
+    }
+
+    /// Replaces the source pane with new text and translates it.
+    fn load_source_text(&mut self, text: &str, notice: String) {
+        set_textarea_text(&mut self.input, text);
+        self.left_machine_text = None;
+        self.active = ActiveSide::Left;
+        schedule_translation(self);
+        self.notice = Some(notice);
+    }
+
+    /// Recognizes text in an image (in the left pane's language) and loads it as the source.
+    pub fn load_ocr(&mut self, image: &Path) {
+        let language = languages()
+            .get(self.left_language)
+            .unwrap_or(&languages()[0]);
+        match recognize_text(&OcrSettings::from_env(), image, language.code) {
+            Ok(text) => self.load_source_text(&text, format!("Recognized {}", image.display())),
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    /// Push-to-talk: the first press starts recording, the second transcribes it.
+    pub fn toggle_recording(&mut self) {
+        match self.recording.take() {
+            Some(recording) => match recording.stop() {
+                Ok(path) => self.transcribe_into_source(&path, true),
+                Err(message) => self.error = Some(message),
+            },
+            None => match Recording::start(&TranscribeSettings::from_env()) {
+                Ok(recording) => {
+                    self.recording = Some(recording);
+                    self.notice = Some("Recording... press Alt+v again to stop".to_string());
+                }
+                Err(message) => self.error = Some(message),
+            },
+        }
+    }
+
+    /// Transcribes audio in the left pane's language and loads it as the source.
+    fn transcribe_into_source(&mut self, audio: &Path, temporary: bool) {
+        let language = languages()
+            .get(self.left_language)
+            .unwrap_or(&languages()[0]);
+        let result = transcribe(&TranscribeSettings::from_env(), audio, language.code);
+        if temporary {
+            discard_recording(audio);
+        }
+        match result {
+            Ok(text) => self.load_source_text(&text, "Transcribed speech".to_string()),
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    /// Before a `p` paste, loads the system clipboard into the pane's register so the
+    /// paste comes from there. Returns the register's text, to spot a yank afterwards, when
+    /// a clipboard is in use.
+    fn sync_clipboard_register(&mut self, input: &Input) -> Option<String> {
+        let pastes = self.active_vim().is_paste(input);
+        let clipboard = self.clipboard.as_mut()?;
+        if pastes && let Some(text) = clipboard.paste() {
+            self.active_textarea_mut().set_yank_text(text);
+        }
+        Some(self.active_textarea().yank_text())
+    }
+
+    /// Lends a named register to the pane's yank buffer for one key and returns the unnamed
+    /// register it displaced, to be put back afterwards.
+    fn load_register(&mut self, name: char) -> String {
+        let text = self
+            .registers
+            .get(&name.to_ascii_lowercase())
+            .cloned()
+            .unwrap_or_default();
+        let unnamed = self.active_textarea().yank_text();
+        self.active_textarea_mut().set_yank_text(text);
+        unnamed
+    }
+
+    /// Saves what a `"a` command yanked or cut; `"A` appends to register `a`, like Vim. False
+    /// when the key didn't yank anything.
+    fn store_register(&mut self, name: char) -> bool {
+        if !self.active_vim().yanked {
+            return false;
+        }
+        let yanked = self.active_textarea().yank_text();
+        let key = name.to_ascii_lowercase();
+        let text = if name.is_ascii_uppercase() {
+            let old = self.registers.get(&key).map_or("", String::as_str);
+            format!("{}{}", old, yanked)
+        } else {
+            yanked
+        };
+        self.registers.insert(key, text);
+        true
+    }
+
+    /// Copies the register to the system clipboard when the last key yanked or cut something.
+    fn copy_new_yank(&mut self, before: &str) {
+        let yanked = self.active_textarea().yank_text();
+        if yanked == before || yanked.is_empty() {
+            return;
+        }
+        if let Some(clipboard) = &mut self.clipboard
+            && let Err(message) = clipboard.copy(&yanked)
+        {
+            self.error = Some(message);
+        }
+    }
+
+    fn copy_translation(&mut self) -> AppAction {
+        let text = self.target_textarea().lines().join("\n");
+        let text = text.trim_end();
+        let result = match &mut self.clipboard {
+            _ if text.is_empty() => Err("Nothing to copy".to_string()),
+            Some(clipboard) => clipboard.copy(text),
+            None => Err("The clipboard is off (PTRUI_CLIPBOARD)".to_string()),
+        };
+        match result {
+            Ok(()) => self.show_toast("copied!"),
+            Err(message) => self.error = Some(message),
+        }
+        AppAction::None
+    }
+
+    pub fn show_toast(&mut self, message: &str) {
+        self.toast = Some((message.to_string(), Instant::now() + TOAST_DURATION));
+    }
+
+    /// Clears a toast whose time is up; returns whether one was cleared.
+    pub fn expire_toast(&mut self) -> bool {
+        self.toast
+            .take_if(|(_, until)| *until <= Instant::now())
+            .is_some()
+    }
+
+    /// Sends the translated pane to `tmux set-buffer`; in popup mode a successful push quits.
+    fn push_to_tmux(&mut self) -> AppAction {
+        let text = self.target_textarea().lines().join("\n");
+        match tmux::set_buffer(text.trim_end()) {
+            Ok(()) if self.popup => AppAction::Quit,
+            Ok(()) => {
+                self.notice = Some("Copied translation to the tmux buffer".to_string());
+                AppAction::None
+            }
+            Err(message) => {
+                self.error = Some(message);
+                AppAction::None
+            }
+        }
+    }
+
+    fn save_session(&mut self, name: &str) {
+        let session = Session {
+            name: name.to_string(),
+            panes: self.draft(),
+            provider: self.provider.clone(),
+        };
+        match session.save() {
+            Ok(()) => self.notice = Some(format!("Saved session {}", name)),
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    pub fn open_session_picker(&mut self) {
+        match Session::list() {
+            Ok(sessions) if sessions.is_empty() => {
+                self.error = Some("No saved sessions; create one with :mksession <name>".into())
+            }
+            Ok(sessions) => {
+                self.session_picker = Some(SessionPicker {
+                    sessions,
+                    selected: 0,
+                })
+            }
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    /// Loads a session's panes; the caller switches to its provider when that differs.
+    pub fn restore_session(&mut self, session: &Session) -> AppAction {
+        self.restore_draft(&session.panes);
+        self.notice = Some(format!("Opened session {}", session.name));
+        if session.provider.is_empty() || session.provider == self.provider {
+            AppAction::None
+        } else {
+            AppAction::SessionProvider {
+                session: session.name.clone(),
+                provider: session.provider.clone(),
+            }
+        }
+    }
+
+    /// Reports a session whose provider isn't configured, so it stays on the current one.
+    fn missing_session_provider(&mut self, session: &str, provider: &str) {
+        self.notice = Some(format!(
+            "Opened session {} (saved with {}, which isn't available; now using {})",
+            session, provider, self.provider
+        ));
+    }
+
+    fn handle_session_picker_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(picker) = self.session_picker.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Char('d')
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && picker.selected < picker.sessions.len() =>
+            {
+                let session = picker.sessions.remove(picker.selected);
+                picker.selected = picker.selected.min(picker.sessions.len().saturating_sub(1));
+                if let Err(message) = Session::delete(&session.name) {
+                    self.error = Some(message);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.session_picker = None,
+            KeyCode::Up | KeyCode::Char('k') if picker.selected > 0 => picker.selected -= 1,
+            KeyCode::Down | KeyCode::Char('j') if picker.selected + 1 < picker.sessions.len() => {
+                picker.selected += 1
+            }
+            KeyCode::Enter => {
+                let session = picker.sessions.get(picker.selected).cloned();
+                self.session_picker = None;
+                if let Some(session) = session {
+                    return self.restore_session(&session);
+                }
+            }
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    /// Switches to `theme`, keeping the config's color overrides.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme.with_colors(&self.colors).unwrap_or(theme);
+    }
+
+    pub fn open_theme_picker(&mut self) {
+        let selected = THEMES
+            .iter()
+            .position(|theme| theme.name == self.theme.name)
+            .unwrap_or(0);
+        self.theme_picker = Some(ThemePicker {
+            selected,
+            previous: self.theme,
+        });
+    }
+
+    fn handle_theme_picker_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(picker) = self.theme_picker.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.theme = picker.previous;
+                self.theme_picker = None;
+            }
+            KeyCode::Up | KeyCode::Char('k') if picker.selected > 0 => {
+                picker.selected -= 1;
+                let theme = THEMES[picker.selected];
+                self.set_theme(theme);
+            }
+            KeyCode::Down | KeyCode::Char('j') if picker.selected + 1 < THEMES.len() => {
+                picker.selected += 1;
+                let theme = THEMES[picker.selected];
+                self.set_theme(theme);
+            }
+            KeyCode::Enter => {
+                self.theme_picker = None;
+                return AppAction::SaveTheme;
+            }
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    fn export_pair(&mut self, path: &str) {
+        let (source_lang, target_lang) = self.active_pair();
+        let source = self.active_text();
+        let target = textarea_text(self.target_textarea());
+        let pair = ExportPair {
+            source_lang,
+            target_lang,
+            source: &source,
+            target: &target,
+            sentences: self.export_sentences,
+        };
+        match write_export(Path::new(path), &pair) {
+            Ok(()) => self.notice = Some(format!("Exported to {}", path)),
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    fn handle_draft_prompt_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Char('y') | KeyCode::Enter => {
+                if let Some(draft) = self.draft_prompt.take() {
+                    self.restore_draft(&draft);
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.draft_prompt = None;
+                if let Err(message) = Draft::discard() {
+                    self.error = Some(message);
+                }
+            }
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    /// Snapshot of the panes as they would be autosaved.
+    pub fn draft(&self) -> Draft {
+        Draft {
+            left_language: languages()[self.left_language].code.to_string(),
+            right_language: languages()[self.right_language].code.to_string(),
+            left_text: textarea_text(&self.input),
+            right_text: textarea_text(&self.output),
+            right_active: self.active == ActiveSide::Right,
+            left_insert: self.left_vim.mode == Mode::Insert,
+            right_insert: self.right_vim.mode == Mode::Insert,
+            saved_at: unix_now(),
+        }
+    }
+
+    pub fn restore_draft(&mut self, draft: &Draft) {
+        if let Some(index) = find_language_index(&draft.left_language) {
+            self.left_language = index;
+        }
+        if let Some(index) = find_language_index(&draft.right_language) {
+            self.right_language = index;
+        }
+        set_textarea_text(&mut self.input, &draft.left_text);
+        set_textarea_text(&mut self.output, &draft.right_text);
+        self.refresh_counts();
+        self.left_machine_text = None;
+        self.right_machine_text = None;
+        self.discard_translation();
+        self.active = if draft.right_active {
+            ActiveSide::Right
+        } else {
+            ActiveSide::Left
+        };
+        for (side, insert) in [
+            (ActiveSide::Left, draft.left_insert),
+            (ActiveSide::Right, draft.right_insert),
+        ] {
+            let mode = if insert { Mode::Insert } else { Mode::Normal };
+            self.update_vim_state(side, Transition::Mode(mode));
+        }
+        self.saved_draft = Some(draft.clone());
+        self.notice = Some("Draft restored".to_string());
+    }
+
+    fn handle_gloss_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => AppAction::Quit,
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.gloss = None;
+                AppAction::None
+            }
+            KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.gloss = None;
+                AppAction::None
+            }
+            _ => AppAction::None,
+        }
+    }
+
+    fn handle_dictionary_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(panel) = self.dictionary.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.dictionary = None,
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.dictionary = None
+            }
+            KeyCode::Down | KeyCode::Char('j') => panel.scroll = panel.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => panel.scroll = panel.scroll.saturating_sub(1),
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    fn open_review(&mut self) {
+        match load_phrases() {
+            Ok(cards) if cards.is_empty() => {
+                self.error = Some("The phrasebook is empty; Alt+a saves a pair to it".to_string());
+            }
+            Ok(cards) => self.review = Some(Review::new(cards)),
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    fn handle_review_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(review) = self.review.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.review = None,
+            // The first press shows the answer, the next one moves on.
+            KeyCode::Char(' ') | KeyCode::Enter if review.reveal() => {
+                self.stats.record_review();
+                self.stats_changed = true;
+            }
+            KeyCode::Char(' ') | KeyCode::Enter | KeyCode::Char('l') | KeyCode::Right => {
+                review.next()
+            }
+            KeyCode::Char('h') | KeyCode::Left => review.previous(),
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    fn handle_stats_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => AppAction::Quit,
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('u') => {
+                self.show_stats = false;
+                AppAction::None
+            }
+            _ => AppAction::None,
+        }
+    }
+
+    fn handle_usage_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => AppAction::Quit,
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('U') => {
+                self.usage = None;
+                AppAction::None
+            }
+            _ => AppAction::None,
+        }
+    }
+
+    fn handle_qr_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => AppAction::Quit,
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.qr_code = None;
+                AppAction::None
+            }
+            _ => AppAction::None,
+        }
+    }
+
+    fn handle_error_details_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(ErrorDetails { scroll, .. }) = self.error_details.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.error_details = None,
+            KeyCode::Down | KeyCode::Char('j') => *scroll = scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+            KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+            KeyCode::Home | KeyCode::Char('g') => *scroll = 0,
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    fn execute_ex(&mut self, command: ExCommand) -> AppAction {
+        match command {
+            ExCommand::GoToLine(line) => {
+                let row = line.saturating_sub(1).min(u16::MAX as usize) as u16;
+                self.active_textarea_mut()
+                    .move_cursor(CursorMove::Jump(row, 0));
+            }
+            ExCommand::GoToLastLine => self.active_textarea_mut().move_cursor(CursorMove::Bottom),
+            ExCommand::DeleteAll => {
+                // A cut rather than a fresh textarea, so `u` brings the text back.
+                let textarea = self.active_textarea_mut();
+                textarea.select_all();
+                if textarea.cut() {
+                    *self.machine_text_mut(self.active) = None;
+                    schedule_translation(self);
+                }
+            }
+            ExCommand::Quit => return AppAction::Quit,
+            ExCommand::WriteQuit => {
+                autosave_draft(self);
+                // Like Vim, a failed write keeps ptrui open.
+                if self.error.is_none() {
+                    return AppAction::Quit;
+                }
+            }
+        }
+        AppAction::None
+    }
+
+    fn handle_registers_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(scroll) = self.registers_view.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.registers_view = None,
+            KeyCode::Down | KeyCode::Char('j') => *scroll = scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    /// Compares a hand-edited pane with the machine translation it started from: the active
+    /// pane when it holds one, else the other.
+    fn open_diff_view(&mut self) {
+        let sides = [self.active, self.active.other()];
+        let compared = sides.into_iter().find_map(|side| {
+            let (machine_text, textarea) = match side {
+                ActiveSide::Left => (&self.left_machine_text, &self.input),
+                ActiveSide::Right => (&self.right_machine_text, &self.output),
+            };
+            machine_text.as_ref().map(|text| (text, textarea))
+        });
+        match compared {
+            Some((machine_text, textarea)) => {
+                self.diff_view = Some(DiffView {
+                    title: "Your edits vs the machine translation".to_string(),
+                    changes: word_diff(machine_text, &textarea_text(textarea)),
+                    scroll: 0,
+                });
+            }
+            None => self.error = Some("No machine translation to compare with".to_string()),
+        }
+    }
+
+    fn handle_diff_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(view) = self.diff_view.as_mut() else {
+            return AppAction::None;
+        };
+        let scroll = &mut view.scroll;
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.diff_view = None,
+            KeyCode::Char('D') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.diff_view = None
+            }
+            KeyCode::Down | KeyCode::Char('j') => *scroll = scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+            KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+            KeyCode::Home | KeyCode::Char('g') => *scroll = 0,
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    fn handle_aligned_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(scroll) = self.aligned.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.aligned = None,
+            KeyCode::Char('i') if key.modifiers.contains(KeyModifiers::ALT) => self.aligned = None,
+            KeyCode::Down | KeyCode::Char('j') => *scroll = scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+            KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+            KeyCode::Home | KeyCode::Char('g') => *scroll = 0,
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    /// Opens the active text in DeepL or Google Translate to sanity-check the API result.
+    pub fn open_in_web_translator(&mut self) {
+        let text = self.active_textarea().lines().join("\n");
+        if text.trim().is_empty() {
+            self.error = Some("Nothing to compare".to_string());
+            return;
+        }
+        let (source_lang, target_lang) = self.active_pair();
+        let url = WebTranslator::from_env().url(text.trim(), source_lang, target_lang);
+        match open_url(&url) {
+            Ok(()) => self.notice = Some("Opened in the browser".to_string()),
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    /// Shows the translated pane as a QR code.
+    pub fn open_qr_code(&mut self) {
+        let text = self.target_textarea().lines().join("\n");
+        let text = text.trim();
+        if text.is_empty() {
+            self.error = Some("Nothing to share".to_string());
+            return;
+        }
+        match render_qr(text) {
+            Ok(lines) => self.qr_code = Some(lines),
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    /// Hides the quality markers, or asks for an estimate when none match the target text.
+    fn toggle_quality(&mut self) -> AppAction {
+        let current = self
+            .quality
+            .as_ref()
+            .is_some_and(|marks| marks.rows(self.target_textarea().lines()).is_some());
+        self.quality = None;
+        if current {
+            AppAction::None
+        } else {
+            AppAction::Quality
+        }
+    }
+
+    fn handle_suggestion_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(picker) = self.suggestions.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc => self.suggestions = None,
+            KeyCode::Up if picker.selected > 0 => picker.selected -= 1,
+            KeyCode::Down if picker.selected + 1 < picker.suggestions.len() => picker.selected += 1,
+            KeyCode::Enter => {
+                if let Some(replacement) = picker.suggestions.get(picker.selected).cloned() {
+                    self.replace_selection(&replacement);
+                }
+                self.suggestions = None;
+            }
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    fn handle_alternatives_key(&mut self, key: crossterm::event::KeyEvent) -> AppAction {
+        let Some(picker) = self.alternatives.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return AppAction::Quit;
+            }
+            KeyCode::Esc => self.alternatives = None,
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => picker.previous(),
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => picker.next(),
+            KeyCode::Enter => {
+                if let Some(picker) = self.alternatives.take() {
+                    self.accept_alternative(picker);
+                }
+            }
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    /// Forgets lookups still on their way, e.g. after switching providers.
+    fn drop_lookups(&mut self) {
+        self.back_translating = None;
+        if let Some(picker) = self.alternatives.as_mut() {
+            picker.pending = 0;
+            if picker.candidates.is_empty() {
+                self.alternatives = None;
+            }
+        }
+    }
+
+    /// Writes the chosen candidate into the target pane as its machine translation.
+    fn accept_alternative(&mut self, picker: AlternativePicker) {
+        if picker.version != self.source_version {
+            self.notice = Some("The alternatives are out of date".to_string());
+            return;
+        }
+        let Some(candidate) = picker.current() else {
+            return;
+        };
+        let target_side = self.active.other();
+        let target = self.textarea_mut(target_side);
+        replace_textarea_text(target, &candidate.text);
+        let written = textarea_text(target);
+        *self.machine_text_mut(target_side) = Some(written);
+        self.refresh_counts();
+        self.held_translation = None;
+    }
+
+    /// Saves the active text and its translation to the phrasebook for flashcard export.
+    fn send_to_deck(&mut self) {
+        let source = self.active_text();
+        let target = textarea_text(self.target_textarea());
+        if source.trim().is_empty() || target.trim().is_empty() {
+            self.error = Some("Nothing to save: translate something first".to_string());
+            return;
+        }
+        let (source_lang, target_lang) = self.active_pair();
+        let pronunciation = romanize(target_lang, &target);
+        let entry = PhraseEntry::new(&source, &target, source_lang, target_lang, pronunciation);
+        match append_phrase(&entry) {
+            Ok(()) => {
+                self.notice = Some("saved to deck".to_string());
+            }
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    fn cycle_tone(&mut self) {
+        self.tone = self.tone.next();
+        schedule_translation(self);
+    }
+
+    fn run_quick_action(&mut self, action: QuickAction) -> AppAction {
+        match action {
+            QuickAction::TranslateNow => return self.execute_command("translate"),
+            QuickAction::SwapText => self.swap_text(),
+            QuickAction::CycleTone => self.cycle_tone(),
+            QuickAction::CycleFormality => self.cycle_formality(),
+            QuickAction::CycleProvider => return AppAction::CycleProvider,
+            QuickAction::History => self.open_history_finder(),
+            QuickAction::ToggleLayout => {
+                self.layout.rotate();
+                return AppAction::SaveLayout;
+            }
+            QuickAction::Quality => return self.toggle_quality(),
+            QuickAction::Speak => return AppAction::Speak,
+            QuickAction::QrCode => self.open_qr_code(),
+        }
+        AppAction::None
+    }
+
+    /// Exchanges the text of the two panes, e.g. after pasting into the wrong side. Languages
+    /// stay put and nothing is retranslated.
+    fn swap_text(&mut self) {
+        std::mem::swap(&mut self.input, &mut self.output);
+        std::mem::swap(&mut self.left_vim, &mut self.right_vim);
+        std::mem::swap(&mut self.left_machine_text, &mut self.right_machine_text);
+        std::mem::swap(&mut self.left_counts, &mut self.right_counts);
+        self.discard_translation();
+    }
+
+    /// Drops held, queued and in-flight translations once the panes are replaced wholesale:
+    /// translating the old text would overwrite the new target pane.
+    fn discard_translation(&mut self) {
+        self.held_translation = None;
+        self.source_version += 1;
+        self.pending_translation = false;
+    }
+
+    /// Loads the frequency list for the current target language. `enable` turns the
+    /// highlighting on; otherwise the list is only reloaded when highlighting is already on.
+    fn refresh_frequency(&mut self, enable: bool) {
+        if !enable && self.frequency.is_none() {
+            return;
+        }
+        let (_, target_lang) = self.active_pair();
+        if self
+            .frequency
+            .as_ref()
+            .is_some_and(|highlight| highlight.language == target_lang)
+        {
+            return;
+        }
+        self.frequency = FrequencyList::load(target_lang).map(|list| FrequencyHighlight {
+            language: target_lang,
+            list,
+        });
+        if self.frequency.is_none() {
+            self.error = Some(format!("No frequency list for {}", target_lang));
+        }
+    }
+
+    /// Swaps the active pane's visual selection for new text and returns to normal mode.
+    pub fn replace_selection(&mut self, replacement: &str) {
+        let side = self.active;
+        let textarea = self.active_textarea_mut();
+        if !textarea.is_selecting() {
+            return;
+        }
+        textarea.move_cursor(CursorMove::Forward);
+        textarea.cut();
+        textarea.insert_str(replacement);
+        self.update_vim_state(side, Transition::Mode(Mode::Normal));
+        schedule_translation(self);
+    }
+
+    fn update_vim_state(&mut self, side: ActiveSide, transition: Transition) {
+        match side {
+            ActiveSide::Left => self.left_vim.apply(transition),
+            ActiveSide::Right => self.right_vim.apply(transition),
+        }
+    }
+
+    /// The earliest time a timer needs the loop: the debounced translation or a slide advance.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let translation = self
+            .last_edit
+            .filter(|_| self.pending_translation)
+            .map(|last_edit| last_edit + self.translation_delay)
+            .map(|due| match self.offline_since {
+                Some(_) => due.max(self.last_attempt + OFFLINE_RETRY_INTERVAL),
+                None => due,
+            });
+        let slide = self
+            .presentation
+            .as_ref()
+            .and_then(Presentation::next_advance);
+        let lock = self
+            .lock
+            .as_ref()
+            .filter(|_| self.presentation.is_none())
+            .and_then(PrivacyLock::next_lock);
+        // Keep the spinner turning and pick up the worker's answer promptly.
+        let spinner = self
+            .translating_since
+            .map(|_| Instant::now() + SPINNER_INTERVAL);
+        let toast = self.toast.as_ref().map(|(_, until)| *until);
+        translation
+            .into_iter()
+            .chain(slide)
+            .chain(lock)
+            .chain(spinner)
+            .chain(toast)
+            .min()
+    }
+
+    /// Engages the privacy lock once the user has been idle long enough; returns whether it
+    /// just locked. Presentations are meant to stay on screen, so they never lock.
+    pub fn check_lock(&mut self) -> bool {
+        let Some(lock) = self.lock.as_mut() else {
+            return false;
+        };
+        if self.presentation.is_some() {
+            lock.touch(Instant::now());
+            return false;
+        }
+        lock.tick(Instant::now())
+    }
+
+    /// The pane that receives translations of the active side.
+    pub fn target_textarea(&self) -> &TextArea<'static> {
+        match self.active {
+            ActiveSide::Left => &self.output,
+            ActiveSide::Right => &self.input,
+        }
+    }
+
+    /// Number of characters (not bytes) in the translated pane, counting line breaks.
+    pub fn target_len(&self) -> usize {
+        let lines = self.target_textarea().lines();
+        let chars: usize = lines.iter().map(|line| line.chars().count()).sum();
+        chars + lines.len().saturating_sub(1)
+    }
+
+    /// The soft limit, when the active pane's text is longer than it.
+    fn soft_limit_exceeded(&self) -> Option<usize> {
+        let limit = self.soft_limit?;
+        let lines = self.active_textarea().lines();
+        let mut chars = lines.len().saturating_sub(1);
+        for line in lines {
+            chars += line.chars().count();
+            if chars > limit {
+                return Some(limit);
+            }
+        }
+        None
+    }
+
+    /// How many characters the translation exceeds the configured limit by, if any.
+    pub fn length_overflow(&self) -> Option<usize> {
+        let limit = self.length_limit?;
+        let len = self.target_len();
+        (len > limit).then(|| len - limit)
+    }
+
+    /// The text of the pane that currently accepts input.
+    pub fn active_text(&self) -> String {
+        match self.active {
+            ActiveSide::Left => textarea_text(&self.input),
+            ActiveSide::Right => textarea_text(&self.output),
+        }
+    }
+
+    fn textarea_mut(&mut self, side: ActiveSide) -> &mut TextArea<'static> {
+        match side {
+            ActiveSide::Left => &mut self.input,
+            ActiveSide::Right => &mut self.output,
+        }
+    }
+
+    fn machine_text_mut(&mut self, side: ActiveSide) -> &mut Option<String> {
+        match side {
+            ActiveSide::Left => &mut self.left_machine_text,
+            ActiveSide::Right => &mut self.right_machine_text,
+        }
+    }
+
+    /// Whether a pane holds a machine translation that was corrected by hand since.
+    pub fn hand_edited(&self, side: ActiveSide) -> bool {
+        let (machine_text, textarea) = match side {
+            ActiveSide::Left => (&self.left_machine_text, &self.input),
+            ActiveSide::Right => (&self.right_machine_text, &self.output),
+        };
+        machine_text
+            .as_ref()
+            .is_some_and(|machine_text| *machine_text != textarea_text(textarea))
+    }
+
+    pub fn frozen(&self, side: ActiveSide) -> bool {
+        match side {
+            ActiveSide::Left => self.left_frozen,
+            ActiveSide::Right => self.right_frozen,
+        }
+    }
+
+    /// Freezes or thaws the active pane. Translations into a frozen pane wait for `Alt+y`.
+    fn toggle_frozen(&mut self) {
+        let frozen = match self.active {
+            ActiveSide::Left => &mut self.left_frozen,
+            ActiveSide::Right => &mut self.right_frozen,
+        };
+        *frozen = !*frozen;
+        self.notice = Some(if *frozen {
+            "Pane frozen: translations into it wait for Alt+y".to_string()
+        } else {
+            "Pane unfrozen".to_string()
+        });
+    }
+
+    /// Writes a held translation over the hand edits it was waiting on.
+    fn accept_held_translation(&mut self) {
+        let Some(held) = self.held_translation.take() else {
+            self.notice = Some("No translation is waiting".to_string());
+            return;
+        };
+        if held.request.version != self.source_version {
+            self.notice = Some("The held translation is out of date".to_string());
+            return;
+        }
+        write_translation(self, &held.request, &held.translated, &held.provider);
+    }
+
+    pub fn active_textarea(&self) -> &TextArea<'static> {
+        match self.active {
+            ActiveSide::Left => &self.input,
+            ActiveSide::Right => &self.output,
+        }
+    }
+
+    pub fn active_textarea_mut(&mut self) -> &mut TextArea<'static> {
+        match self.active {
+            ActiveSide::Left => &mut self.input,
+            ActiveSide::Right => &mut self.output,
+        }
+    }
+
+    /// Language code of one pane.
+    pub fn language_code(&self, side: ActiveSide) -> &'static str {
+        let index = match side {
+            ActiveSide::Left => self.left_language,
+            ActiveSide::Right => self.right_language,
+        };
+        languages().get(index).unwrap_or(&languages()[0]).code
+    }
+
+    /// Language codes for translating out of the active pane: (source, target).
+    pub fn active_pair(&self) -> (&'static str, &'static str) {
+        let left = languages()
+            .get(self.left_language)
+            .unwrap_or(&languages()[0]);
+        let right = languages()
+            .get(self.right_language)
+            .unwrap_or(&languages()[0]);
+        match self.active {
+            ActiveSide::Left => (left.code, right.code),
+            ActiveSide::Right => (right.code, left.code),
+        }
+    }
+
+    fn translation_request(&self) -> TranslationRequest {
+        let (source_lang, target_lang) = self.active_pair();
+        TranslationRequest {
+            version: self.source_version,
+            side: self.active,
+            source_text: self.active_text(),
+            source_lang,
+            target_lang,
+            extra_target: None,
+        }
+    }
+
+    /// A request for the first extra target not yet translated at the current version.
+    fn extra_target_request(&self) -> Option<TranslationRequest> {
+        let target = self
+            .extra_targets
+            .iter()
+            .find(|target| target.version != Some(self.source_version))?;
+        Some(TranslationRequest {
+            version: self.source_version,
+            side: ActiveSide::Left,
+            source_text: textarea_text(&self.input),
+            source_lang: self.language_code(ActiveSide::Left),
+            target_lang: languages()
+                .get(target.language)
+                .unwrap_or(&languages()[0])
+                .code,
+            extra_target: Some(target.language),
+        })
+    }
+
+    /// Romanized or ruby-annotated translation, when enabled and the target script needs it.
+    /// Only the lines the small reading-aid panel can show are converted, so long documents
+    /// don't pay for it on every redraw.
+    pub fn reading_aid_text(&self) -> Option<String> {
+        if self.reading_aid == ReadingAid::Off {
+            return None;
+        }
+        let (_, target_lang) = self.active_pair();
+        let lines = self.target_textarea().lines();
+        let visible = &lines[..lines.len().min(READING_AID_LINES)];
+        self.reading_aid.apply(target_lang, &visible.join("\n"))
+    }
+
+    /// Options for translating the active pane into the other one.
+    pub fn translate_options(&self) -> TranslateOptions<'static> {
+        self.options_into(self.active.other())
+    }
+
+    /// Options for an extra target, which takes the target pane's formality.
+    fn extra_target_options(&self, target_lang: &str) -> TranslateOptions<'static> {
+        TranslateOptions {
+            tone: self.tone.instruction(),
+            formality: self.formality(ActiveSide::Right).parameter(target_lang),
+            ..TranslateOptions::default()
+        }
+    }
+
+    fn options_into(&self, side: ActiveSide) -> TranslateOptions<'static> {
+        TranslateOptions {
+            tone: self.tone.instruction(),
+            formality: self.formality(side).parameter(self.language_code(side)),
+            ..TranslateOptions::default()
+        }
+    }
+
+    pub fn formality(&self, side: ActiveSide) -> Formality {
+        match side {
+            ActiveSide::Left => self.left_formality,
+            ActiveSide::Right => self.right_formality,
+        }
+    }
+
+    /// Cycles the formality of the pane translations are written into.
+    fn cycle_formality(&mut self) {
+        let side = self.active.other();
+        let formality = self.formality(side).next();
+        match side {
+            ActiveSide::Left => self.left_formality = formality,
+            ActiveSide::Right => self.right_formality = formality,
+        }
+        schedule_translation(self);
+        let code = self.language_code(side);
+        if formality != Formality::Default && !supports_formality(code) {
+            self.notice = Some(format!(
+                "{} has no formality setting; translating without it",
+                code
+            ));
+        }
+    }
+
+    pub fn active_mode(&self) -> Mode {
+        self.active_vim().mode
+    }
+
+    /// The `/` pattern being typed in the active pane, if it is in search mode.
+    pub fn search_query(&self) -> Option<&str> {
+        let vim = self.active_vim();
+        (vim.mode == Mode::Search).then_some(vim.query.as_str())
+    }
+
+    fn active_vim(&self) -> &Vim {
+        match self.active {
+            ActiveSide::Left => &self.left_vim,
+            ActiveSide::Right => &self.right_vim,
+        }
+    }
+}
+
+pub struct FrequencyHighlight {
+    pub language: &'static str,
+    pub list: FrequencyList,
+}
+
+/// The error message as it was when its popup opened, so a later retry clearing
+/// `App::error` doesn't empty the popup.
+pub struct ErrorDetails {
+    pub message: String,
+    pub scroll: u16,
+}
+
+pub struct LanguagePicker {
+    pub side: ActiveSide,
+    pub query: String,
+    pub selected: usize,
+}
+
+/// Words and characters in a pane, as shown in the status bar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextCounts {
+    pub words: usize,
+    pub chars: usize,
+}
+
+impl TextCounts {
+    fn of(textarea: &TextArea) -> Self {
+        textarea
+            .lines()
+            .iter()
+            .fold(Self::default(), |counts, line| Self {
+                words: counts.words + line.split_whitespace().count(),
+                chars: counts.chars + line.chars().count(),
+            })
+    }
+}
+
+/// Another language the left pane is translated into, shown read-only under the target pane.
+pub struct ExtraTarget {
+    pub language: usize,
+    pub text: String,
+    pub error: Option<String>,
+    // Source version `text` was translated at; `None` before the first translation.
+    version: Option<u64>,
+}
+
+pub fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    options: TuiOptions,
+) -> io::Result<()> {
+    let mut config = Config::load().map_err(io::Error::other)?;
+    if options.offline {
+        config.api.url = Some(MOCK_URL.to_string());
+    }
+    let mut app = App::new(&config);
+    app.length_limit = length_limit_from_env();
+    app.soft_limit = soft_limit_from_env();
+    app.lock = PrivacyLock::from_env().map_err(io::Error::other)?;
+    app.stats = PracticeStats::load();
+    match HistoryStore::open_default() {
+        Ok(store) => app.history = store,
+        Err(message) => app.error = Some(message),
+    }
+    app.webhook = Webhook::from_env().map_err(io::Error::other)?;
+    app.clipboard = Clipboard::from_env();
+    let providers: Vec<Arc<dyn TranslationProvider>> = provider::available(&config)
+        .map_err(io::Error::other)?
+        .into_iter()
+        .map(Arc::from)
+        .collect();
+    let session = options
+        .session
+        .as_deref()
+        .map(Session::load)
+        .transpose()
+        .map_err(io::Error::other)?;
+    // A session opened at launch starts on the provider it was saved with.
+    let mut provider_index = session
+        .as_ref()
+        .and_then(|session| {
+            providers
+                .iter()
+                .position(|candidate| candidate.name() == session.provider)
+        })
+        .unwrap_or(0);
+    let mut provider = Arc::clone(&providers[provider_index]);
+    // Dictionary, thesaurus and web page lookups share the API's connection pool.
+    let client = provider.http_client().unwrap_or_default();
+    let mut translator = Translator::for_provider(Arc::clone(&provider), config.chunk_chars());
+    let mut lookups = Lookups::for_provider(Arc::clone(&provider), config.chunk_chars());
+    refresh_languages(Arc::clone(&provider));
+    let mut quota_watcher = QuotaWatcher::new(Arc::clone(&provider));
+    quota_watcher.check();
+    let mut in_flight: Option<InFlight> = None;
+    let speech = SpeechSettings::from_env();
+    let notifier = Notifier::from_env();
+    app.cache = TranslationCache::from_env().map_err(io::Error::other)?;
+    app.provider = provider.name();
+    app.popup = options.popup;
+    if !options.no_restore
+        && let Some(draft) = Draft::load()
+    {
+        if config.restore_session {
+            app.restore_draft(&draft);
+            app.notice = Some("Session restored".to_string());
+        } else {
+            app.draft_prompt = Some(draft);
+        }
+    }
+    if let Some(session) = &session {
+        if let AppAction::SessionProvider { session, provider } = app.restore_session(session) {
+            app.missing_session_provider(&session, &provider);
+        }
+    } else if options.pick_session {
+        app.open_session_picker();
+    }
+    if let Some(image) = &options.ocr {
+        app.load_ocr(image);
+    }
+    if options.present {
+        app.start_presentation(options.advance_every);
+    }
+    if word_of_the_day_enabled() {
+        open_word_of_the_day(&mut app, provider.as_ref());
+    }
+    let mut recorder = options
+        .record
+        .as_deref()
+        .map(KeyRecorder::create)
+        .transpose()
+        .map_err(io::Error::other)?;
+    let mut replay = options
+        .replay
+        .as_deref()
+        .map(KeyReplay::load)
+        .transpose()
+        .map_err(io::Error::other)?;
+    let mut last_autosave = Instant::now();
+    let mut needs_redraw = true;
+
+    loop {
+        // Only redraw after something changed; an idle session does no rendering at all.
+        if needs_redraw {
+            style_panes(&mut app);
+            terminal.draw(|frame| draw_ui(frame, &app))?;
+            needs_redraw = false;
+        }
+
+        // Sleep until input arrives or the next timed job (translation, slide, autosave) is due.
+        let next_autosave = last_autosave + DRAFT_AUTOSAVE_INTERVAL;
+        let deadline = app
+            .next_deadline()
+            .into_iter()
+            .chain(replay.as_ref().and_then(KeyReplay::next_due))
+            .fold(next_autosave, Instant::min);
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        // Replayed events play at their recorded pace; the keyboard still works meanwhile.
+        let event = if event::poll(timeout)? {
+            Some(event::read()?)
+        } else {
+            replay
+                .as_mut()
+                .and_then(|replay| replay.pop_due(Instant::now()))
+        };
+        if let (Some(keys), Some(event)) = (recorder.as_mut(), &event)
+            && let Err(message) = keys.record(event)
+        {
+            app.error = Some(message);
+            recorder = None;
+        }
+        if matches!(event, Some(Event::Resize(..))) {
+            needs_redraw = true;
+        }
+        if let Some(Event::Paste(text)) = &event {
+            needs_redraw = true;
+            app.handle_paste(text);
+        }
+        if let Some(Event::Key(key)) = event {
+            needs_redraw = true;
+            match app.handle_key(key) {
+                AppAction::Quit => {
+                    if let Some(recording) = app.recording.take()
+                        && let Ok(path) = recording.stop()
+                    {
+                        discard_recording(&path);
+                    }
+                    settle_translation(&mut app, true);
+                    save_stats(&mut app);
+                    // Keep unsaved panes around so an accidental quit can be undone next launch.
+                    autosave_draft(&mut app);
+                    // Best effort: the totals only feed `ptrui usage`.
+                    let _ = app.cache.stats.accumulate();
+                    return Ok(());
+                }
+                AppAction::NativeizeBoth => {
+                    let started = Instant::now();
+                    nativeize_both(&mut app, provider.as_ref());
+                    notify_if_slow(&notifier, "Native-ize", started, &app);
+                }
+                AppAction::Gloss => open_gloss(&mut app, provider.as_ref()),
+                AppAction::Speak => speak_translation(&mut app, &speech),
+                AppAction::Dictionary => open_dictionary(&mut app, &client),
+                AppAction::Synonyms => open_suggestions(&mut app, &client),
+                AppAction::Alternatives => start_alternatives(&mut app, &lookups),
+                AppAction::Quality => {
+                    let started = Instant::now();
+                    open_quality(&mut app, provider.as_ref());
+                    notify_if_slow(&notifier, "Quality estimate", started, &app);
+                }
+                AppAction::BackTranslate => start_back_translation(&mut app, &lookups),
+                AppAction::CycleProvider => {
+                    provider_index = (provider_index + 1) % providers.len();
+                    provider = Arc::clone(&providers[provider_index]);
+                    // The old worker's answer would come from the wrong provider.
+                    (translator, lookups) =
+                        switch_provider(&mut app, &provider, config.chunk_chars());
+                    in_flight = None;
+                    app.notice = Some(format!("Translating with {}", app.provider));
+                }
+                AppAction::SessionProvider {
+                    session,
+                    provider: name,
+                } => match providers
+                    .iter()
+                    .position(|candidate| candidate.name() == name)
+                {
+                    Some(index) => {
+                        provider_index = index;
+                        provider = Arc::clone(&providers[provider_index]);
+                        (translator, lookups) =
+                            switch_provider(&mut app, &provider, config.chunk_chars());
+                        in_flight = None;
+                        app.notice =
+                            Some(format!("Opened session {} with {}", session, app.provider));
+                    }
+                    None => app.missing_session_provider(&session, &name),
+                },
+                AppAction::Fetch(url) => {
+                    let started = Instant::now();
+                    fetch_into_source(&mut app, &client, &url);
+                    notify_if_slow(&notifier, "Web page translation", started, &app);
+                }
+                AppAction::SaveLayout => {
+                    let layout = app.layout;
+                    if let Err(message) =
+                        Config::save_setting("layout", &format!("{:?}", layout.name()))
+                            .and_then(|_| Config::save_setting("split", &layout.split.to_string()))
+                    {
+                        app.error = Some(message);
+                    }
+                }
+                AppAction::ExternalEdit => {
+                    let text = app.active_text();
+                    match edit_in_editor(terminal, &text)? {
+                        Ok(edited) => app.apply_external_edit(&edited),
+                        Err(message) => app.error = Some(message),
+                    }
+                }
+                AppAction::SaveTheme => {
+                    if let Err(message) =
+                        Config::save_setting("theme", &format!("{:?}", app.theme.name))
+                    {
+                        app.error = Some(message);
+                    }
+                }
+                AppAction::None => {}
+            }
+        }
+        if let Some(presentation) = app.presentation.as_mut() {
+            needs_redraw |= presentation.tick();
+        }
+        needs_redraw |= app.check_lock();
+        needs_redraw |= app.expire_toast();
+        // Edits made since the request went out cancel it; the newer text goes out as soon as
+        // its debounce passes instead of waiting for a stale answer.
+        if in_flight
+            .as_ref()
+            .is_some_and(|flight| flight.request.version != app.source_version)
+        {
+            translator.cancel_before(app.source_version);
+            in_flight = None;
+            app.translating_since = None;
+            app.chunk_progress = None;
+            needs_redraw = true;
+        }
+        if let Some(progress) = translator.try_progress()
+            && in_flight
+                .as_ref()
+                .is_some_and(|flight| flight.request.version == progress.version)
+        {
+            app.chunk_progress = Some((progress.chunk, progress.total));
+            needs_redraw = true;
+        }
+        if let Some(finished) = translator.try_recv()
+            && let Some(flight) = in_flight.take_if(|flight| {
+                finished
+                    .as_ref()
+                    .map_or(true, |done| done.version == flight.request.version)
+            })
+        {
+            needs_redraw = true;
+            let started = flight.started;
+            finish_translation(&mut app, flight, finished);
+            notify_if_slow(&notifier, "Translation", started, &app);
+            quota_watcher.check();
+        }
+        while let Some(done) = lookups.try_recv() {
+            needs_redraw = true;
+            let done = match done {
+                Ok(done) => done,
+                Err(message) => {
+                    app.error = Some(message);
+                    break;
+                }
+            };
+            let started = done.tag.started;
+            let back = matches!(done.tag.purpose, LookupPurpose::BackTranslation { .. });
+            finish_lookup(&mut app, done);
+            if back {
+                notify_if_slow(&notifier, "Back-translation", started, &app);
+            }
+        }
+        if let Some(quota) = quota_watcher.try_recv() {
+            app.quota = Some(quota);
+            needs_redraw = true;
+        }
+        if in_flight.is_none() {
+            needs_redraw |= maybe_translate(&mut app, &translator, &mut in_flight);
+        }
+        settle_translation(&mut app, false);
+        if last_autosave.elapsed() >= DRAFT_AUTOSAVE_INTERVAL {
+            let had_error = app.error.is_some();
+            autosave_draft(&mut app);
+            save_stats(&mut app);
+            // Surface a failed save; otherwise nothing on screen changed.
+            needs_redraw |= app.error.is_some() != had_error;
+            last_autosave = Instant::now();
+        }
+    }
+}
+
+/// Picks today's word in the target language; words from a frequency list are translated into
+/// the source language (best effort, the panel still shows without one).
+fn open_word_of_the_day(app: &mut App, provider: &dyn TranslationProvider) {
+    let (source_lang, target_lang) = app.active_pair();
+    let phrases = load_phrases().unwrap_or_default();
+    let frequency = FrequencyList::load(target_lang);
+    let day = day_number(unix_now());
+    let Some(mut word) = pick_word_of_the_day(&phrases, frequency.as_ref(), target_lang, day)
+    else {
+        return;
+    };
+    if word.translation.is_none() {
+        word.translation = translate_cached(
+            &mut app.cache,
+            provider,
+            &word.text,
+            target_lang,
+            source_lang,
+            &TranslateOptions::lookup(),
+        )
+        .ok()
+        .map(|(text, _)| text);
+    }
+    app.word_of_the_day = Some(word);
+}
+
+/// Long documents can take minutes; let the user know once they can come back.
+fn notify_if_slow(notifier: &Option<Notifier>, job: &str, started: Instant, app: &App) {
+    if let Some(notifier) = notifier {
+        notifier.job_finished(job, started.elapsed(), app.error.as_deref());
+    }
+}
+
+/// Writes the panes to the draft file when they changed, removing it once both are empty.
+fn autosave_draft(app: &mut App) {
+    // Don't clobber the previous session's draft before the user answers the restore prompt.
+    if app.draft_prompt.is_some() {
+        return;
+    }
+    let draft = app.draft();
+    if app
+        .saved_draft
+        .as_ref()
+        .is_some_and(|saved| saved.same_content(&draft))
+    {
+        return;
+    }
+    let result = if draft.is_empty() {
+        Draft::discard()
+    } else {
+        draft.save()
+    };
+    match result {
+        Ok(()) => app.saved_draft = Some(draft),
+        Err(message) => app.error = Some(message),
+    }
+}
+
+fn length_limit_from_env() -> Option<usize> {
+    env::var("PTRUI_LENGTH_LIMIT")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|limit| *limit > 0)
+}
+
+/// `PTRUI_SOFT_LIMIT=0` turns the soft limit off.
+fn soft_limit_from_env() -> Option<usize> {
+    match env::var("PTRUI_SOFT_LIMIT") {
+        Ok(value) => value
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|limit| *limit > 0),
+        Err(_) => Some(DEFAULT_SOFT_LIMIT),
+    }
+}
+
+fn schedule_translation(app: &mut App) {
+    app.refresh_counts();
+    app.source_version += 1;
+    app.pending_translation = true;
+    app.last_edit = Some(Instant::now());
+    app.translation_delay = app.debouncer.pause_delay();
+    app.error = None;
+    app.notice = None;
+}
+
+/// A finished translation that was not written because its pane has hand edits.
+pub struct HeldTranslation {
+    request: TranslationRequest,
+    translated: String,
+    provider: String,
+}
+
+/// A translation of the active pane, tagged with the source version it was taken from.
+struct TranslationRequest {
+    version: u64,
+    side: ActiveSide,
+    source_text: String,
+    source_lang: &'static str,
+    target_lang: &'static str,
+    // Language index of the extra target this goes into, instead of the target pane.
+    extra_target: Option<usize>,
+}
+
+/// A request handed to the translation worker and not answered yet.
+struct InFlight {
+    request: TranslationRequest,
+    key: CacheKey,
+    started: Instant,
+}
+
+/// Translates the active pane once the debounce has passed, then any stale extra target:
+/// empty text and cache hits are answered at once, anything else goes to the worker. Returns
+/// whether the screen changed.
+fn maybe_translate(
+    app: &mut App,
+    translator: &Translator,
+    in_flight: &mut Option<InFlight>,
+) -> bool {
+    // The extra targets follow one at a time once the active pane is translated.
+    let extra = if app.pending_translation {
+        let Some(last_edit) = app.last_edit else {
+            return false;
+        };
+        if last_edit.elapsed() < app.translation_delay {
+            return false;
+        }
+        None
+    } else {
+        let Some(request) = app.extra_target_request() else {
+            return false;
+        };
+        Some(request)
+    };
+    // While offline, only probe every so often; edits in between just replace the queued text.
+    if app
+        .offline_since
+        .is_some_and(|_| app.last_attempt.elapsed() < OFFLINE_RETRY_INTERVAL)
+    {
+        return false;
+    }
+    app.last_attempt = Instant::now();
+
+    let (request, bypass, options) = match extra {
+        Some(request) => {
+            let options = app.extra_target_options(request.target_lang);
+            (request, false, options)
+        }
+        None => (
+            app.translation_request(),
+            std::mem::take(&mut app.bypass_cache),
+            app.translate_options(),
+        ),
+    };
+    if request.source_text.trim().is_empty() {
+        return apply_translation(app, request, Ok((String::new(), String::new())));
+    }
+    let key = CacheKey::new(
+        &app.provider,
+        &request.source_text,
+        request.source_lang,
+        request.target_lang,
+        &options,
+    );
+    if !bypass && let Some(translated) = app.cache.get(&key) {
+        return apply_translation(app, request, Ok((translated, CACHE_PROVIDER.to_string())));
+    }
+    let job = TranslationJob {
+        version: request.version,
+        text: request.source_text.clone(),
+        source_lang: request.source_lang,
+        target_lang: request.target_lang,
+        options,
+    };
+    if let Err(message) = translator.send(job) {
+        return apply_translation(app, request, Err(message));
+    }
+    let started = Instant::now();
+    app.translating_since = Some(started);
+    *in_flight = Some(InFlight {
+        request,
+        key,
+        started,
+    });
+    true
+}
+
+/// Takes the worker's answer: caches it (even if the text changed since, it is still a valid
+/// translation of what was sent) and shows it if still current.
+fn finish_translation(app: &mut App, flight: InFlight, finished: Result<TranslationDone, String>) {
+    app.translating_since = None;
+    app.chunk_progress = None;
+    app.last_latency = Some(flight.started.elapsed());
+    let result = finished.and_then(|done| done.result);
+    if let Ok(translated) = &result {
+        app.cache.insert(flight.key, translated.clone());
+    }
+    let result = result.map(|translated| (translated, app.provider.clone()));
+    apply_translation(app, flight.request, result);
+}
+
+/// Shows a translation unless the source changed after the request was made, so a slow or
+/// out-of-order response never overwrites the translation of newer text.
+fn apply_translation(
+    app: &mut App,
+    request: TranslationRequest,
+    result: Result<(String, String), String>,
+) -> bool {
+    if request.version != app.source_version {
+        return false;
+    }
+    if result.is_ok() {
+        app.offline_since = None;
+    }
+    if let Some(language) = request.extra_target {
+        apply_extra_translation(app, request.version, language, result);
+        return true;
+    }
+    match result {
+        Ok((translated, provider)) => {
+            let translated = if request.source_text.trim().is_empty() {
+                translated
+            } else {
+                mask_profanity(&app.profanity, translated)
+            };
+            // Corrections made by hand, and frozen panes, are never replaced without asking.
+            let target_side = request.side.other();
+            if app.hand_edited(target_side) || app.frozen(target_side) {
+                app.held_translation = Some(HeldTranslation {
+                    request,
+                    translated,
+                    provider,
+                });
+                app.pending_translation = false;
+                return true;
+            }
+            write_translation(app, &request, &translated, &provider);
+        }
+        // Keep the translation queued; it is retried with the latest text once back online.
+        Err(message) if is_unreachable(&message) => {
+            app.offline_since.get_or_insert_with(Instant::now);
+            return true;
+        }
+        Err(message) => {
+            app.error = Some(message);
+        }
+    }
+
+    app.pending_translation = false;
+    true
+}
+
+/// Writes a translation into its extra target; one that couldn't reach the API stays stale
+/// and is retried with the offline queue.
+fn apply_extra_translation(
+    app: &mut App,
+    version: u64,
+    language: usize,
+    result: Result<(String, String), String>,
+) {
+    let Some(target) = app
+        .extra_targets
+        .iter_mut()
+        .find(|target| target.language == language)
+    else {
+        return;
+    };
+    match result {
+        Ok((translated, _)) => {
+            target.text = mask_profanity(&app.profanity, translated);
+            target.error = None;
+        }
+        Err(message) if is_unreachable(&message) => {
+            app.offline_since.get_or_insert_with(Instant::now);
+            return;
+        }
+        Err(message) => target.error = Some(message),
+    }
+    target.version = Some(version);
+}
+
+/// Puts a translation into the target pane and records it.
+fn write_translation(
+    app: &mut App,
+    request: &TranslationRequest,
+    translated: &str,
+    provider: &str,
+) {
+    let target_side = request.side.other();
+    let target = app.textarea_mut(target_side);
+    replace_textarea_text(target, translated);
+    let written = textarea_text(target);
+    *app.machine_text_mut(target_side) = Some(written);
+    app.refresh_counts();
+    app.held_translation = None;
+    if request.source_text.trim().is_empty() {
+        return;
+    }
+    app.error = None;
+    app.unsettled = Some(UnsettledTranslation {
+        provider: provider.to_string(),
+        source_text: request.source_text.clone(),
+        target_text: translated.to_string(),
+        source_lang: request.source_lang,
+        target_lang: request.target_lang,
+    });
+}
+
+/// The last live translation written into a pane, waiting for the text to settle.
+struct UnsettledTranslation {
+    provider: String,
+    source_text: String,
+    target_text: String,
+    source_lang: &'static str,
+    target_lang: &'static str,
+}
+
+/// Logs and counts the last live translation once its text has been left alone for `SETTLE_DELAY`, or
+/// right away with `now` (on quit), so a sentence typed with pauses becomes one history
+/// entry and one webhook event rather than one per translated prefix.
+fn settle_translation(app: &mut App, now: bool) {
+    let idle = app
+        .last_edit
+        .is_none_or(|edit| edit.elapsed() >= SETTLE_DELAY);
+    if !now && (app.pending_translation || !idle) {
+        return;
+    }
+    let Some(settled) = app.unsettled.take() else {
+        return;
+    };
+    record_practice(app, settled.source_text.chars().count());
+    record_translation(
+        app,
+        &settled.provider,
+        &settled.source_text,
+        &settled.target_text,
+        settled.source_lang,
+        settled.target_lang,
+    );
+}
+
+fn open_gloss(app: &mut App, provider: &dyn TranslationProvider) {
+    let text = app.active_text();
+    if text.trim().is_empty() {
+        return;
+    }
+    let (source_lang, target_lang) = app.active_pair();
+    match build_gloss(provider, &text, source_lang, target_lang) {
+        Ok(entries) => app.gloss = Some(entries),
+        Err(message) => app.error = Some(message),
+    }
+}
+
+fn open_dictionary(app: &mut App, client: &reqwest::blocking::Client) {
+    let textarea = app.active_textarea();
+    let word = selected_text(textarea).or_else(|| word_at_cursor(textarea));
+    let Some(word) = word
+        .map(|word| word.trim().to_string())
+        .filter(|w| !w.is_empty())
+    else {
+        return;
+    };
+    let (language, _) = app.active_pair();
+    match lookup_wiktionary(client, &word, language) {
+        Ok(entries) => {
+            app.dictionary = Some(DictionaryPanel {
+                word,
+                language,
+                entries,
+                scroll: 0,
+            })
+        }
+        Err(message) => app.error = Some(message),
+    }
+}
+
+/// Loads a web page's readable text into the left pane and translates it.
+fn fetch_into_source(app: &mut App, client: &reqwest::blocking::Client, url: &str) {
+    match fetch_readable_text(client, url) {
+        Ok(text) => app.load_source_text(&text, format!("Fetched {}", url)),
+        Err(message) => app.error = Some(message),
+    }
+}
+
+fn open_suggestions(app: &mut App, client: &reqwest::blocking::Client) {
+    let Some(original) = selected_text(app.active_textarea()) else {
+        app.error = Some("Select text in visual mode to get suggestions".to_string());
+        return;
+    };
+    let original = original.trim().to_string();
+    if original.is_empty() {
+        return;
+    }
+    let (language, _) = app.active_pair();
+    match suggest_synonyms(client, &original, language) {
+        Ok(suggestions) if suggestions.is_empty() => {
+            app.error = Some(format!("No suggestions for \"{}\"", original));
+        }
+        Ok(suggestions) => {
+            app.suggestions = Some(SuggestionPicker {
+                original,
+                suggestions,
+                selected: 0,
+            })
+        }
+        Err(message) => app.error = Some(message),
+    }
+}
+
+/// Opens the alternatives picker and translates the active pane with each variation of the
+/// settings on the lookup worker; distinct results join the picker as they arrive.
+fn start_alternatives(app: &mut App, lookups: &Lookups<PendingLookup>) {
+    let text = app.active_text();
+    if text.trim().is_empty() {
+        return;
+    }
+    let (source_lang, target_lang) = app.active_pair();
+    let variations = variations(app.translate_options(), target_lang);
+    app.alternatives = Some(AlternativePicker {
+        candidates: Vec::new(),
+        selected: 0,
+        version: app.source_version,
+        pending: variations.len(),
+    });
+    for (label, options) in variations {
+        let tag = PendingLookup {
+            version: app.source_version,
+            key: CacheKey::new(&app.provider, &text, source_lang, target_lang, &options),
+            started: Instant::now(),
+            purpose: LookupPurpose::Alternative { label },
+        };
+        let job = LookupJob {
+            tag,
+            text: text.clone(),
+            source_lang,
+            target_lang,
+            options,
+        };
+        send_lookup(app, lookups, job);
+    }
+}
+
+/// Points the app at another provider and returns workers for it; pending work is translated
+/// again, and lookups still on their way are dropped.
+fn switch_provider(
+    app: &mut App,
+    provider: &Arc<dyn TranslationProvider>,
+    chunk_chars: usize,
+) -> (Translator, Lookups<PendingLookup>) {
+    app.translating_since = None;
+    app.drop_lookups();
+    app.provider = provider.name();
+    schedule_translation(app);
+    (
+        Translator::for_provider(Arc::clone(provider), chunk_chars),
+        Lookups::for_provider(Arc::clone(provider), chunk_chars),
+    )
+}
+
+fn open_quality(app: &mut App, provider: &dyn TranslationProvider) {
+    let source = app.active_text();
+    let target = textarea_text(app.target_textarea());
+    if source.trim().is_empty() || target.trim().is_empty() {
+        return;
+    }
+    let (source_lang, target_lang) = app.active_pair();
+    match estimate_quality(provider, &source, &target, source_lang, target_lang) {
+        Ok(segments) => {
+            let marks = QualityMarks::new(app.target_textarea().lines(), segments);
+            app.notice = Some(match marks.flagged() {
+                0 => "Every sentence round-trips well".to_string(),
+                flagged => format!(
+                    "{} of {} sentences need review (marked ◐ or ○)",
+                    flagged,
+                    marks.segments.len()
+                ),
+            });
+            app.quality = Some(marks);
+        }
+        Err(message) => app.error = Some(message),
+    }
+}
+
+/// A lookup sent to the lookup worker, with the source version and cache key it was sent at.
+struct PendingLookup {
+    version: u64,
+    key: CacheKey,
+    started: Instant,
+    purpose: LookupPurpose,
+}
+
+/// What a lookup's answer is for.
+enum LookupPurpose {
+    // Diffed against `source` in a view with this title.
+    BackTranslation { source: String, title: String },
+    // A candidate for the alternatives picker, produced by the settings `label` names.
+    Alternative { label: String },
+}
+
+/// Answers a lookup from the cache, or hands it to the worker.
+fn send_lookup(app: &mut App, lookups: &Lookups<PendingLookup>, job: LookupJob<PendingLookup>) {
+    if let Some(cached) = app.cache.get(&job.tag.key) {
+        let result = Ok(cached);
+        finish_lookup(
+            app,
+            LookupDone {
+                tag: job.tag,
+                result,
+            },
+        );
+    } else if let Err(message) = lookups.send(job) {
+        // Nothing else is coming from a stopped worker either.
+        app.error = Some(message);
+        app.drop_lookups();
+    }
+}
+
+/// Translates the target pane back into the source language on the lookup worker; the diff
+/// view on the original text against that round trip opens once it arrives.
+fn start_back_translation(app: &mut App, lookups: &Lookups<PendingLookup>) {
+    let source = app.active_text();
+    let target = textarea_text(app.target_textarea());
+    if target.trim().is_empty() {
+        app.error = Some("No translation to translate back".to_string());
+        return;
+    }
+    let (source_lang, target_lang) = app.active_pair();
+    let options = TranslateOptions::default();
+    let tag = PendingLookup {
+        version: app.source_version,
+        key: CacheKey::new(&app.provider, &target, target_lang, source_lang, &options),
+        started: Instant::now(),
+        purpose: LookupPurpose::BackTranslation {
+            source,
+            title: format!(
+                "Back-translation {} → {} vs the original",
+                target_lang, source_lang
+            ),
+        },
+    };
+    app.back_translating = Some(app.source_version);
+    let job = LookupJob {
+        tag,
+        text: target,
+        source_lang: target_lang,
+        target_lang: source_lang,
+        options,
+    };
+    send_lookup(app, lookups, job);
+}
+
+/// Takes a lookup's answer: caches it and shows it, unless the text changed since it was
+/// asked for.
+fn finish_lookup(app: &mut App, done: LookupDone<PendingLookup>) {
+    let PendingLookup {
+        version,
+        key,
+        purpose,
+        ..
+    } = done.tag;
+    if let Ok(translated) = &done.result {
+        app.cache.insert(key, translated.clone());
+    }
+    let current = version == app.source_version;
+    match purpose {
+        LookupPurpose::BackTranslation { source, title } => {
+            if app.back_translating != Some(version) {
+                return;
+            }
+            app.back_translating = None;
+            match done.result {
+                Ok(back_translation) if current => {
+                    app.diff_view = Some(DiffView {
+                        title,
+                        changes: word_diff(&source, &back_translation),
+                        scroll: 0,
+                    });
+                }
+                Ok(_) => {}
+                Err(message) => app.error = Some(message),
+            }
+        }
+        LookupPurpose::Alternative { label } => {
+            let Some(picker) = app
+                .alternatives
+                .as_mut()
+                .filter(|picker| picker.version == version)
+            else {
+                return;
+            };
+            picker.pending = picker.pending.saturating_sub(1);
+            // A failed variation costs only its own candidate.
+            match done.result {
+                Ok(translated) => picker.add(Alternative {
+                    label,
+                    text: mask_profanity(&app.profanity, translated),
+                }),
+                Err(message) => app.error = Some(message),
+            }
+            if picker.pending == 0 && picker.candidates.is_empty() {
+                app.alternatives = None;
+            }
+        }
+    }
+}
+
+fn speak_translation(app: &mut App, speech: &SpeechSettings) {
+    let text = textarea_text(app.target_textarea());
+    if text.trim().is_empty() {
+        return;
+    }
+    let (_, target_lang) = app.active_pair();
+    if let Err(message) = speak(speech, &text, target_lang) {
+        app.error = Some(message);
+    }
+}
+
+fn mask_profanity(filter: &Option<ProfanityFilter>, text: String) -> String {
+    match filter {
+        Some(filter) => filter.mask(&text),
+        None => text,
+    }
+}
+
+/// Logs a completed translation to history and reports it to the webhook. The mock
+/// provider's reversed text is neither: it isn't a translation.
+fn record_translation(
+    app: &mut App,
+    provider: &str,
+    source_text: &str,
+    target_text: &str,
+    source_lang: &str,
+    target_lang: &str,
+) {
+    if app.provider == MOCK_NAME {
+        return;
+    }
+    notify_webhook(
+        app,
+        provider,
+        source_text,
+        target_text,
+        source_lang,
+        target_lang,
+    );
+    record_history(
+        app,
+        provider,
+        source_text,
+        target_text,
+        source_lang,
+        target_lang,
+    );
+}
+
+fn notify_webhook(
+    app: &App,
+    provider: &str,
+    source_text: &str,
+    target_text: &str,
+    source_lang: &str,
+    target_lang: &str,
+) {
+    if let Some(webhook) = &app.webhook {
+        webhook.send(&TranslationEvent {
+            source_text,
+            target_text,
+            source_lang,
+            target_lang,
+            provider,
+        });
+    }
+}
+
+fn record_history(
+    app: &mut App,
+    provider: &str,
+    source_text: &str,
+    target_text: &str,
+    source_lang: &str,
+    target_lang: &str,
+) {
+    let Some(history) = &app.history else {
+        return;
+    };
+    if let Err(message) =
+        history.record(source_text, target_text, source_lang, target_lang, provider)
+    {
+        app.error = Some(message);
+    }
+}
+
+fn record_practice(app: &mut App, chars: usize) {
+    app.stats.record_chars(chars);
+    app.stats_changed = true;
+}
+
+/// Writes the practice counters when they changed since the last save.
+fn save_stats(app: &mut App) {
+    if !std::mem::take(&mut app.stats_changed) {
+        return;
+    }
+    if let Err(message) = app.stats.save() {
+        app.error = Some(message);
+    }
+}
+
+fn nativeize_both(app: &mut App, provider: &dyn TranslationProvider) {
+    let left_lang = languages()
+        .get(app.left_language)
+        .unwrap_or(&languages()[0]);
+    let right_lang = languages()
+        .get(app.right_language)
+        .unwrap_or(&languages()[0]);
+    let left_source = textarea_text(&app.input);
+    let right_source = textarea_text(&app.output);
+    if left_source.trim().is_empty() && right_source.trim().is_empty() {
+        return;
+    }
+
+    let into_right = app.options_into(ActiveSide::Right);
+    let into_left = app.options_into(ActiveSide::Left);
+    let mut new_left = left_source.clone();
+    let mut new_right = right_source.clone();
+    let mut error_message = None;
+    let mut translated_chars = 0;
+
+    if !left_source.trim().is_empty() {
+        let result = translate_cached(
+            &mut app.cache,
+            provider,
+            &left_source,
+            left_lang.code,
+            right_lang.code,
+            &into_right,
+        );
+        match result {
+            Ok((translated, provider)) => {
+                new_right = mask_profanity(&app.profanity, translated);
+                translated_chars += left_source.chars().count();
+                record_translation(
+                    app,
+                    &provider,
+                    &left_source,
+                    &new_right,
+                    left_lang.code,
+                    right_lang.code,
+                );
+            }
+            Err(message) => error_message = Some(message),
+        }
+    }
+    if !right_source.trim().is_empty() {
+        let result = translate_cached(
+            &mut app.cache,
+            provider,
+            &right_source,
+            right_lang.code,
+            left_lang.code,
+            &into_left,
+        );
+        match result {
+            Ok((translated, provider)) => {
+                new_left = mask_profanity(&app.profanity, translated);
+                translated_chars += right_source.chars().count();
+                record_translation(
+                    app,
+                    &provider,
+                    &right_source,
+                    &new_left,
+                    right_lang.code,
+                    left_lang.code,
+                );
+            }
+            Err(message) => {
+                if error_message.is_none() {
+                    error_message = Some(message);
+                }
+            }
+        }
+    }
+
+    replace_textarea_text(&mut app.input, &new_left);
+    replace_textarea_text(&mut app.output, &new_right);
+    app.refresh_counts();
+    if translated_chars > 0 {
+        record_practice(app, translated_chars);
+    }
+    app.error = error_message.or(app.error.take());
+    app.pending_translation = false;
+    app.last_edit = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::DEFAULT_CHUNK_CHARS;
+    use crate::mock::MockProvider;
+    use crossterm::event::KeyEvent;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn wait_for_lookup(lookups: &Lookups<PendingLookup>) -> LookupDone<PendingLookup> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            match lookups.try_recv() {
+                Some(done) => return done.unwrap(),
+                None if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(5)),
+                None => panic!("no lookup finished"),
+            }
+        }
+    }
+
+    #[test]
+    fn tab_switches_active_side() {
+        let mut app = App::new(&Config::default());
+        assert_eq!(app.active, ActiveSide::Left);
+        app.handle_key(press(KeyCode::Tab, KeyModifiers::NONE));
         assert_eq!(app.active, ActiveSide::Right);
     }
 
     #[test]
     fn ctrl_c_requests_quit() {
-        let mut app = App::new();
+        let mut app = App::new(&Config::default());
         let action = app.handle_key(press(KeyCode::Char('c'), KeyModifiers::CONTROL));
         assert_eq!(action, AppAction::Quit);
     }
 
     #[test]
     fn ctrl_r_clears_active_side() {
-        let mut app = App::new();
+        let mut app = App::new(&Config::default());
         app.input = TextArea::from(["hello"]);
         app.handle_key(press(KeyCode::Char('r'), KeyModifiers::CONTROL));
         assert_eq!(textarea_text(&app.input), "");
@@ -361,7 +3481,7 @@ mod tests {
 
     #[test]
     fn typing_schedules_translation_left_to_right() {
-        let mut app = App::new();
+        let mut app = App::new(&Config::default());
         app.handle_key(press(KeyCode::Char('i'), KeyModifiers::NONE));
         app.handle_key(press(KeyCode::Char('h'), KeyModifiers::NONE));
         app.handle_key(press(KeyCode::Char('e'), KeyModifiers::NONE));
@@ -372,9 +3492,525 @@ mod tests {
         assert!(app.pending_translation);
     }
 
+    #[test]
+    fn stale_translations_are_dropped() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["hello"]);
+        let stale = app.translation_request();
+        app.handle_key(press(KeyCode::Char('x'), KeyModifiers::NONE));
+        let result = Ok(("hola".to_string(), "test".to_string()));
+        assert!(!apply_translation(&mut app, stale, result.clone()));
+        assert_eq!(textarea_text(&app.output), "");
+        let current = app.translation_request();
+        assert!(apply_translation(&mut app, current, result));
+        assert_eq!(textarea_text(&app.output), "hola");
+    }
+
+    #[test]
+    fn translates_typed_text_through_the_worker() {
+        let config = Config {
+            debounce_ms: Some(0),
+            ..Config::default()
+        };
+        let mut app = App::new(&config);
+        let translator = Translator::for_provider(Arc::new(MockProvider), DEFAULT_CHUNK_CHARS);
+        let mut in_flight = None;
+        for c in "ihola".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert!(maybe_translate(&mut app, &translator, &mut in_flight));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let finished = loop {
+            match translator.try_recv() {
+                Some(finished) => break finished,
+                None if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(5)),
+                None => panic!("no translation finished"),
+            }
+        };
+        finish_translation(&mut app, in_flight.take().unwrap(), finished);
+        assert_eq!(textarea_text(&app.output), "aloh");
+        assert!(!app.pending_translation);
+    }
+
+    #[test]
+    fn restores_panes_and_vim_modes() {
+        let mut app = App::new(&Config::default());
+        for c in "ihola".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        let draft = app.draft();
+        assert!(draft.left_insert && !draft.right_insert);
+
+        let mut restored = App::new(&Config::default());
+        restored.restore_draft(&draft);
+        assert_eq!(textarea_text(&restored.input), "hola");
+        assert_eq!(restored.left_vim.mode, Mode::Insert);
+        assert_eq!(restored.right_vim.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn rotates_and_resizes_the_panes() {
+        let mut app = App::new(&Config::default());
+        let alt = |c| press(KeyCode::Char(c), KeyModifiers::ALT);
+        assert!(matches!(app.handle_key(alt('l')), AppAction::SaveLayout));
+        app.handle_key(alt('='));
+        assert_eq!(
+            app.layout,
+            PaneLayout {
+                stacked: true,
+                split: 55
+            }
+        );
+    }
+
+    #[test]
+    fn theme_picker_previews_and_cancels() {
+        let mut colors = BTreeMap::new();
+        colors.insert("muted".to_string(), "gray".to_string());
+        let mut app = App::new(&Config {
+            colors,
+            ..Config::default()
+        });
+        app.execute_command("theme");
+        app.handle_key(press(KeyCode::Down, KeyModifiers::NONE));
+        assert_eq!(app.theme.name, "light");
+        assert_eq!(app.theme.muted, ratatui::style::Color::Gray);
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(app.theme.name, "default");
+        assert!(matches!(
+            app.execute_command("theme gruvbox"),
+            AppAction::SaveTheme
+        ));
+        assert_eq!(app.theme.name, "gruvbox");
+    }
+
+    #[test]
+    fn external_edits_are_translated_and_undoable() {
+        let mut app = App::new(&Config::default());
+        app.apply_external_edit("hola\n");
+        assert_eq!(app.active_text(), "hola");
+        assert!(app.pending_translation);
+        app.active_textarea_mut().undo();
+        assert_eq!(app.active_text(), "");
+        app.apply_external_edit("uno\r\ndos\r\n");
+        assert_eq!(app.active_text(), "uno\ndos");
+    }
+
+    #[test]
+    fn toasts_expire() {
+        let mut app = App::new(&Config::default());
+        app.handle_key(press(KeyCode::Char('o'), KeyModifiers::CONTROL));
+        assert_eq!(app.error.as_deref(), Some("Nothing to copy"));
+        app.show_toast("copied!");
+        assert!(app.next_deadline().is_some());
+        assert!(!app.expire_toast());
+        app.toast = Some(("copied!".to_string(), Instant::now()));
+        assert!(app.expire_toast());
+        assert!(app.toast.is_none());
+    }
+
+    #[test]
+    fn unreachable_api_queues_translation() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["hello"]);
+        schedule_translation(&mut app);
+        let request = app.translation_request();
+        let offline = Err("Translation API unreachable: dns error".to_string());
+        apply_translation(&mut app, request, offline);
+        assert!(app.offline_since.is_some());
+        assert!(app.pending_translation);
+        assert!(app.error.is_none());
+        let request = app.translation_request();
+        apply_translation(
+            &mut app,
+            request,
+            Ok(("hola".to_string(), "test".to_string())),
+        );
+        assert!(app.offline_since.is_none());
+        assert!(!app.pending_translation);
+    }
+
+    #[test]
+    fn loading_panes_drops_the_translation_in_flight() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["hello"]);
+        schedule_translation(&mut app);
+        let request = app.translation_request();
+        let entry = HistoryEntry {
+            id: 1,
+            source_text: "good night".to_string(),
+            target_text: "buenas noches".to_string(),
+            source_lang: "EN".to_string(),
+            target_lang: "ES".to_string(),
+            provider: "test".to_string(),
+            created_at: 0,
+            pinned: false,
+            tags: Vec::new(),
+        };
+        app.load_history_entry(&entry);
+        let late = Ok(("hola".to_string(), "test".to_string()));
+        assert!(!apply_translation(&mut app, request, late));
+        assert_eq!(app.output.lines(), ["buenas noches"]);
+
+        schedule_translation(&mut app);
+        let request = app.translation_request();
+        let draft = Draft {
+            left_text: "thanks".to_string(),
+            right_text: "gracias".to_string(),
+            ..app.draft()
+        };
+        app.restore_draft(&draft);
+        let late = Ok(("buenas noches".to_string(), "test".to_string()));
+        assert!(!apply_translation(&mut app, request, late));
+        assert_eq!(app.output.lines(), ["gracias"]);
+    }
+
+    #[test]
+    fn pastes_as_one_edit_in_insert_mode_only() {
+        let mut app = App::new(&Config::default());
+        app.handle_paste("dd");
+        assert!(app.error.is_some());
+        assert_eq!(app.input.lines(), [""]);
+        app.handle_key(press(KeyCode::Char('i'), KeyModifiers::NONE));
+        app.handle_paste("hola\r\nmundo");
+        assert_eq!(app.input.lines(), ["hola", "mundo"]);
+        assert!(app.pending_translation);
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        app.handle_key(press(KeyCode::Char('u'), KeyModifiers::NONE));
+        assert_eq!(app.input.lines(), [""]);
+    }
+
+    #[test]
+    fn formality_applies_to_the_target_pane() {
+        let mut app = App::new(&Config::default());
+        app.handle_key(press(KeyCode::Char('f'), KeyModifiers::ALT));
+        assert_eq!(app.right_formality, Formality::More);
+        assert_eq!(app.translate_options().formality, Some("more"));
+        assert_eq!(app.notice, None);
+        // English has no formal register, so nothing is sent.
+        app.handle_key(press(KeyCode::Tab, KeyModifiers::NONE));
+        app.handle_key(press(KeyCode::Char('f'), KeyModifiers::ALT));
+        assert_eq!(app.left_formality, Formality::More);
+        assert_eq!(app.translate_options().formality, None);
+        assert!(app.notice.is_some());
+    }
+
+    #[test]
+    fn ctrl_y_recalls_a_past_translation() {
+        let mut app = App::new(&Config::default());
+        let store = HistoryStore::open_in_memory(10).unwrap();
+        store
+            .record("good night", "buenas noches", "EN", "ES", "test")
+            .unwrap();
+        app.history = Some(store);
+        app.handle_key(press(KeyCode::Char('y'), KeyModifiers::CONTROL));
+        assert!(app.history_finder.is_some());
+        app.handle_key(press(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(app.history_finder.is_none());
+        assert_eq!(app.input.lines(), ["good night"]);
+        assert_eq!(app.output.lines(), ["buenas noches"]);
+    }
+
+    #[test]
+    fn counts_follow_pane_edits() {
+        let mut app = App::new(&Config::default());
+        app.handle_key(press(KeyCode::Char('i'), KeyModifiers::NONE));
+        for c in "hi there".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(app.left_counts, TextCounts { words: 2, chars: 8 });
+        let request = app.translation_request();
+        apply_translation(
+            &mut app,
+            request,
+            Ok(("hola".to_string(), "test".to_string())),
+        );
+        assert_eq!(app.right_counts, TextCounts { words: 1, chars: 4 });
+        app.swap_text();
+        assert_eq!(app.left_counts, TextCounts { words: 1, chars: 4 });
+    }
+
+    #[test]
+    fn error_details_outlive_the_cleared_error() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["hello"]);
+        schedule_translation(&mut app);
+        let request = app.translation_request();
+        apply_translation(&mut app, request, Err("HTTP 500: boom".to_string()));
+        app.handle_key(press(KeyCode::Char('e'), KeyModifiers::ALT));
+        schedule_translation(&mut app);
+        assert!(app.error.is_none());
+        assert_eq!(
+            app.error_details.as_ref().unwrap().message,
+            "HTTP 500: boom"
+        );
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(app.error_details.is_none());
+    }
+
+    #[test]
+    fn only_settled_translations_are_logged() {
+        let mut app = App::new(&Config::default());
+        app.history = Some(HistoryStore::open_in_memory(10).unwrap());
+        for (source, translated) in [("good", "bueno"), ("good night", "buenas noches")] {
+            app.input = TextArea::from([source]);
+            schedule_translation(&mut app);
+            let request = app.translation_request();
+            apply_translation(
+                &mut app,
+                request,
+                Ok((translated.to_string(), "test".to_string())),
+            );
+            settle_translation(&mut app, false);
+        }
+        let logged = |app: &App| app.history.as_ref().unwrap().recent(10).unwrap();
+        assert!(logged(&app).is_empty());
+        app.last_edit = Some(Instant::now() - SETTLE_DELAY);
+        settle_translation(&mut app, false);
+        let entries = logged(&app);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].target_text, "buenas noches");
+        let today = app.stats.day(day_number(unix_now()));
+        assert_eq!(today.chars_translated, 10);
+        // The mock provider's output is never logged.
+        app.provider = MOCK_NAME.to_string();
+        let request = app.translation_request();
+        apply_translation(
+            &mut app,
+            request,
+            Ok(("sehcon".to_string(), "mock".to_string())),
+        );
+        settle_translation(&mut app, true);
+        assert_eq!(logged(&app).len(), 1);
+    }
+
+    #[test]
+    fn revealed_flashcards_count_as_reviewed() {
+        let mut app = App::new(&Config::default());
+        let card = |source: &str| PhraseEntry::new(source, "target", "EN", "ES", None);
+        app.review = Some(Review::new(vec![card("one"), card("two")]));
+        let space = press(KeyCode::Char(' '), KeyModifiers::NONE);
+        // Reveal, next, reveal, then step back and reveal the first card again.
+        for key in [
+            space,
+            space,
+            space,
+            press(KeyCode::Char('h'), KeyModifiers::NONE),
+            space,
+        ] {
+            app.handle_key(key);
+        }
+        assert!(app.review.as_ref().unwrap().revealed);
+        assert_eq!(app.stats.day(day_number(unix_now())).cards_reviewed, 3);
+        assert!(app.stats_changed);
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(app.review.is_none());
+    }
+
+    #[test]
+    fn function_keys_work_in_insert_mode() {
+        let config = Config {
+            keys: FunctionKeys::bindings("F2=cycle-tone,F3=toggle-layout,F4=cycle-provider")
+                .unwrap(),
+            ..Config::default()
+        };
+        let mut app = App::new(&config);
+        app.handle_key(press(KeyCode::Char('i'), KeyModifiers::NONE));
+        app.handle_key(press(KeyCode::F(2), KeyModifiers::NONE));
+        assert_eq!(app.tone, Tone::Formal);
+        assert!(app.pending_translation);
+        let layout = app.layout.name();
+        assert!(matches!(
+            app.handle_key(press(KeyCode::F(3), KeyModifiers::NONE)),
+            AppAction::SaveLayout
+        ));
+        assert_ne!(app.layout.name(), layout);
+        assert!(matches!(
+            app.handle_key(press(KeyCode::F(4), KeyModifiers::NONE)),
+            AppAction::CycleProvider
+        ));
+    }
+
+    #[test]
+    fn holds_translations_that_would_replace_hand_edits() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["hello"]);
+        let translate = |app: &mut App, text: &str| {
+            let request = app.translation_request();
+            apply_translation(app, request, Ok((text.to_string(), "test".to_string())));
+        };
+        translate(&mut app, "hola");
+        app.output = TextArea::from(["¡hola!"]);
+        assert!(app.hand_edited(ActiveSide::Right));
+        translate(&mut app, "hola");
+        assert_eq!(app.output.lines(), ["¡hola!"]);
+        assert!(app.held_translation.is_some());
+        app.handle_key(press(KeyCode::Char('y'), KeyModifiers::ALT));
+        assert_eq!(app.output.lines(), ["hola"]);
+        assert!(!app.hand_edited(ActiveSide::Right));
+
+        // A frozen pane holds even translations it hasn't been edited since.
+        app.handle_key(press(KeyCode::Tab, KeyModifiers::NONE));
+        app.execute_command("freeze");
+        app.handle_key(press(KeyCode::Tab, KeyModifiers::NONE));
+        assert!(app.frozen(ActiveSide::Right));
+        translate(&mut app, "buenas");
+        assert_eq!(app.output.lines(), ["hola"]);
+        assert!(app.held_translation.is_some());
+    }
+
+    #[test]
+    fn extra_targets_translate_the_left_pane() {
+        let mut app = App::new(&Config::default());
+        app.handle_key(press(KeyCode::Char('l'), KeyModifiers::CONTROL));
+        for c in "French".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        app.handle_key(press(KeyCode::Tab, KeyModifiers::NONE));
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        let french = find_language_index("FR").unwrap();
+        assert_eq!(app.extra_targets.len(), 1);
+        assert_eq!(app.extra_targets[0].language, french);
+        app.toggle_extra_target(app.right_language);
+        assert!(app.error.is_some());
+
+        // The target pane is translated first, then each extra target through the same worker.
+        let translator = Translator::for_provider(Arc::new(MockProvider), DEFAULT_CHUNK_CHARS);
+        let mut in_flight = None;
+        app.input = TextArea::from(["hola"]);
+        schedule_translation(&mut app);
+        app.translation_delay = Duration::ZERO;
+        for extra_target in [None, Some(french)] {
+            assert!(maybe_translate(&mut app, &translator, &mut in_flight));
+            let flight = in_flight.take().unwrap();
+            assert_eq!(flight.request.extra_target, extra_target);
+            let deadline = Instant::now() + Duration::from_secs(5);
+            let finished = loop {
+                match translator.try_recv() {
+                    Some(finished) => break finished,
+                    None if Instant::now() < deadline => {
+                        std::thread::sleep(Duration::from_millis(5))
+                    }
+                    None => panic!("no translation finished"),
+                }
+            };
+            finish_translation(&mut app, flight, finished);
+        }
+        assert_eq!(textarea_text(&app.output), "aloh");
+        assert_eq!(app.extra_targets[0].text, "aloh");
+        assert!(!maybe_translate(&mut app, &translator, &mut in_flight));
+
+        app.toggle_extra_target(french);
+        assert!(app.extra_targets.is_empty());
+    }
+
+    #[test]
+    fn diffs_hand_edits_against_the_machine_translation() {
+        let mut app = App::new(&Config::default());
+        app.execute_command("diff");
+        assert!(app.error.is_some());
+        app.input = TextArea::from(["good morning"]);
+        let request = app.translation_request();
+        apply_translation(
+            &mut app,
+            request,
+            Ok(("buen día".to_string(), "test".to_string())),
+        );
+        app.output = TextArea::from(["buenos días"]);
+        app.handle_key(press(KeyCode::Char('D'), KeyModifiers::ALT));
+        let view = app.diff_view.as_ref().unwrap();
+        assert_eq!(view.counts(), (2, 2));
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(app.diff_view.is_none());
+    }
+
+    #[test]
+    fn back_translation_is_diffed_against_the_original() {
+        let mut app = App::new(&Config::default());
+        assert_eq!(
+            app.handle_key(press(KeyCode::Char('B'), KeyModifiers::ALT)),
+            AppAction::BackTranslate
+        );
+        let lookups = Lookups::for_provider(Arc::new(MockProvider), DEFAULT_CHUNK_CHARS);
+        start_back_translation(&mut app, &lookups);
+        assert!(app.error.is_some());
+        app.input = TextArea::from(["buenos días"]);
+        // The mock reverses text, so this comes back as "buenos noches".
+        app.output = TextArea::from(["sehcon soneub"]);
+        start_back_translation(&mut app, &lookups);
+        assert!(app.back_translating.is_some());
+        assert!(app.diff_view.is_none());
+        finish_lookup(&mut app, wait_for_lookup(&lookups));
+        assert_eq!(app.back_translating, None);
+        let view = app.diff_view.as_ref().unwrap();
+        assert_eq!(view.counts(), (1, 1));
+        assert_eq!(view.title, "Back-translation ES → EN vs the original");
+
+        // An answer for text that was edited meanwhile isn't shown.
+        app.diff_view = None;
+        app.output = TextArea::from(["sehcon saneub"]);
+        start_back_translation(&mut app, &lookups);
+        app.source_edited(false);
+        finish_lookup(&mut app, wait_for_lookup(&lookups));
+        assert!(app.diff_view.is_none());
+        assert_eq!(app.back_translating, None);
+    }
+
+    #[test]
+    fn alternatives_arrive_one_by_one_and_survive_a_failure() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["hola"]);
+        // The tone variations fail; the others differ by their formality.
+        let lookups = Lookups::spawn(|job: &LookupJob<PendingLookup>| match job.options {
+            TranslateOptions { tone: Some(_), .. } => Err("tone rejected".to_string()),
+            TranslateOptions { formality, .. } => Ok(format!("{} {:?}", job.text, formality)),
+        });
+        start_alternatives(&mut app, &lookups);
+        let sent = app.alternatives.as_ref().unwrap().pending;
+        assert!(app.alternatives.as_ref().unwrap().candidates.is_empty());
+        finish_lookup(&mut app, wait_for_lookup(&lookups));
+        let picker = app.alternatives.as_ref().unwrap();
+        assert_eq!(picker.candidates.len(), 1);
+        assert_eq!(picker.pending, sent - 1);
+        for _ in 1..sent {
+            finish_lookup(&mut app, wait_for_lookup(&lookups));
+        }
+        let picker = app.alternatives.as_ref().unwrap();
+        assert_eq!(picker.pending, 0);
+        assert_eq!(picker.candidates.len(), sent - 2);
+        assert_eq!(picker.candidates[0].label, "current settings");
+        assert_eq!(app.error.as_deref(), Some("tone rejected"));
+    }
+
+    #[test]
+    fn swapping_text_does_not_translate() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["hola"]);
+        app.output = TextArea::from(["hello"]);
+        app.pending_translation = true;
+        app.handle_key(press(KeyCode::Char('k'), KeyModifiers::ALT));
+        assert_eq!(app.input.lines(), ["hello"]);
+        assert_eq!(app.output.lines(), ["hola"]);
+        assert!(!app.pending_translation);
+    }
+
+    #[test]
+    fn soft_limit_pauses_live_translation() {
+        let mut app = App::new(&Config::default());
+        app.soft_limit = Some(3);
+        app.handle_key(press(KeyCode::Char('i'), KeyModifiers::NONE));
+        for c in "hola".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert!(!app.pending_translation);
+        assert!(app.error.is_some());
+        app.execute_command("translate");
+        assert!(app.pending_translation);
+    }
+
     #[test]
     fn typing_schedules_translation_right_to_left() {
-        let mut app = App::new();
+        let mut app = App::new(&Config::default());
         app.handle_key(press(KeyCode::Tab, KeyModifiers::NONE));
         app.handle_key(press(KeyCode::Char('i'), KeyModifiers::NONE));
         app.handle_key(press(KeyCode::Char('h'), KeyModifiers::NONE));
@@ -387,7 +4023,7 @@ mod tests {
 
     #[test]
     fn backspace_schedules_translation() {
-        let mut app = App::new();
+        let mut app = App::new(&Config::default());
         app.handle_key(press(KeyCode::Char('i'), KeyModifiers::NONE));
         app.handle_key(press(KeyCode::Char('h'), KeyModifiers::NONE));
         app.handle_key(press(KeyCode::Char('e'), KeyModifiers::NONE));
@@ -398,4 +4034,263 @@ mod tests {
         assert_eq!(textarea_text(&app.input), "hell");
         assert!(app.pending_translation);
     }
+
+    #[test]
+    fn alt_t_cycles_tone_and_retranslates() {
+        let mut app = App::new(&Config::default());
+        assert_eq!(app.tone, Tone::Neutral);
+        app.handle_key(press(KeyCode::Char('t'), KeyModifiers::ALT));
+        assert_eq!(app.tone, Tone::Formal);
+        assert!(app.pending_translation);
+        assert!(app.translate_options().tone.is_some());
+    }
+
+    #[test]
+    fn gloss_view_captures_keys_until_closed() {
+        let mut app = App::new(&Config::default());
+        let action = app.handle_key(press(KeyCode::Char('g'), KeyModifiers::ALT));
+        assert_eq!(action, AppAction::Gloss);
+        app.gloss = Some(Vec::new());
+        app.handle_key(press(KeyCode::Char('i'), KeyModifiers::NONE));
+        assert_eq!(app.active_mode(), Mode::Normal);
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(app.gloss.is_none());
+    }
+
+    #[test]
+    fn command_line_runs_fetch() {
+        let mut app = App::new(&Config::default());
+        app.handle_key(press(KeyCode::Char(':'), KeyModifiers::NONE));
+        for c in "fetch https://example.com".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        let action = app.handle_key(press(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(action, AppAction::Fetch("https://example.com".to_string()));
+        assert!(app.command_line.is_none());
+        assert!(textarea_text(&app.input).is_empty());
+    }
+
+    #[test]
+    fn counts_repeat_motions_and_operators() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["one two three four", "a", "b", "c"]);
+        for c in "3w".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(app.input.cursor(), (0, 14));
+        for c in "j2dd".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(textarea_text(&app.input), "one two three four\nc");
+        for c in "kd3w".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(textarea_text(&app.input), "four\nc");
+        assert_eq!(app.left_vim.count, 0);
+        // Huge counts are capped and motions stop at the edge of the text.
+        for c in "999999999".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(app.left_vim.count, 10_000);
+        app.handle_key(press(KeyCode::Char('j'), KeyModifiers::NONE));
+        assert_eq!(app.input.cursor().0, 1);
+    }
+
+    #[test]
+    fn only_a_paste_command_reads_the_clipboard() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["abc"]);
+        let p = textarea_input_from_key(press(KeyCode::Char('p'), KeyModifiers::NONE));
+        assert!(app.left_vim.is_paste(&p));
+        app.handle_key(press(KeyCode::Char('r'), KeyModifiers::NONE));
+        assert!(!app.left_vim.is_paste(&p));
+        app.handle_key(press(KeyCode::Char('p'), KeyModifiers::NONE));
+        assert_eq!(textarea_text(&app.input), "pbc");
+        app.handle_key(press(KeyCode::Char('i'), KeyModifiers::NONE));
+        assert!(!app.left_vim.is_paste(&p));
+    }
+
+    #[test]
+    fn operators_take_text_objects() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["say \"hola amigo\" (uno)"]);
+        for c in "wwdiw".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(textarea_text(&app.input), "say \" amigo\" (uno)");
+        for c in "ci\"adios".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(textarea_text(&app.input), "say \"adios\" (uno)");
+        for c in "$da(".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(textarea_text(&app.input), "say \"adios\" ");
+        assert_eq!(app.active_mode(), Mode::Normal);
+    }
+
+    #[test]
+    fn r_and_replace_mode_overwrite() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["gato"]);
+        for c in "3rz5rx".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(textarea_text(&app.input), "zzzo");
+        assert_eq!(app.input.cursor(), (0, 2));
+        for c in "lRodo!".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(app.active_mode(), Mode::Replace);
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(textarea_text(&app.input), "zzzodo!");
+        assert!(app.pending_translation);
+    }
+
+    #[test]
+    fn dot_repeats_the_last_change() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["uno dos tres cuatro cinco"]);
+        for c in "dwj.".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(textarea_text(&app.input), "tres cuatro cinco");
+        // Undo isn't a change, and a count replaces the repeated one.
+        for c in "u^2.".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(textarea_text(&app.input), "cuatro cinco");
+        for c in "ia-".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        app.handle_key(press(KeyCode::Char('.'), KeyModifiers::NONE));
+        assert_eq!(textarea_text(&app.input), "a-a-cuatro cinco");
+    }
+
+    #[test]
+    fn named_registers_are_shared_by_the_panes() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["hola mundo"]);
+        for c in "\"ayw\"Ayw\"byiw".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(app.registers[&'a'], "hola mundo");
+        assert_eq!(app.registers[&'b'], "mundo");
+        app.handle_key(press(KeyCode::Tab, KeyModifiers::NONE));
+        app.output.set_yank_text("adiós");
+        for c in "\"ap".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(textarea_text(&app.output), "hola mundo");
+        // Pasting a named register leaves the unnamed one alone.
+        assert_eq!(app.output.yank_text(), "adiós");
+        app.execute_command("registers");
+        assert!(app.overlay_open());
+    }
+
+    #[test]
+    fn named_registers_take_a_yank_of_the_same_text() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["hola mundo"]);
+        for c in "\"ayw^\"Ayw".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(app.registers[&'a'], "hola hola ");
+        for c in "^\"byw".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(app.registers[&'b'], "hola ");
+        // Yanking what the register already holds still fills the unnamed register.
+        app.input.set_yank_text("adiós");
+        for c in "^\"byw".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(app.input.yank_text(), "hola ");
+    }
+
+    #[test]
+    fn jumps_to_lines_and_clears_with_ex_commands() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["uno", "dos", "tres", "cuatro"]);
+        app.execute_command("3");
+        assert_eq!(app.input.cursor(), (2, 0));
+        for c in "2gg".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(app.input.cursor(), (1, 0));
+        app.handle_key(press(KeyCode::Char('G'), KeyModifiers::NONE));
+        assert_eq!(app.input.cursor(), (3, 0));
+        app.execute_command("%d");
+        assert_eq!(textarea_text(&app.input), "");
+        assert!(app.pending_translation);
+        app.handle_key(press(KeyCode::Char('u'), KeyModifiers::NONE));
+        assert_eq!(app.input.lines().len(), 4);
+        assert_eq!(app.execute_command("q"), AppAction::Quit);
+    }
+
+    #[test]
+    fn search_jumps_between_matches() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["Uno dos", "tres uno"]);
+        for c in "/uno".chars() {
+            app.handle_key(press(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(app.search_query(), Some("uno"));
+        app.handle_key(press(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.search_query(), None);
+        assert_eq!(app.input.cursor(), (1, 5));
+        app.handle_key(press(KeyCode::Char('n'), KeyModifiers::NONE));
+        assert_eq!(app.input.cursor(), (0, 0));
+        app.handle_key(press(KeyCode::Char('N'), KeyModifiers::NONE));
+        assert_eq!(app.input.cursor(), (1, 5));
+        app.handle_key(press(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(app.input.search_pattern().is_none());
+    }
+
+    #[test]
+    fn replace_selection_swaps_visual_text() {
+        let mut app = App::new(&Config::default());
+        app.input = TextArea::from(["a big house"]);
+        app.handle_key(press(KeyCode::Char('w'), KeyModifiers::NONE));
+        app.handle_key(press(KeyCode::Char('v'), KeyModifiers::NONE));
+        app.handle_key(press(KeyCode::Char('e'), KeyModifiers::NONE));
+        app.replace_selection("large");
+        assert_eq!(textarea_text(&app.input), "a large house");
+        assert_eq!(app.active_mode(), Mode::Normal);
+        assert!(app.pending_translation);
+    }
+
+    #[test]
+    fn sessions_ask_for_the_provider_they_were_saved_with() {
+        let mut app = App::new(&Config::default());
+        app.provider = "DeepL".to_string();
+        let mut session = Session {
+            name: "work".to_string(),
+            panes: app.draft(),
+            provider: "DeepL".to_string(),
+        };
+        assert_eq!(app.restore_session(&session), AppAction::None);
+        session.provider = MOCK_NAME.to_string();
+        assert_eq!(
+            app.restore_session(&session),
+            AppAction::SessionProvider {
+                session: "work".to_string(),
+                provider: MOCK_NAME.to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn length_overflow_counts_target_chars() {
+        let mut app = App::new(&Config::default());
+        app.output = TextArea::from(["¿Qué tal?"]);
+        assert_eq!(app.length_overflow(), None);
+        app.length_limit = Some(5);
+        assert_eq!(app.target_len(), 9);
+        assert_eq!(app.length_overflow(), Some(4));
+        app.length_limit = Some(9);
+        assert_eq!(app.length_overflow(), None);
+    }
 }
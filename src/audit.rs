@@ -0,0 +1,100 @@
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::dates::unix_now;
+use crate::paths::data_dir;
+use crate::sync::append_jsonl;
+
+/// Append-only JSON-lines record of every call made to the translation API.
+pub struct AuditLog {
+    pub path: PathBuf,
+    // Also log the submitted text; off by default since it may be confidential.
+    pub include_text: bool,
+}
+
+/// One outbound request. Never carries credentials.
+#[derive(Debug, Serialize)]
+pub struct AuditRecord<'a> {
+    pub timestamp: u64,
+    // Scheme, host and path only; query strings can carry keys.
+    pub endpoint: String,
+    pub source_lang: &'a str,
+    pub target_lang: &'a str,
+    pub segments: usize,
+    pub chars: usize,
+    // HTTP status, or `None` when the request never got a response.
+    pub status: Option<u16>,
+    pub ok: bool,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<&'a [&'a str]>,
+}
+
+impl AuditLog {
+    /// `PTRUI_AUDIT_LOG` enables the log: a file path, or `1` for `audit.jsonl` in the data
+    /// directory. `PTRUI_AUDIT_TEXT=1` opts in to logging the submitted text.
+    pub fn from_env() -> Option<Self> {
+        let value = env::var("PTRUI_AUDIT_LOG").ok()?;
+        let path = match value.trim() {
+            "" | "0" => return None,
+            "1" => data_dir()?.join("audit.jsonl"),
+            path => PathBuf::from(path),
+        };
+        let include_text = env::var("PTRUI_AUDIT_TEXT").is_ok_and(|value| value.trim() == "1");
+        Some(Self { path, include_text })
+    }
+
+    pub fn record(
+        &self,
+        url: &str,
+        source_lang: &str,
+        target_lang: &str,
+        texts: &[&str],
+        status: Option<u16>,
+        duration: Duration,
+    ) -> Result<(), String> {
+        let record = AuditRecord {
+            timestamp: unix_now(),
+            endpoint: redact_endpoint(url),
+            source_lang,
+            target_lang,
+            segments: texts.len(),
+            chars: texts.iter().map(|text| text.chars().count()).sum(),
+            status,
+            ok: status.is_some_and(|status| (200..300).contains(&status)),
+            duration_ms: duration.as_millis() as u64,
+            text: self.include_text.then_some(texts),
+        };
+        append_jsonl(&self.path, &record)
+    }
+}
+
+/// Drops credentials, query and fragment from an endpoint URL.
+fn redact_endpoint(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut url) => {
+            let _ = url.set_username("");
+            let _ = url.set_password(None);
+            url.set_query(None);
+            url.set_fragment(None);
+            url.to_string()
+        }
+        Err(_) => "<invalid url>".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_is_redacted() {
+        assert_eq!(
+            redact_endpoint("https://user:pw@api.example.com/v2/translate?auth_key=secret#x"),
+            "https://api.example.com/v2/translate"
+        );
+    }
+}
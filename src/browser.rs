@@ -0,0 +1,102 @@
+use std::env;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Web translators the current pair can be opened in for comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebTranslator {
+    DeepL,
+    Google,
+}
+
+impl WebTranslator {
+    /// `PTRUI_WEB_TRANSLATOR=google` switches from the DeepL default.
+    pub fn from_env() -> Self {
+        match env::var("PTRUI_WEB_TRANSLATOR") {
+            Ok(value) if value.trim().eq_ignore_ascii_case("google") => Self::Google,
+            _ => Self::DeepL,
+        }
+    }
+
+    pub fn url(self, text: &str, source_lang: &str, target_lang: &str) -> String {
+        let source = source_lang.to_ascii_lowercase();
+        let target = target_lang.to_ascii_lowercase();
+        match self {
+            Self::DeepL => format!(
+                "https://www.deepl.com/translator#{}/{}/{}",
+                source,
+                target,
+                percent_encode(text)
+            ),
+            Self::Google => format!(
+                "https://translate.google.com/?sl={}&tl={}&text={}&op=translate",
+                google_code(&source),
+                google_code(&target),
+                percent_encode(text)
+            ),
+        }
+    }
+}
+
+fn google_code(code: &str) -> &str {
+    match code {
+        "zh" => "zh-CN",
+        code => code,
+    }
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters.
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Opens a URL with `$BROWSER`, or the platform's default handler.
+pub fn open_url(url: &str) -> Result<(), String> {
+    let mut command = match env::var("BROWSER") {
+        Ok(browser) if !browser.trim().is_empty() => Command::new(browser.trim()),
+        _ if cfg!(target_os = "macos") => Command::new("open"),
+        // Not `cmd /C start`: cmd would split the URL at `&` and run the rest as commands.
+        _ if cfg!(windows) => {
+            let mut command = Command::new("rundll32");
+            command.arg("url.dll,FileProtocolHandler");
+            command
+        }
+        _ => Command::new("xdg-open"),
+    };
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("Failed to open a browser: {}", err))?;
+    // Reap the opener in the background; some browsers stay attached until closed.
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_encoded_urls() {
+        assert_eq!(
+            WebTranslator::DeepL.url("¿Qué tal? a/b", "ES", "EN"),
+            "https://www.deepl.com/translator#es/en/%C2%BFQu%C3%A9%20tal%3F%20a%2Fb"
+        );
+        assert_eq!(
+            WebTranslator::Google.url("hi", "EN", "ZH"),
+            "https://translate.google.com/?sl=en&tl=zh-CN&text=hi&op=translate"
+        );
+    }
+}
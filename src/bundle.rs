@@ -0,0 +1,351 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+
+use rusqlite::{Connection, MAIN_DB, OpenFlags};
+use serde::{Deserialize, Serialize};
+use toml::Spanned;
+
+use crate::dates::unix_now;
+use crate::paths::{config_dir, data_dir};
+
+const MANIFEST: &str = "manifest.json";
+const BUNDLE_VERSION: u32 = 1;
+const CONFIG_FILE: &str = "config/config.toml";
+// Files in the data directory that only make sense on the machine that wrote them.
+const SKIPPED_FILES: &[&str] = &["draft.json.tmp"];
+// SQLite's journal files; the snapshot of their database already includes what they hold.
+const SKIPPED_SUFFIXES: &[&str] = &["-wal", "-shm", "-journal"];
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    created_at: u64,
+    files: Vec<String>,
+}
+
+/// Writes the data directory (history, phrasebook, stats, sessions, wordlists) and the
+/// config directory, minus secrets, into a zstd-compressed tarball. Returns the file count.
+pub fn export_state(path: &Path) -> Result<usize, String> {
+    export_dirs(path, data_dir().as_deref(), config_dir().as_deref())
+}
+
+/// Restores a bundle into the data and config directories. Existing files are only
+/// replaced with `overwrite`; otherwise nothing is written and the clashes are reported.
+pub fn import_state(path: &Path, overwrite: bool) -> Result<usize, String> {
+    import_dirs(
+        path,
+        overwrite,
+        data_dir().as_deref(),
+        config_dir().as_deref(),
+    )
+}
+
+fn export_dirs(path: &Path, data: Option<&Path>, config: Option<&Path>) -> Result<usize, String> {
+    let mut files = Vec::new();
+    if let Some(dir) = data {
+        collect_files(dir, dir, "data", &mut files)?;
+    }
+    if let Some(dir) = config {
+        collect_files(dir, dir, "config", &mut files)?;
+    }
+
+    let output =
+        File::create(path).map_err(|err| format!("Failed to create {:?}: {}", path, err))?;
+    let encoder = zstd::Encoder::new(output, 0)
+        .map_err(|err| format!("Failed to start compression: {}", err))?;
+    let mut archive = tar::Builder::new(encoder);
+    let manifest = Manifest {
+        version: BUNDLE_VERSION,
+        created_at: unix_now(),
+        files: files.iter().map(|(name, _)| name.clone()).collect(),
+    };
+    let manifest = serde_json::to_vec_pretty(&manifest).map_err(|err| err.to_string())?;
+    append_bytes(&mut archive, MANIFEST, &manifest)?;
+    for (name, source) in &files {
+        let mut contents =
+            fs::read(source).map_err(|err| format!("Failed to read {:?}: {}", source, err))?;
+        if contents.starts_with(SQLITE_HEADER) {
+            contents = sqlite_snapshot(source)?;
+        } else if name == CONFIG_FILE {
+            contents = strip_secrets(&contents)?;
+        }
+        append_bytes(&mut archive, name, &contents)?;
+    }
+    archive
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .and_then(|mut output| output.flush())
+        .map_err(|err| format!("Failed to write {:?}: {}", path, err))?;
+    Ok(files.len())
+}
+
+fn import_dirs(
+    path: &Path,
+    overwrite: bool,
+    data: Option<&Path>,
+    config: Option<&Path>,
+) -> Result<usize, String> {
+    let input = File::open(path).map_err(|err| format!("Failed to open {:?}: {}", path, err))?;
+    let decoder =
+        zstd::Decoder::new(input).map_err(|err| format!("Failed to read {:?}: {}", path, err))?;
+    let mut archive = tar::Archive::new(decoder);
+    let mut entries: Vec<(PathBuf, Vec<u8>)> = Vec::new();
+    let mut manifest = None;
+    for entry in archive
+        .entries()
+        .map_err(|err| format!("Invalid bundle: {}", err))?
+    {
+        let mut entry = entry.map_err(|err| format!("Invalid bundle: {}", err))?;
+        let name = entry
+            .path()
+            .map_err(|err| format!("Invalid bundle: {}", err))?
+            .into_owned();
+        let mut contents = Vec::new();
+        entry
+            .read_to_end(&mut contents)
+            .map_err(|err| format!("Invalid bundle: {}", err))?;
+        if name == Path::new(MANIFEST) {
+            manifest = Some(
+                serde_json::from_slice::<Manifest>(&contents)
+                    .map_err(|err| format!("Invalid bundle manifest: {}", err))?,
+            );
+        } else {
+            entries.push((destination(&name, data, config)?, contents));
+        }
+    }
+    match manifest {
+        Some(manifest) if manifest.version <= BUNDLE_VERSION => {}
+        Some(manifest) => {
+            return Err(format!(
+                "Bundle version {} is newer than this ptrui understands",
+                manifest.version
+            ));
+        }
+        None => return Err("Not a ptrui state bundle (no manifest)".to_string()),
+    }
+
+    let existing: Vec<String> = entries
+        .iter()
+        .filter(|(target, _)| target.exists())
+        .map(|(target, _)| target.display().to_string())
+        .collect();
+    if !overwrite && !existing.is_empty() {
+        return Err(format!(
+            "These files already exist (pass --force to replace them):\n  {}",
+            existing.join("\n  ")
+        ));
+    }
+    for (target, contents) in &entries {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {:?}: {}", parent, err))?;
+        }
+        fs::write(target, contents)
+            .map_err(|err| format!("Failed to write {:?}: {}", target, err))?;
+    }
+    Ok(entries.len())
+}
+
+/// Maps `data/...` and `config/...` bundle paths into the local directories, rejecting
+/// anything that could escape them.
+fn destination(name: &Path, data: Option<&Path>, config: Option<&Path>) -> Result<PathBuf, String> {
+    let mut components = name.components();
+    let root = match components.next() {
+        Some(Component::Normal(root)) if root == "data" => data,
+        Some(Component::Normal(root)) if root == "config" => config,
+        _ => return Err(format!("Unexpected file in bundle: {}", name.display())),
+    }
+    .ok_or_else(|| "Cannot locate the data or config directory".to_string())?;
+    let rest = components.as_path();
+    if rest.as_os_str().is_empty()
+        || !rest
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(format!("Unsafe path in bundle: {}", name.display()));
+    }
+    Ok(root.join(rest))
+}
+
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    prefix: &str,
+    files: &mut Vec<(String, PathBuf)>,
+) -> Result<(), String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(format!("Failed to read {:?}: {}", dir, err)),
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_files(root, &path, prefix, files)?;
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let skipped = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                SKIPPED_FILES.contains(&name)
+                    || SKIPPED_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+            });
+        if path.is_file() && !skipped {
+            let name = format!(
+                "{}/{}",
+                prefix,
+                relative.to_string_lossy().replace('\\', "/")
+            );
+            files.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+fn append_bytes<W: Write>(
+    archive: &mut tar::Builder<W>,
+    name: &str,
+    contents: &[u8],
+) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(unix_now());
+    header.set_cksum();
+    archive
+        .append_data(&mut header, name, contents)
+        .map_err(|err| format!("Failed to add {} to bundle: {}", name, err))
+}
+
+/// A consistent copy of a SQLite database, taken with the backup API so a write in progress
+/// (or one still in the WAL) can't leave a torn file in the bundle.
+fn sqlite_snapshot(source: &Path) -> Result<Vec<u8>, String> {
+    let failed = |err: rusqlite::Error| format!("Failed to back up {:?}: {}", source, err);
+    let conn =
+        Connection::open_with_flags(source, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(failed)?;
+    // Next to the original, so the copy is as private as the database itself.
+    let mut copy = source.as_os_str().to_owned();
+    copy.push(".bundle");
+    let copy = PathBuf::from(copy);
+    let _ = fs::remove_file(&copy);
+    let result = conn
+        .backup(MAIN_DB, &copy, None)
+        .map_err(failed)
+        .and_then(|_| fs::read(&copy).map_err(|err| format!("Failed to read {:?}: {}", copy, err)));
+    let _ = fs::remove_file(&copy);
+    result
+}
+
+/// The config file's secrets, found by parsing it. The LLM provider authenticates with
+/// `api.key` as well, so that is the only credential the file holds.
+#[derive(Deserialize)]
+struct ConfigSecrets {
+    #[serde(default)]
+    api: ApiSecrets,
+}
+
+#[derive(Default, Deserialize)]
+struct ApiSecrets {
+    key: Option<Spanned<toml::Value>>,
+}
+
+/// Removes the lines holding `api.key` from the config file (all of an inline `api = { .. }`
+/// table), keeping the rest, comments included, as written.
+fn strip_secrets(contents: &[u8]) -> Result<Vec<u8>, String> {
+    let text = std::str::from_utf8(contents)
+        .map_err(|_| "The config file is not valid UTF-8".to_string())?;
+    let secrets: ConfigSecrets = toml::from_str(text)
+        .map_err(|err| format!("Failed to read the config file: {}", err.message()))?;
+    let Some(key) = secrets.api.key else {
+        return Ok(contents.to_vec());
+    };
+    let Range { start, end } = key.span();
+    let line_start = text[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = text[end..]
+        .find('\n')
+        .map_or(text.len(), |index| end + index + 1);
+    Ok([&text[..line_start], &text[line_end..]]
+        .concat()
+        .into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_the_api_key() {
+        let config = "theme = \"dark\"\n\n[api]\n# mine\nkey = \"abc\"\nurl = \"x\"\n";
+        assert_eq!(
+            strip_secrets(config.as_bytes()).unwrap(),
+            b"theme = \"dark\"\n\n[api]\n# mine\nurl = \"x\"\n"
+        );
+        let inline = "api = { url = \"x\", key = \"abc\" }\n[keys]\nF5 = \"translate-now\"\n";
+        assert_eq!(
+            strip_secrets(inline.as_bytes()).unwrap(),
+            b"[keys]\nF5 = \"translate-now\"\n"
+        );
+        assert!(strip_secrets(b"[api\nkey = 1").is_err());
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_state_dirs() {
+        let root = Some(Path::new("/tmp/ptrui"));
+        assert!(destination(Path::new("data/../../etc/passwd"), root, root).is_err());
+        assert!(destination(Path::new("other/file"), root, root).is_err());
+    }
+
+    #[test]
+    fn round_trips_state() {
+        let dir = std::env::temp_dir().join(format!("ptrui-bundle-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let (data, config) = (dir.join("data"), dir.join("config"));
+        fs::create_dir_all(&data).unwrap();
+        fs::create_dir_all(&config).unwrap();
+        fs::write(data.join("phrasebook.jsonl"), "{}\n").unwrap();
+        fs::write(
+            config.join("config.toml"),
+            "[api]\nkey = \"abc\"\nurl = \"x\"\n",
+        )
+        .unwrap();
+        let db = Connection::open(data.join("history.sqlite3")).unwrap();
+        db.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE history (text TEXT);
+             INSERT INTO history VALUES ('hola');",
+        )
+        .unwrap();
+
+        let bundle = dir.join("state.tar.zst");
+        assert_eq!(export_dirs(&bundle, Some(&data), Some(&config)).unwrap(), 3);
+        drop(db);
+        let restored = dir.join("restored");
+        let (data, config) = (restored.join("data"), restored.join("config"));
+        assert_eq!(
+            import_dirs(&bundle, false, Some(&data), Some(&config)).unwrap(),
+            3
+        );
+        assert_eq!(
+            fs::read_to_string(config.join("config.toml")).unwrap(),
+            "[api]\nurl = \"x\"\n"
+        );
+        let db = Connection::open(data.join("history.sqlite3")).unwrap();
+        let text: String = db
+            .query_row("SELECT text FROM history", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(text, "hola");
+        assert!(import_dirs(&bundle, false, Some(&data), Some(&config)).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+
+use crate::api::TranslateOptions;
+use crate::paths::data_dir;
+use crate::provider::TranslationProvider;
+
+const DEFAULT_CAPACITY: usize = 512;
+// `PRAGMA user_version` of the disk cache; older tables are dropped rather than migrated.
+const SCHEMA_VERSION: i64 = 2;
+// Provider label for translations answered by the cache.
+pub const CACHE_PROVIDER: &str = "cache";
+
+/// Identifies a translation: same provider, text, pair, context, tone and formality give the same
+/// result. Trailing whitespace doesn't count, so retyping a sentence finds it even with a stray
+/// space.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    // `TranslationProvider::name`: the API host, or host and model for chat models, so one
+    // backend's answers (or the mock's reversed text) are never served as another's.
+    pub provider: String,
+    pub text: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub context: Option<String>,
+    pub tone: Option<String>,
+    pub formality: Option<String>,
+}
+
+impl CacheKey {
+    pub fn new(
+        provider: &str,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        options: &TranslateOptions,
+    ) -> Self {
+        Self {
+            provider: provider.to_string(),
+            text: normalize(text),
+            source_lang: source_lang.to_string(),
+            target_lang: target_lang.to_string(),
+            context: options.context.map(str::to_string),
+            tone: options.tone.map(str::to_string),
+            formality: options.formality.map(str::to_string),
+        }
+    }
+}
+
+fn normalize(text: &str) -> String {
+    let lines: Vec<&str> = text.trim_end().lines().map(str::trim_end).collect();
+    lines.join("\n")
+}
+
+/// Cache counters; kept per session and accumulated on disk for `ptrui usage`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    // Source characters served from the cache instead of the API (the quota saved).
+    pub chars_saved: u64,
+}
+
+impl CacheStats {
+    pub fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("cache_stats.json"))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Adds a session's counters to the totals on disk.
+    pub fn accumulate(&self) -> Result<(), String> {
+        if *self == Self::default() {
+            return Ok(());
+        }
+        let path = Self::path().ok_or_else(|| "Cannot locate a data directory".to_string())?;
+        let mut total = Self::load();
+        total.hits += self.hits;
+        total.misses += self.misses;
+        total.evictions += self.evictions;
+        total.chars_saved += self.chars_saved;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {:?}: {}", parent, err))?;
+        }
+        let contents = serde_json::to_string_pretty(&total).map_err(|err| err.to_string())?;
+        fs::write(&path, contents).map_err(|err| format!("Failed to write {:?}: {}", path, err))
+    }
+
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+
+    pub fn summary_lines(&self) -> Vec<String> {
+        vec![
+            format!(
+                "Cache: {} hits, {} misses ({:.0}% hit rate), {} evictions",
+                self.hits,
+                self.misses,
+                self.hit_rate() * 100.0,
+                self.evictions
+            ),
+            format!("Quota saved: {} chars served from cache", self.chars_saved),
+        ]
+    }
+}
+
+/// In-memory LRU of recent translations so retyping or toggling back doesn't re-bill the API.
+pub struct TranslationCache {
+    entries: HashMap<CacheKey, String>,
+    // Least recently used first.
+    order: VecDeque<CacheKey>,
+    pub capacity: usize,
+    pub stats: CacheStats,
+    // Translations kept across sessions, when enabled.
+    disk: Option<DiskCache>,
+}
+
+impl TranslationCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+            stats: CacheStats::default(),
+            disk: None,
+        }
+    }
+
+    /// Capacity from `PTRUI_CACHE_SIZE` (0 disables the in-memory cache) and the disk cache
+    /// from `PTRUI_DISK_CACHE_SIZE`.
+    pub fn from_env() -> Result<Self, String> {
+        let capacity = env::var("PTRUI_CACHE_SIZE")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_CAPACITY);
+        let mut cache = Self::new(capacity);
+        cache.disk = DiskCache::from_env()?;
+        Ok(cache)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&mut self, key: &CacheKey) -> Option<String> {
+        if self.capacity == 0 && self.disk.is_none() {
+            return None;
+        }
+        let found = match self.entries.get(key) {
+            Some(value) => {
+                let value = value.clone();
+                self.touch(key);
+                Some(value)
+            }
+            // Promote disk hits so the next lookup stays in memory.
+            None => {
+                let value = self.disk.as_ref().and_then(|disk| disk.get(key));
+                if let Some(value) = &value {
+                    self.insert_memory(key.clone(), value.clone());
+                }
+                value
+            }
+        };
+        match &found {
+            Some(_) => {
+                self.stats.hits += 1;
+                self.stats.chars_saved += key.text.chars().count() as u64;
+            }
+            None => self.stats.misses += 1,
+        }
+        found
+    }
+
+    pub fn insert(&mut self, key: CacheKey, value: String) {
+        // Best effort: a failed write only costs a future API call.
+        if let Some(disk) = &self.disk {
+            let _ = disk.insert(&key, &value);
+        }
+        self.insert_memory(key, value);
+    }
+
+    fn insert_memory(&mut self, key: CacheKey, value: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.insert(key.clone(), value).is_some() {
+            self.touch(&key);
+            return;
+        }
+        self.order.push_back(key);
+        while self.entries.len() > self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+    }
+
+    fn touch(&mut self, key: &CacheKey) {
+        if let Some(position) = self.order.iter().position(|entry| entry == key)
+            && let Some(key) = self.order.remove(position)
+        {
+            self.order.push_back(key);
+        }
+    }
+}
+
+/// Translations kept in SQLite across sessions, least recently used dropped first.
+pub struct DiskCache {
+    conn: Connection,
+    max_entries: usize,
+}
+
+impl DiskCache {
+    pub fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("cache.sqlite3"))
+    }
+
+    /// `PTRUI_DISK_CACHE_SIZE` entries (unset or 0 keeps the cache in memory only).
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let Ok(value) = env::var("PTRUI_DISK_CACHE_SIZE") else {
+            return Ok(None);
+        };
+        let max_entries = value
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid PTRUI_DISK_CACHE_SIZE: {}", value))?;
+        if max_entries == 0 {
+            return Ok(None);
+        }
+        let path = Self::path().ok_or_else(|| "Cannot locate a data directory".to_string())?;
+        Self::open(&path, max_entries).map(Some)
+    }
+
+    pub fn open(path: &Path, max_entries: usize) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {:?}: {}", parent, err))?;
+        }
+        let conn = Connection::open(path)
+            .map_err(|err| format!("Failed to open cache {:?}: {}", path, err))?;
+        Self::with_connection(conn, max_entries)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory(max_entries: usize) -> Result<Self, String> {
+        let conn = Connection::open_in_memory().map_err(|err| err.to_string())?;
+        Self::with_connection(conn, max_entries)
+    }
+
+    fn with_connection(conn: Connection, max_entries: usize) -> Result<Self, String> {
+        // Entries from before the provider and tone were part of the key can't be attributed;
+        // drop them.
+        let version: i64 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|err| format!("Failed to read cache version: {}", err))?;
+        if version < SCHEMA_VERSION {
+            conn.execute_batch(&format!(
+                "DROP TABLE IF EXISTS cache; PRAGMA user_version = {};",
+                SCHEMA_VERSION
+            ))
+            .map_err(|err| format!("Failed to upgrade cache: {}", err))?;
+        }
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS cache (
+                provider TEXT NOT NULL,
+                text TEXT NOT NULL,
+                source_lang TEXT NOT NULL,
+                target_lang TEXT NOT NULL,
+                context TEXT NOT NULL,
+                tone TEXT NOT NULL,
+                formality TEXT NOT NULL,
+                translated TEXT NOT NULL,
+                used_at INTEGER NOT NULL,
+                PRIMARY KEY (provider, text, source_lang, target_lang, context, tone, formality)
+            );
+            CREATE INDEX IF NOT EXISTS cache_used_at ON cache (used_at);",
+        )
+        .map_err(|err| format!("Failed to set up cache: {}", err))?;
+        Ok(Self { conn, max_entries })
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<String> {
+        let context = key.context.as_deref().unwrap_or_default();
+        let tone = key.tone.as_deref().unwrap_or_default();
+        let formality = key.formality.as_deref().unwrap_or_default();
+        let translated = self
+            .conn
+            .query_row(
+                "SELECT translated FROM cache
+                 WHERE text = ?1 AND source_lang = ?2 AND target_lang = ?3 AND context = ?4
+                   AND formality = ?5 AND provider = ?6 AND tone = ?7",
+                params![
+                    key.text,
+                    key.source_lang,
+                    key.target_lang,
+                    context,
+                    formality,
+                    key.provider,
+                    tone
+                ],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .ok()
+            .flatten()?;
+        let _ = self.conn.execute(
+            "UPDATE cache SET used_at = (SELECT COALESCE(MAX(used_at), 0) + 1 FROM cache)
+             WHERE text = ?1 AND source_lang = ?2 AND target_lang = ?3 AND context = ?4
+               AND formality = ?5 AND provider = ?6 AND tone = ?7",
+            params![
+                key.text,
+                key.source_lang,
+                key.target_lang,
+                context,
+                formality,
+                key.provider,
+                tone
+            ],
+        );
+        Some(translated)
+    }
+
+    pub fn insert(&self, key: &CacheKey, translated: &str) -> Result<(), String> {
+        let context = key.context.as_deref().unwrap_or_default();
+        let tone = key.tone.as_deref().unwrap_or_default();
+        let formality = key.formality.as_deref().unwrap_or_default();
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO cache
+                    (text, source_lang, target_lang, context, formality, translated, provider,
+                     tone, used_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8,
+                    (SELECT COALESCE(MAX(used_at), 0) + 1 FROM cache))",
+                params![
+                    key.text,
+                    key.source_lang,
+                    key.target_lang,
+                    context,
+                    formality,
+                    translated,
+                    key.provider,
+                    tone
+                ],
+            )
+            .map_err(|err| format!("Failed to write cache: {}", err))?;
+        self.conn
+            .execute(
+                "DELETE FROM cache WHERE rowid NOT IN
+                    (SELECT rowid FROM cache ORDER BY used_at DESC LIMIT ?1)",
+                params![self.max_entries as i64],
+            )
+            .map_err(|err| format!("Failed to prune cache: {}", err))?;
+        Ok(())
+    }
+}
+
+/// Translates through the cache; also returns who answered (`cache` or the provider).
+pub fn translate_cached(
+    cache: &mut TranslationCache,
+    provider: &dyn TranslationProvider,
+    text: &str,
+    source_lang: &str,
+    target_lang: &str,
+    options: &TranslateOptions,
+) -> Result<(String, String), String> {
+    let key = CacheKey::new(&provider.name(), text, source_lang, target_lang, options);
+    if let Some(translated) = cache.get(&key) {
+        return Ok((translated, CACHE_PROVIDER.to_string()));
+    }
+    let translated = provider.translate(text, source_lang, target_lang, options)?;
+    cache.insert(key, translated.clone());
+    Ok((translated, provider.name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text: &str) -> CacheKey {
+        CacheKey::new("test", text, "EN", "ES", &TranslateOptions::default())
+    }
+
+    #[test]
+    fn falls_back_to_the_disk_cache() {
+        let disk = DiskCache::open_in_memory(2).unwrap();
+        disk.insert(&key("one"), "uno").unwrap();
+        disk.insert(&key("two"), "dos").unwrap();
+        assert_eq!(disk.get(&key("one")).as_deref(), Some("uno"));
+        disk.insert(&key("three"), "tres").unwrap();
+        assert_eq!(disk.get(&key("two")), None);
+        let other = CacheKey::new("mock", "one", "EN", "ES", &TranslateOptions::default());
+        assert_eq!(disk.get(&other), None);
+
+        let mut cache = TranslationCache::new(4);
+        cache.disk = Some(disk);
+        assert_eq!(cache.get(&key("one  \n")).as_deref(), Some("uno"));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.stats.hits, 1);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = TranslationCache::new(2);
+        cache.insert(key("one"), "uno".into());
+        cache.insert(key("two"), "dos".into());
+        assert_eq!(cache.get(&key("one")).as_deref(), Some("uno"));
+        cache.insert(key("three"), "tres".into());
+        assert_eq!(cache.get(&key("two")), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.stats,
+            CacheStats {
+                hits: 1,
+                misses: 1,
+                evictions: 1,
+                chars_saved: 3,
+            }
+        );
+    }
+}
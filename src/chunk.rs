@@ -0,0 +1,129 @@
+// Well under what DeepL and Google accept in one request, and small enough that a long
+// document shows progress.
+pub const DEFAULT_CHUNK_CHARS: usize = 5000;
+
+/// A piece of a long text sent in its own request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunk<'a> {
+    pub text: &'a str,
+    // Whitespace between this chunk and the next, put back verbatim around the translations.
+    pub separator: &'a str,
+}
+
+/// Splits `text` into chunks of at most `max_chars` characters, cutting at the last paragraph
+/// break that fits, else the last line break, sentence end or space, and only mid-word when a
+/// single word is longer than the budget.
+pub fn split_chunks(text: &str, max_chars: usize) -> Vec<Chunk<'_>> {
+    let max_chars = max_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let rest = &text[start..];
+        let Some((limit, _)) = rest.char_indices().nth(max_chars) else {
+            chunks.push(Chunk {
+                text: rest,
+                separator: "",
+            });
+            break;
+        };
+        let window = &rest[..limit];
+        let cut = [
+            window.rfind("\n\n"),
+            window.rfind('\n'),
+            sentence_end(window),
+            window.rfind(char::is_whitespace),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|&cut| cut > 0)
+        .unwrap_or(limit);
+        let after = &rest[cut..];
+        let separator = &after[..after.len() - after.trim_start().len()];
+        chunks.push(Chunk {
+            text: &rest[..cut],
+            separator,
+        });
+        start += cut + separator.len();
+    }
+    chunks
+}
+
+/// The byte offset just past the last sentence-ending punctuation in `window`.
+fn sentence_end(window: &str) -> Option<usize> {
+    let mut following: Option<char> = None;
+    for (index, c) in window.char_indices().rev() {
+        let ends = match c {
+            '。' | '！' | '？' => true,
+            '.' | '!' | '?' => following.is_some_and(char::is_whitespace),
+            _ => false,
+        };
+        if ends {
+            return Some(index + c.len_utf8());
+        }
+        following = Some(c);
+    }
+    None
+}
+
+/// Translates `text` one chunk at a time and joins the results with the original separators.
+/// `progress` is called before each chunk with its index and the chunk count, and can stop
+/// the rest by returning an error.
+pub fn translate_chunked(
+    text: &str,
+    max_chars: usize,
+    mut translate: impl FnMut(&str) -> Result<String, String>,
+    mut progress: impl FnMut(usize, usize) -> Result<(), String>,
+) -> Result<String, String> {
+    let chunks = split_chunks(text, max_chars);
+    if chunks.len() <= 1 {
+        return translate(text);
+    }
+    let mut translated = String::with_capacity(text.len());
+    for (index, chunk) in chunks.iter().enumerate() {
+        progress(index, chunks.len())?;
+        if !chunk.text.trim().is_empty() {
+            translated.push_str(&translate(chunk.text)?);
+        }
+        translated.push_str(chunk.separator);
+    }
+    Ok(translated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cuts_at_paragraphs_then_sentences() {
+        let text = "First paragraph here.\n\nSecond one. It has two sentences.";
+        let chunks: Vec<&str> = split_chunks(text, 30)
+            .iter()
+            .map(|chunk| chunk.text)
+            .collect();
+        assert_eq!(
+            chunks,
+            [
+                "First paragraph here.",
+                "Second one.",
+                "It has two sentences."
+            ]
+        );
+        assert_eq!(split_chunks("abcdef", 4)[0].text, "abcd");
+
+        let mut seen = Vec::new();
+        let translated = translate_chunked(
+            text,
+            30,
+            |chunk| Ok(chunk.to_uppercase()),
+            |done, total| {
+                seen.push((done, total));
+                Ok(())
+            },
+        );
+        assert_eq!(
+            translated.unwrap(),
+            "FIRST PARAGRAPH HERE.\n\nSECOND ONE. IT HAS TWO SENTENCES."
+        );
+        assert_eq!(seen, [(0, 3), (1, 3), (2, 3)]);
+    }
+}
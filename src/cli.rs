@@ -0,0 +1,538 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::bundle::{export_state, import_state};
+use crate::cache::CacheStats;
+use crate::dates::{SECONDS_PER_DAY, format_day, parse_day};
+use crate::filter::run_filter;
+use crate::gitmsg::{run_git_msg, team_language};
+use crate::history::HistoryStore;
+use crate::languages::find_language_index;
+use crate::oneshot::run_translate;
+use crate::phrasebook::{PhraseEntry, anki_tsv, load_phrases};
+use crate::pipe::run_pipe;
+use crate::romanize::romanize;
+use crate::server::run_server;
+use crate::stats::PracticeStats;
+use crate::update::check_update;
+
+const USAGE: &str = "Usage:
+  ptrui [--session <name> | --sessions] [--popup] [--present [--advance <seconds>]]
+        [--ocr <image>] [--record <file.keys> | --replay <file.keys>] [--offline]
+        [--no-restore]
+                                 start the interactive translator, optionally opening a saved
+                                 session or the session picker; --popup uses a compact layout
+                                 for tmux display-popup, --present a read-only presentation of
+                                 history entries, --ocr translates the text in an image,
+                                 --record saves every key with its timing and --replay plays
+                                 such a file back; --offline translates with the mock
+                                 provider (reversed text) instead of the API and
+                                 --no-restore starts with empty panes
+  ptrui [--file <input>] --to <code> [--from <code>] [--output <file>] [--json]
+                                 translate a file, or text piped to stdin, without the TUI;
+                                 prints to stdout unless --output is given, as a JSON object
+                                 with the languages and provider with --json; the source
+                                 language is detected without --from
+  ptrui export anki <deck.tsv> [--history]
+                                 export the phrasebook (or translation history) as Anki TSV
+  ptrui filter --to <code> [--from <code>]
+                                 translate stdin to stdout line by line, keeping indentation
+                                 (for editor filters like :'<,'>!ptrui filter --to DE)
+  ptrui git-msg [--to <code>] [--from <code>] [--log]
+                                 translate a commit message, git log output (--log) or the
+                                 comments in a diff from stdin to the team language
+  ptrui lsp                      serve translate/detect/languages as JSON-RPC over stdio
+  ptrui pipe                     translate JSON lines ({\"text\", \"from\", \"to\"}) from stdin to stdout
+  ptrui stats                    show practice statistics and streaks
+  ptrui usage                    chart translation volume per day, language pair and provider
+  ptrui history purge [--before YYYY-MM-DD]
+                                 delete translation history (all, or entries before a UTC date)
+  ptrui self check-update [--download]
+                                 compare with the latest GitHub release and print upgrade
+                                 steps, downloading this platform's archive with --download
+  ptrui export-state <bundle.tar.zst>
+                                 back up history, phrasebook, stats and config (minus secrets)
+  ptrui import-state <bundle.tar.zst> [--force]
+                                 restore a state bundle, replacing existing files with --force";
+
+/// Startup choices for the interactive translator.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TuiOptions {
+    // Open this saved session right away.
+    pub session: Option<String>,
+    // Start with the session picker open.
+    pub pick_session: bool,
+    // Compact layout sized for `tmux display-popup`.
+    pub popup: bool,
+    // Start in presentation mode, optionally auto-advancing at this interval.
+    pub present: bool,
+    pub advance_every: Option<Duration>,
+    // Image whose recognized text is loaded into the source pane at startup.
+    pub ocr: Option<PathBuf>,
+    // Write input events with their timing here, for bug reports and demos.
+    pub record: Option<PathBuf>,
+    // Play back a file written by `--record` before handing over to the keyboard.
+    pub replay: Option<PathBuf>,
+    // Translate with the mock provider instead of the API.
+    pub offline: bool,
+    // Start with empty panes instead of restoring (or offering) the last session's.
+    pub no_restore: bool,
+}
+
+/// What the binary was asked to do.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Tui(TuiOptions),
+    ExportAnki {
+        path: PathBuf,
+        from_history: bool,
+    },
+    Stats,
+    Usage,
+    // Delete history entries created before this day number, or all of them.
+    HistoryPurge {
+        before: Option<i64>,
+    },
+    ExportState {
+        path: PathBuf,
+    },
+    ImportState {
+        path: PathBuf,
+        overwrite: bool,
+    },
+    Filter {
+        source_lang: String,
+        target_lang: String,
+    },
+    // Translate a file, or stdin when there is none, without the TUI.
+    Translate {
+        input: Option<PathBuf>,
+        source_lang: Option<String>,
+        target_lang: String,
+        output: Option<PathBuf>,
+        json: bool,
+    },
+    GitMsg {
+        source_lang: Option<String>,
+        target_lang: String,
+        log: bool,
+    },
+    Lsp,
+    Pipe,
+    CheckUpdate {
+        download: bool,
+    },
+    Help,
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [first, ..]
+            if first.starts_with("--") && (args.contains(&"--file") || args.contains(&"--to")) =>
+        {
+            parse_translate_flags(&args)
+        }
+        [first, ..] if first.starts_with("--") && *first != "--help" => parse_tui_flags(&args),
+        [] => Ok(Command::Tui(TuiOptions::default())),
+        ["-h" | "--help" | "help"] => Ok(Command::Help),
+        ["export", "anki", path] => Ok(Command::ExportAnki {
+            path: PathBuf::from(path),
+            from_history: false,
+        }),
+        ["export", "anki", path, "--history"] | ["export", "anki", "--history", path] => {
+            Ok(Command::ExportAnki {
+                path: PathBuf::from(path),
+                from_history: true,
+            })
+        }
+        ["filter", flags @ ..] => parse_filter_flags(flags),
+        ["git-msg", flags @ ..] => parse_git_msg_flags(flags),
+        ["lsp"] => Ok(Command::Lsp),
+        ["self", "check-update"] => Ok(Command::CheckUpdate { download: false }),
+        ["self", "check-update", "--download"] => Ok(Command::CheckUpdate { download: true }),
+        ["pipe"] => Ok(Command::Pipe),
+        ["stats"] => Ok(Command::Stats),
+        ["usage"] => Ok(Command::Usage),
+        ["history", "purge"] => Ok(Command::HistoryPurge { before: None }),
+        ["history", "purge", "--before", date] => parse_day(date)
+            .map(|day| Command::HistoryPurge { before: Some(day) })
+            .ok_or_else(|| format!("Invalid date {:?}, expected YYYY-MM-DD", date)),
+        ["export-state", path] => Ok(Command::ExportState {
+            path: PathBuf::from(path),
+        }),
+        ["import-state", path] => Ok(Command::ImportState {
+            path: PathBuf::from(path),
+            overwrite: false,
+        }),
+        ["import-state", path, "--force"] | ["import-state", "--force", path] => {
+            Ok(Command::ImportState {
+                path: PathBuf::from(path),
+                overwrite: true,
+            })
+        }
+        ["export", "anki"] => Err(format!("Missing deck path\n\n{}", USAGE)),
+        _ => Err(format!(
+            "Unrecognized arguments: {}\n\n{}",
+            args.join(" "),
+            USAGE
+        )),
+    }
+}
+
+fn parse_tui_flags(args: &[&str]) -> Result<Command, String> {
+    let mut options = TuiOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "--session" => {
+                let name = args
+                    .next()
+                    .ok_or_else(|| format!("--session needs a name\n\n{}", USAGE))?;
+                options.session = Some(name.to_string());
+            }
+            "--sessions" => options.pick_session = true,
+            "--popup" => options.popup = true,
+            "--present" => options.present = true,
+            "--offline" => options.offline = true,
+            "--no-restore" => options.no_restore = true,
+            "--ocr" => {
+                let image = args
+                    .next()
+                    .ok_or_else(|| format!("--ocr needs an image path\n\n{}", USAGE))?;
+                options.ocr = Some(PathBuf::from(image));
+            }
+            "--record" | "--replay" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("{} needs a file path\n\n{}", arg, USAGE))?;
+                let slot = if *arg == "--record" {
+                    &mut options.record
+                } else {
+                    &mut options.replay
+                };
+                *slot = Some(PathBuf::from(path));
+            }
+            "--advance" => {
+                let seconds = args
+                    .next()
+                    .and_then(|seconds| seconds.parse::<u64>().ok())
+                    .filter(|seconds| *seconds > 0)
+                    .ok_or_else(|| format!("--advance needs a number of seconds\n\n{}", USAGE))?;
+                options.present = true;
+                options.advance_every = Some(Duration::from_secs(seconds));
+            }
+            _ => return Err(format!("Unrecognized option: {}\n\n{}", arg, USAGE)),
+        }
+    }
+    Ok(Command::Tui(options))
+}
+
+fn parse_filter_flags(args: &[&str]) -> Result<Command, String> {
+    let mut source_lang = "EN".to_string();
+    let mut target_lang = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let slot = match *arg {
+            "--from" => &mut source_lang,
+            "--to" => target_lang.insert(String::new()),
+            _ => return Err(format!("Unrecognized option: {}\n\n{}", arg, USAGE)),
+        };
+        let code = args
+            .next()
+            .ok_or_else(|| format!("{} needs a language code", arg))?;
+        if find_language_index(code).is_none() {
+            return Err(format!("Unknown language code {:?}", code));
+        }
+        *slot = code.to_ascii_uppercase();
+    }
+    let target_lang = target_lang.ok_or_else(|| format!("filter needs --to\n\n{}", USAGE))?;
+    Ok(Command::Filter {
+        source_lang,
+        target_lang,
+    })
+}
+
+fn parse_translate_flags(args: &[&str]) -> Result<Command, String> {
+    let mut input = None;
+    let mut output = None;
+    let mut source_lang = None;
+    let mut target_lang = None;
+    let mut json = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if *arg == "--json" {
+            json = true;
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value\n\n{}", arg, USAGE))?;
+        match *arg {
+            "--file" => input = Some(PathBuf::from(value)),
+            "--output" => output = Some(PathBuf::from(value)),
+            "--from" | "--to" => {
+                if find_language_index(value).is_none() {
+                    return Err(format!("Unknown language code {:?}", value));
+                }
+                let slot = if *arg == "--from" {
+                    &mut source_lang
+                } else {
+                    &mut target_lang
+                };
+                *slot = Some(value.to_ascii_uppercase());
+            }
+            _ => return Err(format!("Unrecognized option: {}\n\n{}", arg, USAGE)),
+        }
+    }
+    Ok(Command::Translate {
+        input,
+        source_lang,
+        target_lang: target_lang.ok_or_else(|| format!("--file needs --to\n\n{}", USAGE))?,
+        output,
+        json,
+    })
+}
+
+fn parse_git_msg_flags(args: &[&str]) -> Result<Command, String> {
+    let mut source_lang = None;
+    let mut target_lang = None;
+    let mut log = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let slot = match *arg {
+            "--log" => {
+                log = true;
+                continue;
+            }
+            "--from" => &mut source_lang,
+            "--to" => &mut target_lang,
+            _ => return Err(format!("Unrecognized option: {}\n\n{}", arg, USAGE)),
+        };
+        let code = args
+            .next()
+            .ok_or_else(|| format!("{} needs a language code", arg))?;
+        if find_language_index(code).is_none() {
+            return Err(format!("Unknown language code {:?}", code));
+        }
+        *slot = Some(code.to_ascii_uppercase());
+    }
+    Ok(Command::GitMsg {
+        source_lang,
+        target_lang: team_language(target_lang.as_deref()),
+        log,
+    })
+}
+
+/// Runs a non-interactive command, returning a message for stderr on failure.
+pub fn run(command: Command) -> Result<(), String> {
+    match command {
+        Command::Tui(_) => Ok(()),
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Command::ExportAnki { path, from_history } => export_anki(&path, from_history),
+        Command::HistoryPurge { before } => purge_history(before),
+        Command::Filter {
+            source_lang,
+            target_lang,
+        } => run_filter(&source_lang, &target_lang),
+        Command::Translate {
+            input,
+            source_lang,
+            target_lang,
+            output,
+            json,
+        } => run_translate(
+            input.as_deref(),
+            source_lang.as_deref(),
+            &target_lang,
+            output.as_deref(),
+            json,
+        ),
+        Command::GitMsg {
+            source_lang,
+            target_lang,
+            log,
+        } => run_git_msg(source_lang.as_deref(), &target_lang, log),
+        Command::Lsp => run_server(),
+        Command::Pipe => run_pipe(),
+        Command::CheckUpdate { download } => check_update(download),
+        Command::ExportState { path } => {
+            let count = export_state(&path)?;
+            println!("Wrote {} files to {}", count, path.display());
+            Ok(())
+        }
+        Command::ImportState { path, overwrite } => {
+            let count = import_state(&path, overwrite)?;
+            println!("Restored {} files from {}", count, path.display());
+            Ok(())
+        }
+        Command::Usage => {
+            let Some(store) = HistoryStore::open_default()? else {
+                return Err("Usage is computed from history, which is disabled".to_string());
+            };
+            for line in store.usage()?.summary_lines() {
+                println!("{}", line);
+            }
+            println!();
+            for line in CacheStats::load().summary_lines() {
+                println!("{}", line);
+            }
+            Ok(())
+        }
+        Command::Stats => {
+            for line in PracticeStats::load().summary_lines() {
+                println!("{}", line);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn export_anki(path: &PathBuf, from_history: bool) -> Result<(), String> {
+    if path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("apkg"))
+    {
+        return Err("Only TSV decks are supported; import the .tsv file in Anki".to_string());
+    }
+    let entries = if from_history {
+        history_phrases()?
+    } else {
+        load_phrases()?
+    };
+    fs::write(path, anki_tsv(&entries))
+        .map_err(|err| format!("Failed to write {:?}: {}", path, err))?;
+    println!("Exported {} cards to {}", entries.len(), path.display());
+    Ok(())
+}
+
+fn purge_history(before: Option<i64>) -> Result<(), String> {
+    let Some(store) = HistoryStore::open_default()? else {
+        return Err("History is disabled (PTRUI_HISTORY_MAX=0)".to_string());
+    };
+    let cutoff = before.map(|day| day.max(0) as u64 * SECONDS_PER_DAY);
+    let removed = store.purge(cutoff)?;
+    match before {
+        Some(day) => println!("Deleted {} entries before {}", removed, format_day(day)),
+        None => println!("Deleted {} entries", removed),
+    }
+    Ok(())
+}
+
+/// History entries shaped as phrasebook cards, with romanization as pronunciation.
+fn history_phrases() -> Result<Vec<PhraseEntry>, String> {
+    let Some(store) = HistoryStore::open_default()? else {
+        return Ok(Vec::new());
+    };
+    Ok(store
+        .recent(usize::MAX >> 1)?
+        .into_iter()
+        .map(|entry| PhraseEntry {
+            pronunciation: romanize(&entry.target_lang, &entry.target_text),
+            source: entry.source_text,
+            target: entry.target_text,
+            source_lang: entry.source_lang,
+            target_lang: entry.target_lang,
+            added_at: entry.created_at,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(parse_args(&[]), Ok(Command::Tui(TuiOptions::default())));
+        assert_eq!(
+            parse_args(&args(&["--session", "thesis"])),
+            Ok(Command::Tui(TuiOptions {
+                session: Some("thesis".to_string()),
+                ..TuiOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args(&["--popup", "--sessions", "--offline"])),
+            Ok(Command::Tui(TuiOptions {
+                pick_session: true,
+                popup: true,
+                offline: true,
+                ..TuiOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args(&["--replay", "bug.keys", "--no-restore"])),
+            Ok(Command::Tui(TuiOptions {
+                replay: Some(PathBuf::from("bug.keys")),
+                no_restore: true,
+                ..TuiOptions::default()
+            }))
+        );
+        assert!(parse_args(&args(&["--record"])).is_err());
+        assert_eq!(
+            parse_args(&args(&["export", "anki", "deck.tsv"])),
+            Ok(Command::ExportAnki {
+                path: PathBuf::from("deck.tsv"),
+                from_history: false,
+            })
+        );
+        assert!(parse_args(&args(&["export"])).is_err());
+        assert_eq!(
+            parse_args(&args(&["history", "purge", "--before", "1970-01-03"])),
+            Ok(Command::HistoryPurge { before: Some(2) })
+        );
+        assert!(parse_args(&args(&["history", "purge", "--before", "soon"])).is_err());
+        assert_eq!(
+            parse_args(&args(&["filter", "--to", "de"])),
+            Ok(Command::Filter {
+                source_lang: "EN".to_string(),
+                target_lang: "DE".to_string(),
+            })
+        );
+        assert!(parse_args(&args(&["filter", "--from", "ES"])).is_err());
+        assert_eq!(
+            parse_args(&args(&[
+                "--file", "in.txt", "--to", "de", "--output", "out.txt"
+            ])),
+            Ok(Command::Translate {
+                input: Some(PathBuf::from("in.txt")),
+                source_lang: None,
+                target_lang: "DE".to_string(),
+                output: Some(PathBuf::from("out.txt")),
+                json: false,
+            })
+        );
+        assert_eq!(
+            parse_args(&args(&["--to", "es", "--json"])),
+            Ok(Command::Translate {
+                input: None,
+                source_lang: None,
+                target_lang: "ES".to_string(),
+                output: None,
+                json: true,
+            })
+        );
+        assert!(parse_args(&args(&["--file", "in.txt", "--from", "EN"])).is_err());
+        assert_eq!(
+            parse_args(&args(&["self", "check-update", "--download"])),
+            Ok(Command::CheckUpdate { download: true })
+        );
+        assert_eq!(
+            parse_args(&args(&["git-msg", "--log", "--to", "ja"])),
+            Ok(Command::GitMsg {
+                source_lang: None,
+                target_lang: "JA".to_string(),
+                log: true,
+            })
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::env;
+use std::io::{self, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// The system clipboard behind vim's yank and paste. Without a display server (e.g. over
+/// SSH) copies go out as an OSC 52 escape, which most terminals and tmux forward to the
+/// local clipboard; pasting then falls back to the pane's own register.
+pub struct Clipboard {
+    system: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    /// `PTRUI_CLIPBOARD=off` keeps yanks inside ptrui, `osc52` skips the display server.
+    pub fn from_env() -> Option<Self> {
+        let setting = env::var("PTRUI_CLIPBOARD").unwrap_or_default();
+        match setting.trim() {
+            "off" | "0" | "false" => None,
+            "osc52" => Some(Self { system: None }),
+            _ => Some(Self {
+                system: arboard::Clipboard::new().ok(),
+            }),
+        }
+    }
+
+    pub fn copy(&mut self, text: &str) -> Result<(), String> {
+        if let Some(system) = &mut self.system {
+            return system
+                .set_text(text)
+                .map_err(|err| format!("Failed to copy to the clipboard: {}", err));
+        }
+        let mut stdout = io::stdout();
+        stdout
+            .write_all(osc52(text).as_bytes())
+            .and_then(|_| stdout.flush())
+            .map_err(|err| format!("Failed to copy to the clipboard: {}", err))
+    }
+
+    /// The clipboard's text, if it can be read; OSC 52 has no widely supported read.
+    pub fn paste(&mut self) -> Option<String> {
+        self.system.as_mut()?.get_text().ok()
+    }
+}
+
+/// The escape sequence asking the terminal to put `text` on the clipboard.
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_osc52() {
+        assert_eq!(osc52("hola"), "\x1b]52;c;aG9sYQ==\x07");
+    }
+}
@@ -0,0 +1,43 @@
+/// Vim's own `:` commands, as opposed to ptrui's named ones (`:fetch`, `:theme`, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExCommand {
+    // `:12` jumps to line 12 (1-based).
+    GoToLine(usize),
+    // `:$`
+    GoToLastLine,
+    // `:%d` deletes every line of the active pane.
+    DeleteAll,
+    // `:q`, `:q!`, `:qa`
+    Quit,
+    // `:wq`, `:x`
+    WriteQuit,
+}
+
+/// Reads `line` (without the `:`) as an ex command; `None` leaves it to the named commands.
+pub fn parse_ex(line: &str) -> Option<ExCommand> {
+    let line = line.trim();
+    match line {
+        "$" => return Some(ExCommand::GoToLastLine),
+        "%d" | "%delete" => return Some(ExCommand::DeleteAll),
+        "q" | "q!" | "quit" | "qa" | "qa!" | "qall" => return Some(ExCommand::Quit),
+        "wq" | "wq!" | "x" | "xit" => return Some(ExCommand::WriteQuit),
+        _ => {}
+    }
+    line.parse().ok().map(ExCommand::GoToLine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vim_commands() {
+        assert_eq!(parse_ex("12"), Some(ExCommand::GoToLine(12)));
+        assert_eq!(parse_ex(" $ "), Some(ExCommand::GoToLastLine));
+        assert_eq!(parse_ex("%d"), Some(ExCommand::DeleteAll));
+        assert_eq!(parse_ex("q!"), Some(ExCommand::Quit));
+        assert_eq!(parse_ex("x"), Some(ExCommand::WriteQuit));
+        assert_eq!(parse_ex("-3"), None);
+        assert_eq!(parse_ex("theme gruvbox"), None);
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::chunk::DEFAULT_CHUNK_CHARS;
+use crate::languages::find_language_index;
+use crate::layout::PaneLayout;
+use crate::paths::config_dir;
+use crate::quickkeys::FunctionKeys;
+use crate::textarea::DEFAULT_UNDO_LEVELS;
+use crate::theme::Theme;
+
+/// Settings from `config.toml`. Environment variables override the values from the file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub api: ApiConfig,
+    pub llm: LlmConfig,
+    // Language codes for the left and right panes.
+    pub source_lang: Option<String>,
+    pub target_lang: Option<String>,
+    // A fixed wait before translating, instead of adapting to the typing rhythm.
+    pub debounce_ms: Option<u64>,
+    // Texts longer than this many characters are translated in several requests.
+    pub chunk_chars: Option<usize>,
+    // Edits each pane remembers for `u`, automatic translations included.
+    pub undo_levels: Option<usize>,
+    pub theme: Option<String>,
+    // Per-color overrides on top of the theme, e.g. `text = "black"` or `border = "#458588"`.
+    pub colors: BTreeMap<String, String>,
+    // Reopen the last session's panes at launch without asking.
+    pub restore_session: bool,
+    // "side-by-side" or "stacked" panes, and the left (or top) pane's share in percent.
+    pub layout: Option<String>,
+    pub split: Option<u16>,
+    // Function keys bound to quick actions, e.g. `F5 = "translate-now"`.
+    pub keys: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiConfig {
+    pub url: Option<String>,
+    pub key: Option<String>,
+    pub auth_header: Option<String>,
+    pub timeouts: TimeoutsConfig,
+}
+
+/// Translating through a chat-completions endpoint at `api.url`, used when a model is set.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LlmConfig {
+    pub model: Option<String>,
+    // System prompt with `{source}`, `{target}` and `{instructions}` placeholders.
+    pub prompt: Option<String>,
+    pub temperature: Option<f64>,
+    pub timeouts: TimeoutsConfig,
+}
+
+/// Seconds a provider may take to connect, to send the next part of an answer and to finish
+/// one request; unset keeps its default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeoutsConfig {
+    pub connect: Option<f64>,
+    pub read: Option<f64>,
+    pub deadline: Option<f64>,
+}
+
+impl Config {
+    /// `PTRUI_CONFIG`, else `config.toml` in the config directory.
+    pub fn path() -> Option<PathBuf> {
+        env::var_os("PTRUI_CONFIG")
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| config_dir().map(|dir| dir.join("config.toml")))
+    }
+
+    /// The config file (if any) with environment overrides applied. A missing file is fine;
+    /// a malformed one is an error rather than silently ignored.
+    pub fn load() -> Result<Self, String> {
+        let config = match Self::path() {
+            Some(path) => match fs::read_to_string(&path) {
+                Ok(contents) => Self::parse(&contents)
+                    .map_err(|message| format!("Invalid {}: {}", path.display(), message))?,
+                Err(err) if err.kind() == ErrorKind::NotFound => Self::default(),
+                Err(err) => return Err(format!("Failed to read {:?}: {}", path, err)),
+            },
+            None => Self::default(),
+        };
+        config.with_env(|name| env::var(name).ok())
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(contents).map_err(|err| err.message().to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Replaces file values with the environment variables that were set.
+    fn with_env(mut self, var: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let set = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+        if let Some(url) = set("TRANSLATION_API_URL") {
+            self.api.url = Some(url);
+        }
+        if let Some(key) = set("TRANSLATION_API_KEY") {
+            self.api.key = Some(key);
+        }
+        if let Some(header) = set("TRANSLATION_API_AUTH_HEADER") {
+            self.api.auth_header = Some(header);
+        }
+        if let Some(model) = set("TRANSLATION_LLM_MODEL") {
+            self.llm.model = Some(model);
+        }
+        if let Some(prompt) = set("TRANSLATION_LLM_PROMPT") {
+            self.llm.prompt = Some(prompt);
+        }
+        if let Some(temperature) = set("TRANSLATION_LLM_TEMPERATURE") {
+            let temperature = temperature
+                .trim()
+                .parse()
+                .map_err(|_| "TRANSLATION_LLM_TEMPERATURE must be a number".to_string())?;
+            self.llm.temperature = Some(temperature);
+        }
+        for (prefix, timeouts) in [
+            ("TRANSLATION_API", &mut self.api.timeouts),
+            ("TRANSLATION_LLM", &mut self.llm.timeouts),
+        ] {
+            for (name, value) in [
+                ("CONNECT_TIMEOUT", &mut timeouts.connect),
+                ("READ_TIMEOUT", &mut timeouts.read),
+                ("DEADLINE", &mut timeouts.deadline),
+            ] {
+                let key = format!("{}_{}", prefix, name);
+                if let Some(seconds) = set(&key) {
+                    let seconds = seconds
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("{} must be a number of seconds", key))?;
+                    *value = Some(seconds);
+                }
+            }
+        }
+        if let Some(theme) = set("PTRUI_THEME") {
+            self.theme = Some(theme);
+        }
+        if let Some(spec) = set("PTRUI_FKEYS") {
+            self.keys = FunctionKeys::bindings(&spec)?;
+        }
+        self.validate()?;
+        Ok(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for code in [&self.source_lang, &self.target_lang].into_iter().flatten() {
+            if find_language_index(code).is_none() {
+                return Err(format!("Unknown language code {:?}", code));
+            }
+        }
+        if let Some(name) = &self.theme {
+            Theme::named(name)?;
+        }
+        Theme::default().with_colors(&self.colors)?;
+        PaneLayout::new(self.layout.as_deref(), self.split)?;
+        if self.chunk_chars == Some(0) {
+            return Err("chunk_chars must be at least 1".to_string());
+        }
+        FunctionKeys::from_bindings(&self.keys)?;
+        if let Some(temperature) = self.llm.temperature
+            && !(0.0..=2.0).contains(&temperature)
+        {
+            return Err("LLM temperature must be between 0 and 2".to_string());
+        }
+        for (section, timeouts) in [("api", &self.api.timeouts), ("llm", &self.llm.timeouts)] {
+            for (name, value) in [
+                ("connect", timeouts.connect),
+                ("read", timeouts.read),
+                ("deadline", timeouts.deadline),
+            ] {
+                if value.is_some_and(|seconds: f64| !seconds.is_finite() || seconds <= 0.0) {
+                    return Err(format!(
+                        "{}.timeouts.{} must be a positive number of seconds",
+                        section, name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn debounce(&self) -> Option<Duration> {
+        self.debounce_ms.map(Duration::from_millis)
+    }
+
+    pub fn chunk_chars(&self) -> usize {
+        self.chunk_chars.unwrap_or(DEFAULT_CHUNK_CHARS)
+    }
+
+    pub fn undo_levels(&self) -> usize {
+        self.undo_levels.unwrap_or(DEFAULT_UNDO_LEVELS)
+    }
+
+    /// Writes one top-level setting (a TOML value) to the config file, leaving the rest of
+    /// it, comments included, as it was.
+    pub fn save_setting(key: &str, value: &str) -> Result<(), String> {
+        let path = Self::path().ok_or_else(|| "Cannot locate a config directory".to_string())?;
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("Failed to read {:?}: {}", path, err)),
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {:?}: {}", parent, err))?;
+        }
+        fs::write(&path, with_setting(&contents, key, value))
+            .map_err(|err| format!("Failed to write {:?}: {}", path, err))
+    }
+}
+
+/// `contents` with `key = value` replaced, or added after the last top-level setting (top-level
+/// keys must come before the first table).
+fn with_setting(contents: &str, key: &str, value: &str) -> String {
+    let setting = format!("{} = {}", key, value);
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let first_table = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+    let top_level = &lines[..first_table];
+    match top_level.iter().position(|line| {
+        line.split('=')
+            .next()
+            .is_some_and(|name| name.trim() == key)
+    }) {
+        Some(index) => lines[index] = setting,
+        None => {
+            let end = top_level
+                .iter()
+                .rposition(|line| !line.trim().is_empty())
+                .map_or(0, |index| index + 1);
+            lines.insert(end, setting);
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_overrides_the_file() {
+        let config = Config::parse(
+            r#"
+            source_lang = "DE"
+            debounce_ms = 400
+            theme = "deuteranopia"
+            restore_session = true
+
+            [api]
+            url = "https://api.example.com/translate"
+            key = "from-file"
+
+            [api.timeouts]
+            deadline = 60
+
+            [keys]
+            F5 = "translate-now"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.debounce(), Some(Duration::from_millis(400)));
+        let config = config
+            .with_env(|name| match name {
+                "TRANSLATION_API_KEY" => Some("from-env".to_string()),
+                "PTRUI_FKEYS" => Some("F6=swap-text".to_string()),
+                "TRANSLATION_LLM_DEADLINE" => Some("0.5".to_string()),
+                "TRANSLATION_API_READ_TIMEOUT" => Some("10".to_string()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(
+            config.api.url.as_deref(),
+            Some("https://api.example.com/translate")
+        );
+        assert_eq!(config.api.key.as_deref(), Some("from-env"));
+        assert_eq!(config.api.timeouts.deadline, Some(60.0));
+        assert_eq!(config.llm.timeouts.deadline, Some(0.5));
+        assert_eq!(config.api.timeouts.read, Some(10.0));
+        assert_eq!(config.source_lang.as_deref(), Some("DE"));
+        assert!(config.restore_session);
+        assert_eq!(
+            config.keys,
+            BTreeMap::from([("F6".to_string(), "swap-text".to_string())])
+        );
+
+        assert!(Config::parse("theme = \"neon\"").is_err());
+        assert!(Config::parse("target_lang = \"XX\"").is_err());
+        assert!(Config::parse("colour = \"red\"").is_err());
+        assert!(Config::parse("[colors]\ntext = \"ultraviolet\"").is_err());
+        assert!(Config::parse("[llm]\ntemperature = 5.0").is_err());
+        assert!(Config::parse("split = 5").is_err());
+        assert!(Config::parse("chunk_chars = 0").is_err());
+        assert!(Config::parse("[llm.timeouts]\ndeadline = -1.0").is_err());
+        assert!(Config::parse("[api.timeouts]\nread = 0").is_err());
+    }
+
+    #[test]
+    fn saves_settings_in_place() {
+        let file = "# mine\ntheme = \"dark\"\n\n[api]\nurl = \"x\"\n";
+        assert_eq!(
+            with_setting(file, "layout", "\"stacked\""),
+            "# mine\ntheme = \"dark\"\nlayout = \"stacked\"\n\n[api]\nurl = \"x\"\n"
+        );
+        assert_eq!(
+            with_setting(file, "theme", "\"light\""),
+            "# mine\ntheme = \"light\"\n\n[api]\nurl = \"x\"\n"
+        );
+        assert_eq!(with_setting("", "split", "60"), "split = 60\n");
+    }
+}
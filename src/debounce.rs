@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+
+/// Wait before translating an edit made after a pause, or a non-typing change like a new language.
+pub const PAUSE_DELAY: Duration = Duration::from_millis(150);
+// Bounds on the wait while keystrokes keep coming.
+const BURST_DELAY_MIN: Duration = Duration::from_millis(250);
+const BURST_DELAY_MAX: Duration = Duration::from_millis(800);
+// Keystrokes closer together than this count as one burst of typing.
+const BURST_GAP: Duration = Duration::from_secs(1);
+
+/// Adapts the translation debounce to the typing rhythm: quick after a pause, longer mid-burst
+/// so every keystroke doesn't cost a request, and immediate at the end of a sentence.
+#[derive(Debug, Default)]
+pub struct Debouncer {
+    last_keystroke: Option<Instant>,
+    // Smoothed gap between keystrokes in the current burst.
+    typing_gap: Option<Duration>,
+    // Set by `debounce_ms` in the config: always wait this long instead.
+    fixed: Option<Duration>,
+}
+
+impl Debouncer {
+    pub fn new(fixed: Option<Duration>) -> Self {
+        Self {
+            fixed,
+            ..Self::default()
+        }
+    }
+
+    /// Wait before translating a change that isn't typing.
+    pub fn pause_delay(&self) -> Duration {
+        self.fixed.unwrap_or(PAUSE_DELAY)
+    }
+
+    /// Records a keystroke that changed the text and returns how long to wait before translating.
+    pub fn delay_after(&mut self, now: Instant, ends_sentence: bool) -> Duration {
+        let gap = self
+            .last_keystroke
+            .map(|last| now.saturating_duration_since(last))
+            .filter(|gap| *gap < BURST_GAP);
+        self.last_keystroke = Some(now);
+        self.typing_gap = gap.map(|gap| match self.typing_gap {
+            Some(average) => (average * 3 + gap) / 4,
+            None => gap,
+        });
+        if ends_sentence {
+            return Duration::ZERO;
+        }
+        if let Some(fixed) = self.fixed {
+            return fixed;
+        }
+        match self.typing_gap {
+            // A few keystrokes' worth, so the next key usually lands before the request.
+            Some(gap) => (gap * 3).clamp(BURST_DELAY_MIN, BURST_DELAY_MAX),
+            None => PAUSE_DELAY,
+        }
+    }
+}
+
+/// Enter and sentence-ending punctuation translate right away.
+pub fn ends_sentence(c: char) -> bool {
+    matches!(
+        c,
+        '\n' | '.' | '!' | '?' | ';' | '…' | '。' | '！' | '？' | '；'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adapts_to_typing_rhythm() {
+        let mut debouncer = Debouncer::default();
+        let start = Instant::now();
+        assert_eq!(debouncer.delay_after(start, false), PAUSE_DELAY);
+        let burst = start + Duration::from_millis(120);
+        assert_eq!(
+            debouncer.delay_after(burst, false),
+            Duration::from_millis(360)
+        );
+        let after_pause = burst + Duration::from_secs(2);
+        assert_eq!(debouncer.delay_after(after_pause, false), PAUSE_DELAY);
+        let stop = after_pause + Duration::from_millis(100);
+        assert_eq!(debouncer.delay_after(stop, true), Duration::ZERO);
+    }
+}
@@ -0,0 +1,137 @@
+// Very common function words per Latin-script language; enough to tell short snippets apart.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "EN",
+        &[
+            "the", "and", "is", "of", "to", "in", "it", "that", "you", "with", "for", "this",
+            "are", "was", "have",
+        ],
+    ),
+    (
+        "ES",
+        &[
+            "el", "la", "de", "que", "y", "en", "los", "las", "es", "por", "con", "una", "para",
+            "del", "está",
+        ],
+    ),
+    (
+        "FR",
+        &[
+            "le", "la", "les", "de", "et", "est", "un", "une", "des", "du", "que", "pour", "pas",
+            "dans", "vous",
+        ],
+    ),
+    (
+        "DE",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "zu", "ein", "eine", "mit", "den",
+            "sie", "es", "auf",
+        ],
+    ),
+    (
+        "IT",
+        &[
+            "il", "di", "che", "è", "e", "la", "non", "per", "un", "una", "sono", "gli", "del",
+            "della", "con",
+        ],
+    ),
+    (
+        "PT",
+        &[
+            "o", "a", "de", "que", "e", "do", "da", "não", "em", "um", "uma", "para", "com", "os",
+            "você",
+        ],
+    ),
+    (
+        "NL",
+        &[
+            "de", "het", "een", "en", "van", "is", "niet", "ik", "dat", "je", "op", "te", "zijn",
+            "met", "voor",
+        ],
+    ),
+    (
+        "PL",
+        &[
+            "i", "w", "nie", "się", "na", "to", "że", "jest", "z", "do", "jak", "co", "ale", "tak",
+            "od",
+        ],
+    ),
+    (
+        "SV",
+        &[
+            "och", "att", "det", "som", "en", "är", "på", "av", "för", "med", "inte", "jag", "har",
+            "till", "den",
+        ],
+    ),
+];
+
+/// Guesses the language of a text: writing system first, then stopword overlap for Latin
+/// scripts. Returns a code from `BUILTIN_LANGUAGES` and a 0..=1 confidence.
+pub fn detect_language(text: &str) -> Option<(&'static str, f64)> {
+    if let Some(code) = detect_script(text) {
+        return Some((code, 1.0));
+    }
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(code, stopwords)| {
+            let hits = words
+                .iter()
+                .filter(|word| stopwords.contains(&word.as_str()))
+                .count();
+            (*code, hits)
+        })
+        .collect();
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+    let (best, hits) = scores[0];
+    if hits == 0 {
+        return None;
+    }
+    let runner_up = scores[1].1;
+    Some((best, (hits - runner_up) as f64 / hits as f64))
+}
+
+/// Languages identified by their writing system alone.
+fn detect_script(text: &str) -> Option<&'static str> {
+    let mut han = false;
+    for c in text.chars() {
+        match c as u32 {
+            0x3040..=0x30FF => return Some("JA"),
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => return Some("KO"),
+            0x0400..=0x04FF => return Some("RU"),
+            0x4E00..=0x9FFF => han = true,
+            _ => {}
+        }
+    }
+    han.then_some("ZH")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_scripts_and_stopwords() {
+        assert_eq!(
+            detect_language("日本語の文章").map(|(code, _)| code),
+            Some("JA")
+        );
+        assert_eq!(
+            detect_language("你好世界").map(|(code, _)| code),
+            Some("ZH")
+        );
+        assert_eq!(
+            detect_language("Der Hund ist nicht mit der Katze").map(|(code, _)| code),
+            Some("DE")
+        );
+        assert_eq!(
+            detect_language("The cat is on the mat").map(|(code, _)| code),
+            Some("EN")
+        );
+        assert_eq!(detect_language("12345"), None);
+    }
+}
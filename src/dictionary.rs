@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::api::LOOKUP_DEADLINE;
+
+const WIKTIONARY_DEFINITION_URL: &str = "https://en.wiktionary.org/api/rest_v1/page/definition/";
+
+#[derive(Debug, Deserialize)]
+struct WiktionaryUsage {
+    #[serde(rename = "partOfSpeech")]
+    part_of_speech: String,
+    #[serde(default)]
+    definitions: Vec<WiktionaryDefinition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WiktionaryDefinition {
+    definition: String,
+}
+
+/// One part-of-speech section for a looked-up word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryEntry {
+    pub part_of_speech: String,
+    // Plain-text senses; inflection notes such as "plural of" or conjugation forms live here.
+    pub definitions: Vec<String>,
+}
+
+pub struct DictionaryPanel {
+    pub word: String,
+    pub language: &'static str,
+    pub entries: Vec<DictionaryEntry>,
+    pub scroll: u16,
+}
+
+/// Queries Wiktionary for a word and keeps only the sections for the given language.
+pub fn lookup_wiktionary(
+    client: &reqwest::blocking::Client,
+    word: &str,
+    language_code: &str,
+) -> Result<Vec<DictionaryEntry>, String> {
+    let mut url = Url::parse(WIKTIONARY_DEFINITION_URL).map_err(|err| err.to_string())?;
+    url.path_segments_mut()
+        .map_err(|_| "Invalid dictionary URL".to_string())?
+        .pop_if_empty()
+        .push(word);
+    let response = client
+        .get(url)
+        .header(
+            reqwest::header::USER_AGENT,
+            concat!("ptrui/", env!("CARGO_PKG_VERSION")),
+        )
+        .timeout(LOOKUP_DEADLINE)
+        .send()
+        .map_err(|err| format!("Failed to call Wiktionary: {}", err))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        return Err(format!("Wiktionary error ({})", response.status()));
+    }
+    let usages: HashMap<String, Vec<WiktionaryUsage>> = response
+        .json()
+        .map_err(|err| format!("Invalid Wiktionary response: {}", err))?;
+
+    let key = language_code.to_ascii_lowercase();
+    Ok(usages
+        .into_iter()
+        .filter(|(language, _)| *language == key)
+        .flat_map(|(_, usages)| usages)
+        .map(|usage| DictionaryEntry {
+            part_of_speech: usage.part_of_speech,
+            definitions: usage
+                .definitions
+                .iter()
+                .map(|definition| strip_html(&definition.definition))
+                .filter(|definition| !definition.is_empty())
+                .collect(),
+        })
+        .collect())
+}
+
+/// Drops markup from Wiktionary's HTML definitions and decodes the common entities.
+fn strip_html(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .trim()
+        .to_string()
+}
@@ -0,0 +1,144 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+// Above this many token pairs left after trimming the common ends, the middle is shown as
+// one removal and one insertion rather than spending the memory on a full comparison.
+const MAX_COMPARISONS: usize = 4_000_000;
+
+/// A run of text that a post-edit kept, added or removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// The diff overlay: hand edits compared with the machine translation they started from, or
+/// the source compared with its back-translation.
+pub struct DiffView {
+    // What is being compared, for the title.
+    pub title: String,
+    pub changes: Vec<Change>,
+    pub scroll: u16,
+}
+
+impl DiffView {
+    /// Words added and removed, for the title.
+    pub fn counts(&self) -> (usize, usize) {
+        let words = |text: &str| text.unicode_words().count();
+        self.changes
+            .iter()
+            .fold((0, 0), |(added, removed), change| match change {
+                Change::Added(text) => (added + words(text), removed),
+                Change::Removed(text) => (added, removed + words(text)),
+                Change::Same(_) => (added, removed),
+            })
+    }
+}
+
+/// Word-level differences from `old` to `new`: words, spaces and punctuation are compared
+/// as units, and neighbouring units of the same kind are merged.
+pub fn word_diff(old: &str, new: &str) -> Vec<Change> {
+    let old: Vec<&str> = old.split_word_bounds().collect();
+    let new: Vec<&str> = new.split_word_bounds().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_middle, new_middle) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut changes = Vec::new();
+    push(&mut changes, Change::Same(old[..prefix].concat()));
+    if old_middle.len().saturating_mul(new_middle.len()) > MAX_COMPARISONS {
+        push(&mut changes, Change::Removed(old_middle.concat()));
+        push(&mut changes, Change::Added(new_middle.concat()));
+    } else {
+        for change in lcs_diff(old_middle, new_middle) {
+            push(&mut changes, change);
+        }
+    }
+    push(
+        &mut changes,
+        Change::Same(old[old.len() - suffix..].concat()),
+    );
+    changes
+}
+
+/// Diffs two token lists through their longest common subsequence.
+fn lcs_diff(old: &[&str], new: &[&str]) -> Vec<Change> {
+    // lengths[i][j]: the longest common subsequence of old[i..] and new[j..].
+    let mut lengths = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut changes = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            changes.push(Change::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lengths[i + 1][j] >= lengths[i][j + 1]) {
+            changes.push(Change::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            changes.push(Change::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    changes
+}
+
+/// Appends a change, merging it into the last one when they are of the same kind.
+fn push(changes: &mut Vec<Change>, change: Change) {
+    let text = match &change {
+        Change::Same(text) | Change::Added(text) | Change::Removed(text) => text,
+    };
+    if text.is_empty() {
+        return;
+    }
+    match (changes.last_mut(), &change) {
+        (Some(Change::Same(last)), Change::Same(text))
+        | (Some(Change::Added(last)), Change::Added(text))
+        | (Some(Change::Removed(last)), Change::Removed(text)) => last.push_str(text),
+        _ => changes.push(change),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_by_word() {
+        let changes = word_diff("the big red house.", "the small red house!");
+        assert_eq!(
+            changes,
+            [
+                Change::Same("the ".to_string()),
+                Change::Removed("big".to_string()),
+                Change::Added("small".to_string()),
+                Change::Same(" red house".to_string()),
+                Change::Removed(".".to_string()),
+                Change::Added("!".to_string()),
+            ]
+        );
+        let view = DiffView {
+            title: String::new(),
+            changes,
+            scroll: 0,
+        };
+        assert_eq!(view.counts(), (1, 1));
+    }
+}
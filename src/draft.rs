@@ -0,0 +1,101 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths::data_dir;
+
+/// Snapshot of both panes, autosaved so a crash or stray Ctrl+c doesn't lose work.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Draft {
+    pub left_language: String,
+    pub right_language: String,
+    pub left_text: String,
+    pub right_text: String,
+    pub right_active: bool,
+    // Whether each pane was in vim insert mode; other modes come back as normal mode.
+    #[serde(default)]
+    pub left_insert: bool,
+    #[serde(default)]
+    pub right_insert: bool,
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+impl Draft {
+    pub fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("draft.json"))
+    }
+
+    pub fn load() -> Option<Self> {
+        let contents = fs::read_to_string(Self::path()?).ok()?;
+        serde_json::from_str::<Self>(&contents)
+            .ok()
+            .filter(|draft| !draft.is_empty())
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or_else(|| "Cannot locate a data directory".to_string())?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {:?}: {}", parent, err))?;
+        }
+        let contents = serde_json::to_string(self).map_err(|err| err.to_string())?;
+        // Write then rename so a crash mid-save never leaves a truncated draft.
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, contents).map_err(|err| format!("Failed to write {:?}: {}", temp, err))?;
+        fs::rename(&temp, &path).map_err(|err| format!("Failed to write {:?}: {}", path, err))
+    }
+
+    pub fn discard() -> Result<(), String> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        match fs::remove_file(&path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                Err(format!("Failed to remove {:?}: {}", path, err))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left_text.trim().is_empty() && self.right_text.trim().is_empty()
+    }
+
+    /// Compares pane contents and languages, ignoring when the draft was saved.
+    pub fn same_content(&self, other: &Draft) -> bool {
+        Draft {
+            saved_at: other.saved_at,
+            ..self.clone()
+        } == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft(left: &str) -> Draft {
+        Draft {
+            left_language: "EN".to_string(),
+            right_language: "ES".to_string(),
+            left_text: left.to_string(),
+            right_text: String::new(),
+            right_active: false,
+            left_insert: false,
+            right_insert: false,
+            saved_at: 1,
+        }
+    }
+
+    #[test]
+    fn compares_content_without_timestamp() {
+        let mut later = draft("hello");
+        later.saved_at = 99;
+        assert!(draft("hello").same_content(&later));
+        assert!(!draft("hello").same_content(&draft("hello there")));
+        assert!(draft("  \n").is_empty());
+    }
+}
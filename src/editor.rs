@@ -0,0 +1,129 @@
+use std::env;
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::{self, Stdout, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crossterm::event::{DisableBracketedPaste, EnableBracketedPaste};
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+
+/// `$VISUAL`, else `$EDITOR`, else `vi`, split into program and arguments so values like
+/// `code --wait` work.
+fn editor_command(visual: Option<String>, editor: Option<String>) -> Vec<String> {
+    let command = [visual, editor]
+        .into_iter()
+        .flatten()
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    command.split_whitespace().map(str::to_string).collect()
+}
+
+/// Opens `text` in the user's editor and returns what was saved. The TUI is suspended for the
+/// editor's lifetime and restored afterwards, whatever the editor did.
+pub fn edit_in_editor(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    text: &str,
+) -> io::Result<Result<String, String>> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
+    let edited = run_editor(text);
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableBracketedPaste
+    )?;
+    terminal.clear()?;
+    Ok(edited)
+}
+
+/// Creates a fresh directory only the user can enter. Failing when the name is taken keeps
+/// another user from planting a file or symlink where the text is written.
+fn private_dir() -> io::Result<PathBuf> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.subsec_nanos());
+    let dir = env::temp_dir().join(format!("ptrui-{}-{}", std::process::id(), nanos));
+    let mut builder = DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(&dir)?;
+    Ok(dir)
+}
+
+/// Writes `text` to a new file readable by the user alone.
+fn write_private(path: &Path, text: &str) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(text.as_bytes())
+}
+
+fn run_editor(text: &str) -> Result<String, String> {
+    let dir = private_dir().map_err(|err| format!("Failed to create a temp directory: {}", err))?;
+    let edited = edit_file(&dir.join("ptrui.txt"), text);
+    let _ = fs::remove_dir_all(&dir);
+    edited
+}
+
+fn edit_file(path: &Path, text: &str) -> Result<String, String> {
+    write_private(path, text).map_err(|err| format!("Failed to write {:?}: {}", path, err))?;
+    let command = editor_command(env::var("VISUAL").ok(), env::var("EDITOR").ok());
+    let status = Command::new(&command[0])
+        .args(&command[1..])
+        .arg(path)
+        .status()
+        .map_err(|err| format!("Failed to start {}: {}", command[0], err));
+    match status {
+        Ok(status) if status.success() => {
+            fs::read_to_string(path).map_err(|err| format!("Failed to read {:?}: {}", path, err))
+        }
+        Ok(status) => Err(format!(
+            "{} exited with {}; text left unchanged",
+            command[0], status
+        )),
+        Err(message) => Err(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_visual_then_editor() {
+        assert_eq!(
+            editor_command(Some(" ".to_string()), Some("code --wait".to_string())),
+            ["code", "--wait"]
+        );
+        assert_eq!(editor_command(None, None), ["vi"]);
+    }
+
+    #[test]
+    fn writes_into_a_new_private_file() {
+        let dir = private_dir().unwrap();
+        let path = dir.join("ptrui.txt");
+        write_private(&path, "secret").unwrap();
+        assert!(write_private(&path, "again").is_err());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&dir), 0o700);
+            assert_eq!(mode(&path), 0o600);
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "secret");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
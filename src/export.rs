@@ -0,0 +1,250 @@
+use std::fs;
+use std::path::Path;
+
+use ratatui::text::Span;
+use serde::Serialize;
+
+use crate::languages::language_name;
+use crate::segment::align_sentences;
+
+/// Output format for exporting the current pair, picked from the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Text,
+    Markdown,
+    Json,
+    Html,
+}
+
+impl ExportFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("md" | "markdown") => ExportFormat::Markdown,
+            Some("json") => ExportFormat::Json,
+            Some("html" | "htm") => ExportFormat::Html,
+            _ => ExportFormat::Text,
+        }
+    }
+}
+
+/// The source and translated sides of a pair, in the direction they were translated.
+pub struct ExportPair<'a> {
+    pub source_lang: &'a str,
+    pub target_lang: &'a str,
+    pub source: &'a str,
+    pub target: &'a str,
+    // Pair sentences rather than lines, for prose whose line breaks don't correspond.
+    pub sentences: bool,
+}
+
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    source_lang: &'a str,
+    target_lang: &'a str,
+    source: &'a str,
+    target: &'a str,
+    // Line-by-line pairs, matching the side-by-side layout of the other formats.
+    lines: Vec<[&'a str; 2]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sentences: Option<Vec<[&'a str; 2]>>,
+}
+
+pub fn render_export(format: ExportFormat, pair: &ExportPair) -> Result<String, String> {
+    match format {
+        ExportFormat::Text => Ok(text_columns(pair)),
+        ExportFormat::Markdown => Ok(markdown_table(pair)),
+        ExportFormat::Html => Ok(html_document(pair)),
+        ExportFormat::Json => {
+            let export = JsonExport {
+                source_lang: pair.source_lang,
+                target_lang: pair.target_lang,
+                source: pair.source,
+                target: pair.target,
+                lines: line_pairs(pair),
+                sentences: pair
+                    .sentences
+                    .then(|| align_sentences(pair.source, pair.target)),
+            };
+            serde_json::to_string_pretty(&export)
+                .map(|json| json + "\n")
+                .map_err(|err| err.to_string())
+        }
+    }
+}
+
+pub fn write_export(path: &Path, pair: &ExportPair) -> Result<(), String> {
+    let contents = render_export(ExportFormat::from_path(path), pair)?;
+    fs::write(path, contents).map_err(|err| format!("Failed to write {:?}: {}", path, err))
+}
+
+/// Pairs source and target lines, padding the shorter side with empty lines.
+fn line_pairs<'a>(pair: &ExportPair<'a>) -> Vec<[&'a str; 2]> {
+    let source: Vec<&str> = pair.source.lines().collect();
+    let target: Vec<&str> = pair.target.lines().collect();
+    (0..source.len().max(target.len()))
+        .map(|i| {
+            [
+                source.get(i).copied().unwrap_or_default(),
+                target.get(i).copied().unwrap_or_default(),
+            ]
+        })
+        .collect()
+}
+
+/// Rows for the side-by-side formats: sentences when asked for, otherwise lines.
+fn rows<'a>(pair: &ExportPair<'a>) -> Vec<[&'a str; 2]> {
+    if pair.sentences {
+        align_sentences(pair.source, pair.target)
+    } else {
+        line_pairs(pair)
+    }
+}
+
+fn text_columns(pair: &ExportPair) -> String {
+    let rows = rows(pair);
+    let width = rows
+        .iter()
+        .map(|[source, _]| Span::raw(*source).width())
+        .chain([Span::raw(pair.source_lang).width()])
+        .max()
+        .unwrap_or_default();
+    let mut out = String::new();
+    for [left, right] in [[pair.source_lang, pair.target_lang]]
+        .into_iter()
+        .chain(rows)
+    {
+        let padding = width - Span::raw(left).width();
+        let line = format!("{}{}  |  {}", left, " ".repeat(padding), right);
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn markdown_table(pair: &ExportPair) -> String {
+    let mut out = format!(
+        "| {} | {} |\n| --- | --- |\n",
+        markdown_cell(pair.source_lang),
+        markdown_cell(pair.target_lang)
+    );
+    for [source, target] in rows(pair) {
+        out.push_str(&format!(
+            "| {} | {} |\n",
+            markdown_cell(source),
+            markdown_cell(target)
+        ));
+    }
+    out
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:72rem;\
+color:#222}h1{font-size:1.2rem}table{border-collapse:collapse;width:100%;table-layout:fixed}\
+th,td{border:1px solid #ccc;padding:.5rem .75rem;vertical-align:top;text-align:left;\
+white-space:pre-wrap}th{background:#f3f3f3}tr:nth-child(even) td{background:#fafafa}\
+td.n{width:2.5rem;color:#999;text-align:right}";
+
+/// Standalone page with a two-column table; each column carries its `lang` attribute so
+/// browsers pick suitable fonts and screen readers the right voice.
+fn html_document(pair: &ExportPair) -> String {
+    let source_code = pair.source_lang.to_ascii_lowercase();
+    let target_code = pair.target_lang.to_ascii_lowercase();
+    let source_name = language_name(pair.source_lang);
+    let target_name = language_name(pair.target_lang);
+    let title = format!("{} → {}", source_name, target_name);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"generator\" content=\"ptrui\">\n\
+         <meta name=\"source-language\" content=\"{}\">\n\
+         <meta name=\"target-language\" content=\"{}\">\n\
+         <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n<table>\n\
+         <thead><tr><th class=\"n\">#</th><th lang=\"{}\">{}</th><th lang=\"{}\">{}</th></tr></thead>\n\
+         <tbody>\n",
+        source_code,
+        html_escape(pair.source_lang),
+        html_escape(pair.target_lang),
+        html_escape(&title),
+        HTML_STYLE,
+        html_escape(&title),
+        source_code,
+        html_escape(source_name),
+        target_code,
+        html_escape(target_name),
+    );
+    for (index, [source, target]) in rows(pair).into_iter().enumerate() {
+        out.push_str(&format!(
+            "<tr><td class=\"n\">{}</td><td lang=\"{}\">{}</td><td lang=\"{}\">{}</td></tr>\n",
+            index + 1,
+            source_code,
+            html_escape(source),
+            target_code,
+            html_escape(target)
+        ));
+    }
+    out.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    out
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAIR: ExportPair = ExportPair {
+        source_lang: "EN",
+        target_lang: "ES",
+        source: "Hello|there\nBye",
+        target: "Hola",
+        sentences: false,
+    };
+
+    #[test]
+    fn renders_each_format() {
+        assert_eq!(
+            render_export(ExportFormat::Text, &PAIR).unwrap(),
+            "EN           |  ES\nHello|there  |  Hola\nBye          |\n"
+        );
+        assert_eq!(
+            render_export(ExportFormat::Markdown, &PAIR).unwrap(),
+            "| EN | ES |\n| --- | --- |\n| Hello\\|there | Hola |\n| Bye |  |\n"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&render_export(ExportFormat::Json, &PAIR).unwrap()).unwrap();
+        assert_eq!(json["lines"][1], serde_json::json!(["Bye", ""]));
+        assert_eq!(
+            ExportFormat::from_path(Path::new("notes.MD")),
+            ExportFormat::Markdown
+        );
+    }
+
+    #[test]
+    fn html_aligns_sentences_and_escapes() {
+        let pair = ExportPair {
+            source_lang: "EN",
+            target_lang: "ES",
+            source: "One <b>. Two\nlines.",
+            target: "Uno <b>. Dos líneas.",
+            sentences: true,
+        };
+        let html = render_export(ExportFormat::Html, &pair).unwrap();
+        assert!(html.contains("<title>English → Spanish</title>"));
+        assert!(html.contains(
+            "<tr><td class=\"n\">2</td><td lang=\"en\">Two</td><td lang=\"es\">Dos líneas.</td></tr>"
+        ));
+        assert!(html.contains("<td lang=\"en\">One &lt;b&gt;.</td>"));
+    }
+}
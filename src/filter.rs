@@ -0,0 +1,134 @@
+use std::io::{self, Read, Write};
+use std::time::Instant;
+
+use crate::api::TranslateOptions;
+use crate::config::Config;
+use crate::notify::Notifier;
+use crate::provider::{self, TranslationProvider};
+
+// Most translation APIs cap the number of texts per request.
+const LINES_PER_REQUEST: usize = 50;
+
+/// Translates stdin to stdout line by line, for use as an editor filter
+/// (`:'<,'>!ptrui filter --to DE`).
+pub fn run_filter(source_lang: &str, target_lang: &str) -> Result<(), String> {
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|err| format!("Failed to read stdin: {}", err))?;
+    let provider = provider::from_config(&Config::load()?)?;
+    let started = Instant::now();
+    let output = filter_text(&input, |lines| {
+        translate_lines(provider.as_ref(), lines, source_lang, target_lang)
+    });
+    if let Some(notifier) = Notifier::from_env() {
+        notifier.job_finished(
+            "ptrui filter",
+            started.elapsed(),
+            output.as_ref().err().map(String::as_str),
+        );
+    }
+    io::stdout()
+        .write_all(output?.as_bytes())
+        .map_err(|err| format!("Failed to write stdout: {}", err))
+}
+
+/// Translates many lines in as few requests as the API allows, preserving their order.
+pub fn translate_lines(
+    provider: &dyn TranslationProvider,
+    lines: &[&str],
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<Vec<String>, String> {
+    let mut translated = Vec::with_capacity(lines.len());
+    for chunk in lines.chunks(LINES_PER_REQUEST) {
+        translated.extend(provider.translate_batch(
+            chunk,
+            source_lang,
+            target_lang,
+            &TranslateOptions::default(),
+        )?);
+    }
+    Ok(translated)
+}
+
+/// Translates the text of each non-blank line, keeping its indentation, trailing whitespace
+/// (including `\r`), blank lines and the final newline exactly as they were.
+pub fn filter_text(
+    input: &str,
+    translate: impl FnOnce(&[&str]) -> Result<Vec<String>, String>,
+) -> Result<String, String> {
+    translate_line_parts(input, |line| Some(split_line(line)), translate)
+}
+
+/// Translates the part of each line picked by `split` (prefix, text, suffix); lines it
+/// returns `None` for, or with empty text, are copied through untouched.
+pub fn translate_line_parts<'a>(
+    input: &'a str,
+    split: impl Fn(&'a str) -> Option<(&'a str, &'a str, &'a str)>,
+    translate: impl FnOnce(&[&str]) -> Result<Vec<String>, String>,
+) -> Result<String, String> {
+    let lines: Vec<(&str, &str, &str)> = input
+        .split('\n')
+        .map(|line| split(line).unwrap_or((line, "", "")))
+        .collect();
+    let bodies: Vec<&str> = lines
+        .iter()
+        .map(|(_, body, _)| *body)
+        .filter(|body| !body.is_empty())
+        .collect();
+    if bodies.is_empty() {
+        return Ok(input.to_string());
+    }
+    let translated = translate(&bodies)?;
+    if translated.len() != bodies.len() {
+        return Err(format!(
+            "Expected {} translated lines, got {}",
+            bodies.len(),
+            translated.len()
+        ));
+    }
+    let mut translated = translated.into_iter();
+    let output: Vec<String> = lines
+        .iter()
+        .map(|(indent, body, trailing)| {
+            if body.is_empty() {
+                format!("{}{}", indent, trailing)
+            } else {
+                // A line must stay a line, or the editor's range would shift.
+                let text = translated.next().unwrap_or_default().replace('\n', " ");
+                format!("{}{}{}", indent, text.trim(), trailing)
+            }
+        })
+        .collect();
+    Ok(output.join("\n"))
+}
+
+/// Splits a line into leading whitespace, text and trailing whitespace.
+fn split_line(line: &str) -> (&str, &str, &str) {
+    let body_start = line.len() - line.trim_start().len();
+    let (indent, rest) = line.split_at(body_start);
+    let body = rest.trim_end();
+    (indent, body, &rest[body.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_indentation_and_line_structure() {
+        let input = "    hello world\n\n\tbye \r\n";
+        let output = filter_text(input, |lines| {
+            assert_eq!(lines, ["hello world", "bye"]);
+            Ok(lines.iter().map(|line| line.to_uppercase()).collect())
+        });
+        assert_eq!(output.as_deref(), Ok("    HELLO WORLD\n\n\tBYE \r\n"));
+    }
+
+    #[test]
+    fn blank_input_skips_the_api() {
+        let output = filter_text("  \n\n", |_| Err("called".to_string()));
+        assert_eq!(output.as_deref(), Ok("  \n\n"));
+    }
+}
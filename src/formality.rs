@@ -0,0 +1,61 @@
+/// How formal a translation into a pane's language should be (DeepL's `formality`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Formality {
+    #[default]
+    Default,
+    More,
+    Less,
+}
+
+impl Formality {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Default => Self::More,
+            Self::More => Self::Less,
+            Self::Less => Self::Default,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::More => "formal",
+            Self::Less => "informal",
+        }
+    }
+
+    /// The `formality` request parameter for translating into `target_lang`. Languages
+    /// without formal and informal forms get none, so the request doesn't fail.
+    pub fn parameter(self, target_lang: &str) -> Option<&'static str> {
+        if !supports_formality(target_lang) {
+            return None;
+        }
+        match self {
+            Self::Default => None,
+            Self::More => Some("more"),
+            Self::Less => Some("less"),
+        }
+    }
+}
+
+/// Target languages DeepL accepts a formality for.
+pub fn supports_formality(lang: &str) -> bool {
+    let base = lang.split('-').next().unwrap_or_default();
+    ["DE", "FR", "IT", "ES", "NL", "PL", "PT", "JA", "RU"]
+        .iter()
+        .any(|code| code.eq_ignore_ascii_case(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_formality_for_unsupported_languages() {
+        assert_eq!(Formality::More.parameter("DE"), Some("more"));
+        assert_eq!(Formality::Less.parameter("pt-BR"), Some("less"));
+        assert_eq!(Formality::More.parameter("EN"), None);
+        assert_eq!(Formality::Default.parameter("FR"), None);
+        assert_eq!(Formality::Less.next(), Formality::Default);
+    }
+}
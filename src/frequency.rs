@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crate::paths::data_dir;
+
+const BUNDLED_LISTS: &[(&str, &str)] = &[
+    ("DE", include_str!("data/frequency/de.txt")),
+    ("EN", include_str!("data/frequency/en.txt")),
+    ("ES", include_str!("data/frequency/es.txt")),
+    ("FR", include_str!("data/frequency/fr.txt")),
+];
+
+// Words ranked below this are "common" even in very long user-supplied lists.
+const MAX_COMMON_RANK: usize = 1000;
+// Shorter lists, like the bundled ones, leave out too many everyday words for a missing word
+// to count as rare.
+const MIN_RARE_LIST_LEN: usize = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrequencyBand {
+    Common,
+    Uncommon,
+    Rare,
+    // Missing from a list too short to say.
+    Unlisted,
+}
+
+/// Word ranks for one language, most frequent first.
+pub struct FrequencyList {
+    ranks: HashMap<String, usize>,
+    common_cutoff: usize,
+}
+
+impl FrequencyList {
+    pub fn parse(contents: &str) -> Self {
+        let mut ranks = HashMap::new();
+        for word in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            let rank = ranks.len();
+            ranks.entry(word.to_lowercase()).or_insert(rank);
+        }
+        let common_cutoff = (ranks.len() / 2).min(MAX_COMMON_RANK);
+        Self {
+            ranks,
+            common_cutoff,
+        }
+    }
+
+    /// Loads `<data dir>/frequency/<code>.txt` if present, else the bundled list.
+    pub fn load(language_code: &str) -> Option<Self> {
+        let file_name = format!("{}.txt", language_code.to_ascii_lowercase());
+        if let Some(contents) = data_dir()
+            .map(|dir| dir.join("frequency").join(&file_name))
+            .and_then(|path| fs::read_to_string(path).ok())
+        {
+            return Some(Self::parse(&contents));
+        }
+        BUNDLED_LISTS
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(language_code))
+            .map(|(_, contents)| Self::parse(contents))
+    }
+
+    /// Words outside the common band, most frequent first.
+    pub fn uncommon_words(&self) -> Vec<&str> {
+        let mut words: Vec<(usize, &str)> = self
+            .ranks
+            .iter()
+            .filter(|(_, rank)| **rank >= self.common_cutoff)
+            .map(|(word, rank)| (*rank, word.as_str()))
+            .collect();
+        words.sort_unstable();
+        words.into_iter().map(|(_, word)| word).collect()
+    }
+
+    pub fn band(&self, word: &str) -> FrequencyBand {
+        match self.ranks.get(&word.to_lowercase()) {
+            Some(rank) if *rank < self.common_cutoff => FrequencyBand::Common,
+            Some(_) => FrequencyBand::Uncommon,
+            None if self.ranks.len() >= MIN_RARE_LIST_LEN => FrequencyBand::Rare,
+            None => FrequencyBand::Unlisted,
+        }
+    }
+}
+
+/// Splits a line into alternating word and non-word runs so it can be re-joined losslessly.
+pub fn word_runs(line: &str) -> Vec<(&str, bool)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut in_word = None;
+    for (index, c) in line.char_indices() {
+        let is_word = c.is_alphabetic() || c == '\'';
+        if in_word.is_some_and(|current| current != is_word) {
+            runs.push((&line[start..index], in_word.unwrap_or(false)));
+            start = index;
+        }
+        in_word = Some(is_word);
+    }
+    if start < line.len() {
+        runs.push((&line[start..], in_word.unwrap_or(false)));
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bands_follow_rank() {
+        let list = FrequencyList::parse("the\nof\nhouse\nwindow\n");
+        assert_eq!(list.band("The"), FrequencyBand::Common);
+        assert_eq!(list.band("window"), FrequencyBand::Uncommon);
+        assert_eq!(list.band("serendipity"), FrequencyBand::Unlisted);
+        let words: Vec<String> = (0..MIN_RARE_LIST_LEN)
+            .map(|rank| format!("w{}", rank))
+            .collect();
+        let list = FrequencyList::parse(&words.join("\n"));
+        assert_eq!(list.band("serendipity"), FrequencyBand::Rare);
+    }
+
+    #[test]
+    fn word_runs_round_trip() {
+        let runs = word_runs("¿Dónde está, amigo?");
+        let joined: String = runs.iter().map(|(run, _)| *run).collect();
+        assert_eq!(joined, "¿Dónde está, amigo?");
+        let words: Vec<&str> = runs.iter().filter(|(_, w)| *w).map(|(r, _)| *r).collect();
+        assert_eq!(words, ["Dónde", "está", "amigo"]);
+    }
+}
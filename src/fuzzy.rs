@@ -0,0 +1,144 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+// fzf-style weights: every matched grapheme scores, matches at word starts and runs of
+// consecutive matches score extra, and gaps inside the match cost a little.
+const SCORE_MATCH: i32 = 16;
+const BONUS_BOUNDARY: i32 = 8;
+const BONUS_CAMEL: i32 = 7;
+const BONUS_CONSECUTIVE: i32 = 4;
+// The first query grapheme's position bonus counts double, so "ge" prefers "German".
+const FIRST_BONUS_MULTIPLIER: i32 = 2;
+const GAP_START: i32 = -3;
+const GAP_EXTEND: i32 = -1;
+
+/// Scores `query` as a case-insensitive subsequence of `candidate`, comparing whole graphemes.
+///
+/// Higher scores are better. The best alignment is found by dynamic programming, like fzf's
+/// v2 algorithm, so a later match at a word start can beat an earlier scattered one.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    FuzzyCandidate::new(candidate).score(&query_graphemes(query))
+}
+
+/// Lowercased graphemes of a query, split once and scored against many candidates.
+pub fn query_graphemes(query: &str) -> Vec<String> {
+    query
+        .graphemes(true)
+        .map(|grapheme| grapheme.to_lowercase())
+        .collect()
+}
+
+/// A candidate split into lowercased graphemes with their position bonuses, so lists that are
+/// filtered on every keystroke only pay for segmentation once.
+pub struct FuzzyCandidate {
+    lowered: Vec<String>,
+    bonuses: Vec<i32>,
+}
+
+impl FuzzyCandidate {
+    pub fn new(candidate: &str) -> Self {
+        let original: Vec<&str> = candidate.graphemes(true).collect();
+        Self {
+            lowered: original
+                .iter()
+                .map(|grapheme| grapheme.to_lowercase())
+                .collect(),
+            bonuses: (0..original.len())
+                .map(|index| position_bonus(&original, index))
+                .collect(),
+        }
+    }
+
+    pub fn score(&self, query: &[String]) -> Option<i32> {
+        let Some((first, rest)) = query.split_first() else {
+            return Some(0);
+        };
+        // A greedy pass rejects most candidates cheaply and finds where alignments can start.
+        let start = self.lowered.iter().position(|grapheme| grapheme == first)?;
+        let mut remaining = rest.iter().peekable();
+        for grapheme in &self.lowered[start + 1..] {
+            if remaining.peek() == Some(&grapheme) {
+                remaining.next();
+            }
+        }
+        if remaining.peek().is_some() {
+            return None;
+        }
+        let lowered = &self.lowered[start..];
+        let bonuses = &self.bonuses[start..];
+
+        // `previous[j]` is the best score with the previous query grapheme matched at `j`.
+        let mut previous: Vec<Option<i32>> = lowered
+            .iter()
+            .zip(bonuses)
+            .map(|(grapheme, bonus)| {
+                (grapheme == first).then_some(SCORE_MATCH + bonus * FIRST_BONUS_MULTIPLIER)
+            })
+            .collect();
+        let mut current = vec![None; lowered.len()];
+        for needle in rest {
+            // Best earlier match with the gap up to the current position already charged.
+            let mut gapped: Option<i32> = None;
+            current[0] = None;
+            for index in 1..lowered.len() {
+                if index >= 2 {
+                    let opened = previous[index - 2].map(|score| score + GAP_START);
+                    gapped = gapped.map(|score| score + GAP_EXTEND).max(opened);
+                }
+                current[index] = if lowered[index] == *needle {
+                    let adjacent = previous[index - 1].map(|score| score + BONUS_CONSECUTIVE);
+                    adjacent
+                        .max(gapped)
+                        .map(|score| score + SCORE_MATCH + bonuses[index])
+                } else {
+                    None
+                };
+            }
+            std::mem::swap(&mut previous, &mut current);
+        }
+        previous.into_iter().flatten().max()
+    }
+}
+
+/// Extra score for matching at the start of the text, after a separator, or at a camelCase hump.
+fn position_bonus(graphemes: &[&str], index: usize) -> i32 {
+    let Some(previous) = index.checked_sub(1).map(|before| graphemes[before]) else {
+        return BONUS_BOUNDARY;
+    };
+    let is_separator = |grapheme: &str| {
+        grapheme
+            .chars()
+            .all(|c| c.is_whitespace() || c.is_ascii_punctuation())
+    };
+    let current = graphemes[index];
+    if is_separator(previous) && !is_separator(current) {
+        BONUS_BOUNDARY
+    } else if previous.chars().all(char::is_lowercase) && current.chars().all(char::is_uppercase) {
+        BONUS_CAMEL
+    } else {
+        0
+    }
+}
+
+/// Indices of `candidates` matching `query`, best first; ties keep the original order.
+pub fn fuzzy_filter<'a>(query: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<usize> {
+    let mut matches: Vec<(i32, usize)> = candidates
+        .enumerate()
+        .filter_map(|(index, candidate)| fuzzy_score(query, candidate).map(|score| (score, index)))
+        .collect();
+    matches.sort_by_key(|(score, index)| (-score, *index));
+    matches.into_iter().map(|(_, index)| index).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_word_starts_and_handles_non_ascii() {
+        let candidates = ["portuguese pt", "english en", "german de"];
+        assert_eq!(fuzzy_filter("ge", candidates.into_iter())[0], 2);
+        assert!(fuzzy_score("ÉT", "été brûlant").is_some());
+        assert!(fuzzy_score("日本", "にほん 日本語").is_some());
+        assert_eq!(fuzzy_score("xyz", "été"), None);
+    }
+}
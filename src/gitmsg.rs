@@ -0,0 +1,176 @@
+use std::env;
+use std::io::{self, Read, Write};
+
+use crate::config::Config;
+use crate::detect::detect_language;
+use crate::filter::{translate_line_parts, translate_lines};
+use crate::provider;
+
+// Comment leaders recognized on added diff lines, longest first.
+const COMMENT_MARKERS: &[&str] = &[
+    "///", "//!", "//", "/**", "/*", "<!--", "--", ";;", "# ", "* ",
+];
+
+/// What `ptrui git-msg` is reading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitInput {
+    // A commit message, as passed to a `commit-msg` hook or typed on stdin.
+    Message,
+    // `git log` output; only the indented message lines are translated.
+    Log,
+    // `git diff` output; only comments on added lines are translated.
+    Diff,
+}
+
+impl GitInput {
+    /// Diffs are recognized by their headers; anything else is a message unless `--log`.
+    pub fn detect(input: &str, log: bool) -> Self {
+        if log {
+            Self::Log
+        } else if input.starts_with("diff --git")
+            || input.lines().any(|line| line.starts_with("@@ "))
+        {
+            Self::Diff
+        } else {
+            Self::Message
+        }
+    }
+}
+
+/// The language commit messages are translated to: `--to`, then `PTRUI_TEAM_LANGUAGE`, then
+/// English.
+pub fn team_language(explicit: Option<&str>) -> String {
+    explicit
+        .map(str::to_string)
+        .or_else(|| env::var("PTRUI_TEAM_LANGUAGE").ok())
+        .map(|code| code.trim().to_ascii_uppercase())
+        .filter(|code| !code.is_empty())
+        .unwrap_or_else(|| "EN".to_string())
+}
+
+pub fn run_git_msg(source_lang: Option<&str>, target_lang: &str, log: bool) -> Result<(), String> {
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|err| format!("Failed to read stdin: {}", err))?;
+    let provider = provider::from_config(&Config::load()?)?;
+    let kind = GitInput::detect(&input, log);
+    let output = translate_git_text(&input, kind, |lines| {
+        let source_lang = match source_lang {
+            Some(code) => code.to_string(),
+            None => detect_language(&lines.join("\n"))
+                .map(|(code, _)| code.to_string())
+                .ok_or_else(|| "Could not detect the source language; pass --from".to_string())?,
+        };
+        if source_lang == target_lang {
+            return Ok(lines.iter().map(|line| line.to_string()).collect());
+        }
+        translate_lines(provider.as_ref(), lines, &source_lang, target_lang)
+    })?;
+    io::stdout()
+        .write_all(output.as_bytes())
+        .map_err(|err| format!("Failed to write stdout: {}", err))
+}
+
+/// Translates the prose in git text, leaving structure (headers, trailers, code) alone.
+pub fn translate_git_text(
+    input: &str,
+    kind: GitInput,
+    translate: impl FnOnce(&[&str]) -> Result<Vec<String>, String>,
+) -> Result<String, String> {
+    match kind {
+        GitInput::Message => translate_line_parts(input, split_message_line, translate),
+        GitInput::Log => translate_line_parts(
+            input,
+            |line| {
+                line.strip_prefix("    ")
+                    .and_then(split_message_line)
+                    .map(|(_, body, rest)| {
+                        (&line[..line.len() - body.len() - rest.len()], body, rest)
+                    })
+            },
+            translate,
+        ),
+        GitInput::Diff => translate_line_parts(input, split_diff_comment, translate),
+    }
+}
+
+/// Message text, skipping `#` comment lines from the commit template and trailers like
+/// `Signed-off-by:` that tools parse.
+fn split_message_line(line: &str) -> Option<(&str, &str, &str)> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('#') || is_trailer(trimmed) {
+        return None;
+    }
+    Some(split_around(line, line.len() - trimmed.len()))
+}
+
+/// The text of a full-line comment on an added diff line.
+fn split_diff_comment(line: &str) -> Option<(&str, &str, &str)> {
+    let code = line
+        .strip_prefix('+')
+        .filter(|_| !line.starts_with("+++"))?;
+    let trimmed = code.trim_start();
+    let marker = COMMENT_MARKERS
+        .iter()
+        .find(|marker| trimmed.starts_with(**marker))?;
+    let after_marker = &trimmed[marker.len()..];
+    let text_start = line.len() - after_marker.trim_start().len();
+    let (prefix, body, suffix) = split_around(line, text_start);
+    // Keep block-comment closers out of the translated text.
+    match body.strip_suffix("*/").or_else(|| body.strip_suffix("-->")) {
+        Some(inner) => {
+            let inner = inner.trim_end();
+            Some((prefix, inner, &line[prefix.len() + inner.len()..]))
+        }
+        None => Some((prefix, body, suffix)),
+    }
+}
+
+/// Splits at `start`, then trims trailing whitespace off the text.
+fn split_around(line: &str, start: usize) -> (&str, &str, &str) {
+    let (prefix, rest) = line.split_at(start);
+    let body = rest.trim_end();
+    (prefix, body, &rest[body.len()..])
+}
+
+/// `Token-Name: value` lines at the end of commit messages.
+fn is_trailer(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(key, _)| {
+        key.contains('-') && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shout(lines: &[&str]) -> Result<Vec<String>, String> {
+        Ok(lines.iter().map(|line| line.to_uppercase()).collect())
+    }
+
+    #[test]
+    fn translates_message_but_not_trailers_or_comments() {
+        let message =
+            "fix login\n\nmore detail\n# Please enter the commit message\nSigned-off-by: A <a@x>\n";
+        assert_eq!(
+            translate_git_text(message, GitInput::Message, shout).unwrap(),
+            "FIX LOGIN\n\nMORE DETAIL\n# Please enter the commit message\nSigned-off-by: A <a@x>\n"
+        );
+    }
+
+    #[test]
+    fn translates_log_messages_and_diff_comments() {
+        let log = "commit abc123\nAuthor: A <a@x>\n\n    arregla el login\n";
+        assert_eq!(
+            translate_git_text(log, GitInput::Log, shout).unwrap(),
+            "commit abc123\nAuthor: A <a@x>\n\n    ARREGLA EL LOGIN\n"
+        );
+        let diff = "diff --git a/x b/x\n+++ b/x\n@@ -1 +1,3 @@\n+    // comprueba el token\n+let x = 1; // no\n-// viejo\n+/* bloque */\n";
+        assert_eq!(GitInput::detect(diff, false), GitInput::Diff);
+        assert_eq!(
+            translate_git_text(diff, GitInput::Diff, shout).unwrap(),
+            "diff --git a/x b/x\n+++ b/x\n@@ -1 +1,3 @@\n+    // COMPRUEBA EL TOKEN\n+let x = 1; // no\n-// viejo\n+/* BLOQUE */\n"
+        );
+    }
+}
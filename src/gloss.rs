@@ -0,0 +1,70 @@
+use crate::api::TranslateOptions;
+use crate::provider::TranslationProvider;
+
+/// One source word paired with its word-level translation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlossEntry {
+    pub word: String,
+    pub gloss: String,
+}
+
+/// Splits text into display words, keeping punctuation attached for readability.
+pub fn gloss_words(text: &str) -> Vec<&str> {
+    text.split_whitespace().collect()
+}
+
+/// The part of a word worth looking up: surrounding punctuation stripped.
+pub fn lookup_key(word: &str) -> &str {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+}
+
+/// Builds an interlinear gloss by translating every distinct word in one batch.
+pub fn build_gloss(
+    provider: &dyn TranslationProvider,
+    text: &str,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<Vec<GlossEntry>, String> {
+    let words = gloss_words(text);
+    let mut keys: Vec<&str> = Vec::new();
+    for word in &words {
+        let key = lookup_key(word);
+        if !key.is_empty() && !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    let glosses = if keys.is_empty() {
+        Vec::new()
+    } else {
+        provider.translate_batch(&keys, source_lang, target_lang, &TranslateOptions::lookup())?
+    };
+
+    Ok(words
+        .into_iter()
+        .map(|word| {
+            let key = lookup_key(word);
+            let gloss = keys
+                .iter()
+                .position(|candidate| *candidate == key)
+                .and_then(|index| glosses.get(index))
+                .cloned()
+                .unwrap_or_default();
+            GlossEntry {
+                word: word.to_string(),
+                gloss,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_key_strips_punctuation() {
+        assert_eq!(lookup_key("¿Dónde?"), "Dónde");
+        assert_eq!(lookup_key("l'eau,"), "l'eau");
+        assert_eq!(lookup_key("..."), "");
+    }
+}
@@ -0,0 +1,826 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use flate2::Crc;
+use rusqlite::{Connection, OptionalExtension, ToSql, params};
+use serde::{Deserialize, Serialize};
+
+use crate::dates::{SECONDS_PER_DAY, day_number, unix_now};
+use crate::fuzzy::fuzzy_filter;
+use crate::paths::data_dir;
+use crate::sync::SyncDir;
+use crate::usage::{USAGE_DAYS, UsageSummary};
+
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+const SYNC_KIND: &str = "history";
+const REMOVED_KIND: &str = "history_removed";
+
+/// One completed translation as stored in the history database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub source_text: String,
+    pub target_text: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub provider: String,
+    // Seconds since the Unix epoch.
+    pub created_at: u64,
+    // Starred entries are listed first and never pruned automatically.
+    pub pinned: bool,
+    // Lowercase labels such as "work" or "travel".
+    pub tags: Vec<String>,
+}
+
+/// A translation as shared with other machines through the sync directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SyncedTranslation {
+    source_text: String,
+    target_text: String,
+    source_lang: String,
+    target_lang: String,
+    provider: String,
+    created_at: u64,
+}
+
+impl SyncedTranslation {
+    fn key(&self) -> EntryKey {
+        entry_key(
+            self.created_at,
+            [
+                &self.source_text,
+                &self.target_text,
+                &self.source_lang,
+                &self.target_lang,
+            ],
+        )
+    }
+}
+
+/// A translation by creation time and a checksum of its text and languages, so removals can
+/// be shared without writing the removed text back out.
+type EntryKey = (u64, u32);
+
+fn entry_key(created_at: u64, fields: [&str; 4]) -> EntryKey {
+    let mut crc = Crc::new();
+    for field in fields {
+        crc.update(field.as_bytes());
+        crc.update(&[0]);
+    }
+    (created_at, crc.sum())
+}
+
+/// A translation removed from this machine's history, shared through the sync directory so
+/// it isn't imported back from another machine's file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Tombstone {
+    created_at: u64,
+    checksum: u32,
+    // Deleted by hand, so every machine drops it; otherwise it was only pruned or purged here.
+    deleted: bool,
+}
+
+impl Tombstone {
+    fn key(&self) -> EntryKey {
+        (self.created_at, self.checksum)
+    }
+}
+
+/// SQLite-backed log of every completed translation, capped at `max_entries` rows.
+pub struct HistoryStore {
+    conn: Connection,
+    pub max_entries: usize,
+    // Entries older than this many days are pruned; `None` keeps them forever.
+    pub max_age_days: Option<u64>,
+    // Shared directory new translations are appended to and merged from.
+    sync: Option<SyncDir>,
+}
+
+impl HistoryStore {
+    pub fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("history.sqlite3"))
+    }
+
+    /// Opens the store in the data directory and applies the retention policy.
+    ///
+    /// `PTRUI_HISTORY_MAX` sets the entry cap (0 disables history) and
+    /// `PTRUI_HISTORY_MAX_AGE_DAYS` the maximum age (0 or unset keeps entries forever).
+    pub fn open_default() -> Result<Option<Self>, String> {
+        let max_entries = match env::var("PTRUI_HISTORY_MAX") {
+            Ok(value) => value
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid PTRUI_HISTORY_MAX: {}", value))?,
+            Err(_) => DEFAULT_MAX_ENTRIES,
+        };
+        if max_entries == 0 {
+            return Ok(None);
+        }
+        let path = Self::path().ok_or_else(|| "Cannot locate a data directory".to_string())?;
+        let max_age_days = match env::var("PTRUI_HISTORY_MAX_AGE_DAYS") {
+            Ok(value) => value
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid PTRUI_HISTORY_MAX_AGE_DAYS: {}", value))?,
+            Err(_) => 0,
+        };
+        let key = history_key_from_env()?;
+        let mut store = Self::open(&path, max_entries, key.as_deref())?;
+        store.max_age_days = Some(max_age_days).filter(|days| *days > 0);
+        // Sync files are plain text, so an encrypted history stays on this machine.
+        if key.is_none()
+            && let Some(sync) = SyncDir::from_env()
+        {
+            store.attach_sync(sync)?;
+        }
+        store.prune()?;
+        Ok(Some(store))
+    }
+
+    /// Opens (or creates) the database, unlocking it with `key` when encryption is used.
+    pub fn open(path: &Path, max_entries: usize, key: Option<&str>) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {:?}: {}", parent, err))?;
+        }
+        let conn = Connection::open(path)
+            .map_err(|err| format!("Failed to open history {:?}: {}", path, err))?;
+        if let Some(key) = key {
+            apply_key(&conn, key)?;
+        }
+        Self::with_connection(conn, max_entries)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory(max_entries: usize) -> Result<Self, String> {
+        let conn = Connection::open_in_memory().map_err(|err| err.to_string())?;
+        Self::with_connection(conn, max_entries)
+    }
+
+    fn with_connection(conn: Connection, max_entries: usize) -> Result<Self, String> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_text TEXT NOT NULL,
+                target_text TEXT NOT NULL,
+                source_lang TEXT NOT NULL,
+                target_lang TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS history_created_at ON history (created_at);
+            PRAGMA secure_delete = ON;",
+        )
+        .map_err(|err| format!("Failed to prepare history: {}", err))?;
+        // Databases created before pinning and tagging lack these columns.
+        if conn
+            .prepare("SELECT pinned, tags FROM history LIMIT 0")
+            .is_err()
+        {
+            conn.execute_batch(
+                "ALTER TABLE history ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE history ADD COLUMN tags TEXT NOT NULL DEFAULT '';",
+            )
+            .map_err(|err| format!("Failed to upgrade history: {}", err))?;
+        }
+        Ok(Self {
+            conn,
+            max_entries,
+            max_age_days: None,
+            sync: None,
+        })
+    }
+
+    /// Stores a translation and drops the oldest rows beyond the cap.
+    pub fn record(
+        &self,
+        source_text: &str,
+        target_text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        provider: &str,
+    ) -> Result<(), String> {
+        let translation = SyncedTranslation {
+            source_text: source_text.to_string(),
+            target_text: target_text.to_string(),
+            source_lang: source_lang.to_string(),
+            target_lang: target_lang.to_string(),
+            provider: provider.to_string(),
+            created_at: unix_now(),
+        };
+        self.insert(&translation)?;
+        if let Some(sync) = &self.sync {
+            sync.append(SYNC_KIND, &translation)?;
+        }
+        self.prune()?;
+        Ok(())
+    }
+
+    fn insert(&self, translation: &SyncedTranslation) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT INTO history
+                    (source_text, target_text, source_lang, target_lang, provider, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    translation.source_text,
+                    translation.target_text,
+                    translation.source_lang,
+                    translation.target_lang,
+                    translation.provider,
+                    translation.created_at as i64
+                ],
+            )
+            .map(|_| ())
+            .map_err(|err| format!("Failed to record history: {}", err))
+    }
+
+    /// Merges the other machines' translations and deletions, then compacts this machine's
+    /// files in the sync directory to what is still needed: its translations that weren't
+    /// removed, every deletion, and removals of entries another machine still shares.
+    fn attach_sync(&mut self, sync: SyncDir) -> Result<(), String> {
+        let translations: Vec<SyncedTranslation> = sync.load_others(SYNC_KIND)?;
+        let own_removed: Vec<Tombstone> = sync.load_own(REMOVED_KIND)?;
+        let deleted: HashSet<EntryKey> = sync
+            .load_others::<Tombstone>(REMOVED_KIND)?
+            .iter()
+            .filter(|tombstone| tombstone.deleted)
+            .map(Tombstone::key)
+            .collect();
+        let removed: HashSet<EntryKey> = own_removed
+            .iter()
+            .map(Tombstone::key)
+            .chain(deleted.iter().copied())
+            .collect();
+        let shared: HashSet<EntryKey> = translations.iter().map(SyncedTranslation::key).collect();
+        self.import(
+            translations
+                .into_iter()
+                .filter(|translation| !removed.contains(&translation.key()))
+                .collect(),
+        )?;
+        self.delete_keys(&deleted)?;
+        sync.retain_own(SYNC_KIND, |translation: &SyncedTranslation| {
+            !removed.contains(&translation.key())
+        })?;
+        sync.retain_own(REMOVED_KIND, |tombstone: &Tombstone| {
+            tombstone.deleted || shared.contains(&tombstone.key())
+        })?;
+        self.sync = Some(sync);
+        Ok(())
+    }
+
+    /// Deletes the entries another machine's user deleted.
+    fn delete_keys(&self, keys: &HashSet<EntryKey>) -> Result<(), String> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT id, source_text, target_text, source_lang, target_lang
+                 FROM history WHERE created_at = ?1",
+            )
+            .map_err(|err| format!("Failed to read history: {}", err))?;
+        for &(created_at, checksum) in keys {
+            let rows = statement
+                .query_map(params![created_at as i64], |row| {
+                    let fields: [String; 4] = [row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?];
+                    let key = entry_key(created_at, fields.each_ref().map(String::as_str));
+                    Ok((row.get::<_, i64>(0)?, key))
+                })
+                .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+                .map_err(|err| format!("Failed to read history: {}", err))?;
+            for (id, _) in rows.iter().filter(|(_, key)| key.1 == checksum) {
+                self.conn
+                    .execute("DELETE FROM history WHERE id = ?1", params![id])
+                    .map_err(|err| format!("Failed to delete history entry: {}", err))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the entries matching `condition`, leaving a tombstone for each in the sync
+    /// directory; `deleted` marks a removal by hand, which other machines apply too.
+    fn remove(
+        &self,
+        condition: &str,
+        values: &[&dyn ToSql],
+        deleted: bool,
+    ) -> Result<usize, String> {
+        let mut tombstones = Vec::new();
+        if self.sync.is_some() {
+            let mut statement = self
+                .conn
+                .prepare(&format!(
+                    "SELECT created_at, source_text, target_text, source_lang, target_lang
+                     FROM history WHERE {}",
+                    condition
+                ))
+                .map_err(|err| format!("Failed to read history: {}", err))?;
+            tombstones = statement
+                .query_map(values, |row| {
+                    let created_at = row.get::<_, i64>(0)? as u64;
+                    let fields: [String; 4] = [row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?];
+                    let (_, checksum) =
+                        entry_key(created_at, fields.each_ref().map(String::as_str));
+                    Ok(Tombstone {
+                        created_at,
+                        checksum,
+                        deleted,
+                    })
+                })
+                .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+                .map_err(|err| format!("Failed to read history: {}", err))?;
+        }
+        let removed = self
+            .conn
+            .execute(&format!("DELETE FROM history WHERE {}", condition), values)
+            .map_err(|err| format!("Failed to remove history entries: {}", err))?;
+        if let Some(sync) = &self.sync {
+            for tombstone in &tombstones {
+                sync.append(REMOVED_KIND, tombstone)?;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Merges translations from other machines, skipping ones already stored.
+    fn import(&self, translations: Vec<SyncedTranslation>) -> Result<usize, String> {
+        let mut imported = 0;
+        for translation in translations {
+            let exists = self
+                .conn
+                .query_row(
+                    "SELECT 1 FROM history WHERE created_at = ?1 AND source_text = ?2
+                        AND target_text = ?3 AND source_lang = ?4 AND target_lang = ?5",
+                    params![
+                        translation.created_at as i64,
+                        translation.source_text,
+                        translation.target_text,
+                        translation.source_lang,
+                        translation.target_lang
+                    ],
+                    |_| Ok(()),
+                )
+                .optional()
+                .map_err(|err| format!("Failed to read history: {}", err))?
+                .is_some();
+            if !exists {
+                self.insert(&translation)?;
+                imported += 1;
+            }
+        }
+        Ok(imported)
+    }
+
+    /// Drops unpinned entries beyond the cap or older than the maximum age; returns how many went.
+    pub fn prune(&self) -> Result<usize, String> {
+        let mut removed = self.remove(
+            "pinned = 0 AND id NOT IN
+                (SELECT id FROM history WHERE pinned = 0
+                 ORDER BY created_at DESC, id DESC LIMIT ?1)",
+            params![self.max_entries as i64],
+            false,
+        )?;
+        if let Some(days) = self.max_age_days {
+            let cutoff = unix_now().saturating_sub(days.saturating_mul(SECONDS_PER_DAY));
+            removed += self.delete_before(cutoff, true)?;
+        }
+        Ok(removed)
+    }
+
+    /// Deletes entries (pinned ones included) created before `timestamp`, or all when `None`.
+    pub fn purge(&self, before: Option<u64>) -> Result<usize, String> {
+        let cutoff = before.unwrap_or(u64::MAX);
+        let removed = self.delete_before(cutoff, false)?;
+        // Other machines' files are theirs to purge; this one's shared copy goes too.
+        if let Some(sync) = &self.sync {
+            sync.retain_own(SYNC_KIND, |translation: &SyncedTranslation| {
+                translation.created_at >= cutoff
+            })?;
+        }
+        // Shrink the file so purged text doesn't linger in free pages.
+        self.conn
+            .execute_batch("VACUUM")
+            .map_err(|err| format!("Failed to compact history: {}", err))?;
+        Ok(removed)
+    }
+
+    fn delete_before(&self, timestamp: u64, keep_pinned: bool) -> Result<usize, String> {
+        self.remove(
+            "created_at < ?1 AND (?2 = 0 OR pinned = 0)",
+            params![timestamp.min(i64::MAX as u64) as i64, keep_pinned],
+            false,
+        )
+    }
+
+    pub fn set_pinned(&self, id: i64, pinned: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE history SET pinned = ?2 WHERE id = ?1",
+                params![id, pinned],
+            )
+            .map(|_| ())
+            .map_err(|err| format!("Failed to pin history entry: {}", err))
+    }
+
+    pub fn set_tags(&self, id: i64, tags: &[String]) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE history SET tags = ?2 WHERE id = ?1",
+                params![id, tags.join(" ")],
+            )
+            .map(|_| ())
+            .map_err(|err| format!("Failed to tag history entry: {}", err))
+    }
+
+    /// Deletes one entry, on every synced machine.
+    pub fn delete(&self, id: i64) -> Result<(), String> {
+        self.remove("id = ?1", params![id], true).map(|_| ())
+    }
+
+    /// Aggregates translation volume for the usage dashboard.
+    pub fn usage(&self) -> Result<UsageSummary, String> {
+        let today = day_number(unix_now());
+        let first_day = today - (USAGE_DAYS as i64 - 1);
+        let mut summary = UsageSummary {
+            first_day,
+            daily_chars: vec![0; USAGE_DAYS],
+            ..UsageSummary::default()
+        };
+        for (day, chars) in self.grouped(
+            "SELECT created_at / 86400, SUM(length(source_text)) FROM history
+             WHERE created_at >= ?1 GROUP BY 1",
+            first_day * SECONDS_PER_DAY as i64,
+        )? {
+            if let Some(slot) = day
+                .parse::<i64>()
+                .ok()
+                .and_then(|day| summary.daily_chars.get_mut((day - first_day) as usize))
+            {
+                *slot = chars;
+            }
+        }
+        summary.pairs = self.grouped(
+            "SELECT source_lang || '→' || target_lang, SUM(length(source_text)) FROM history
+             WHERE created_at >= ?1 GROUP BY 1 ORDER BY 2 DESC",
+            0,
+        )?;
+        summary.providers = self.grouped(
+            "SELECT provider, COUNT(*) FROM history WHERE created_at >= ?1
+             GROUP BY 1 ORDER BY 2 DESC",
+            0,
+        )?;
+        Ok(summary)
+    }
+
+    fn grouped(&self, sql: &str, since: i64) -> Result<Vec<(String, u64)>, String> {
+        let mut statement = self.conn.prepare(sql).map_err(|err| err.to_string())?;
+        let rows = statement
+            .query_map(params![since], |row| {
+                let label: rusqlite::types::Value = row.get(0)?;
+                let label = match label {
+                    rusqlite::types::Value::Integer(value) => value.to_string(),
+                    rusqlite::types::Value::Text(text) => text,
+                    _ => String::new(),
+                };
+                Ok((label, row.get::<_, i64>(1)?.max(0) as u64))
+            })
+            .map_err(|err| err.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("Failed to read history: {}", err))
+    }
+
+    /// Most recent entries first.
+    pub fn recent(&self, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT id, source_text, target_text, source_lang, target_lang, provider, created_at,
+                    pinned, tags
+                 FROM history ORDER BY created_at DESC, id DESC LIMIT ?1",
+            )
+            .map_err(|err| err.to_string())?;
+        let rows = statement
+            .query_map(params![limit as i64], |row| {
+                Ok(HistoryEntry {
+                    id: row.get(0)?,
+                    source_text: row.get(1)?,
+                    target_text: row.get(2)?,
+                    source_lang: row.get(3)?,
+                    target_lang: row.get(4)?,
+                    provider: row.get(5)?,
+                    created_at: row.get::<_, i64>(6)? as u64,
+                    pinned: row.get(7)?,
+                    tags: parse_tags(&row.get::<_, String>(8)?),
+                })
+            })
+            .map_err(|err| err.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("Failed to read history: {}", err))
+    }
+}
+
+/// Splits user-typed tags on whitespace and commas, dropping `#` prefixes and duplicates.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(|c: char| c.is_whitespace() || c == ',') {
+        let tag = tag.trim_start_matches('#').to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Passphrase for the history database from `PTRUI_HISTORY_KEY`, or the file named by
+/// `PTRUI_HISTORY_KEY_FILE` (an age identity file's `AGE-SECRET-KEY-` line, or the whole file).
+fn history_key_from_env() -> Result<Option<String>, String> {
+    if let Ok(key) = env::var("PTRUI_HISTORY_KEY") {
+        return Ok(Some(key).filter(|key| !key.is_empty()));
+    }
+    let Ok(path) = env::var("PTRUI_HISTORY_KEY_FILE") else {
+        return Ok(None);
+    };
+    let contents = fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to read PTRUI_HISTORY_KEY_FILE {}: {}", path, err))?;
+    let key = contents
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("AGE-SECRET-KEY-"))
+        .unwrap_or(contents.trim());
+    if key.is_empty() {
+        return Err(format!("PTRUI_HISTORY_KEY_FILE {} is empty", path));
+    }
+    Ok(Some(key.to_string()))
+}
+
+#[cfg(feature = "encrypted-history")]
+fn apply_key(conn: &Connection, key: &str) -> Result<(), String> {
+    // SQLCipher logs decryption failures to stderr, which would scribble over the TUI.
+    conn.pragma_update(None, "cipher_log_level", "NONE")
+        .map_err(|err| format!("Failed to configure history encryption: {}", err))?;
+    conn.pragma_update(None, "key", key)
+        .map_err(|err| format!("Failed to unlock history: {}", err))?;
+    // SQLCipher only checks the key on first read.
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|_| "Wrong history passphrase, or the history database is not encrypted".into())
+}
+
+#[cfg(not(feature = "encrypted-history"))]
+fn apply_key(_conn: &Connection, _key: &str) -> Result<(), String> {
+    Err("History encryption needs ptrui built with --features encrypted-history".to_string())
+}
+
+/// Telescope-style popup state for searching history by content in either language.
+pub struct HistoryFinder {
+    // Free text plus `#tag` filters and `*` for pinned entries only.
+    pub query: String,
+    pub entries: Vec<HistoryEntry>,
+    // Lowercased "source target" text per entry, matched against the query.
+    haystacks: Vec<String>,
+    pub matches: Vec<usize>,
+    pub selected: usize,
+    // Tags being edited for the selected entry, space separated.
+    pub tag_edit: Option<String>,
+}
+
+impl HistoryFinder {
+    pub fn new(entries: Vec<HistoryEntry>) -> Self {
+        let haystacks = entries
+            .iter()
+            .map(|entry| format!("{} {}", entry.source_text, entry.target_text).to_lowercase())
+            .collect();
+        let mut finder = Self {
+            query: String::new(),
+            entries,
+            haystacks,
+            matches: Vec::new(),
+            selected: 0,
+            tag_edit: None,
+        };
+        finder.refilter();
+        finder
+    }
+
+    /// Recomputes matches after the query changes; without free text, pinned entries come
+    /// first and the rest follow by recency.
+    pub fn refilter(&mut self) {
+        let mut text = Vec::new();
+        let mut tags = Vec::new();
+        let mut pinned_only = false;
+        for token in self.query.split_whitespace() {
+            if token == "*" {
+                pinned_only = true;
+            } else if let Some(tag) = token.strip_prefix('#') {
+                tags.push(tag.to_lowercase());
+            } else {
+                text.push(token);
+            }
+        }
+        let allowed = |entry: &HistoryEntry| {
+            (!pinned_only || entry.pinned)
+                && tags
+                    .iter()
+                    .all(|tag| entry.tags.iter().any(|have| have.starts_with(tag.as_str())))
+        };
+        self.matches = if text.is_empty() {
+            let mut matches: Vec<usize> = (0..self.entries.len())
+                .filter(|&index| allowed(&self.entries[index]))
+                .collect();
+            matches.sort_by_key(|&index| !self.entries[index].pinned);
+            matches
+        } else {
+            fuzzy_filter(&text.join(" "), self.haystacks.iter().map(String::as_str))
+                .into_iter()
+                .filter(|&index| allowed(&self.entries[index]))
+                .collect()
+        };
+        self.selected = 0;
+    }
+
+    pub fn selected_entry_mut(&mut self) -> Option<&mut HistoryEntry> {
+        self.matches
+            .get(self.selected)
+            .and_then(|&index| self.entries.get_mut(index))
+    }
+
+    pub fn selected_entry(&self) -> Option<&HistoryEntry> {
+        self.matches
+            .get(self.selected)
+            .and_then(|&index| self.entries.get(index))
+    }
+
+    /// Drops the highlighted entry from the list, keeping the query and nearby selection.
+    pub fn remove_selected(&mut self) -> Option<HistoryEntry> {
+        let index = *self.matches.get(self.selected)?;
+        let selected = self.selected;
+        self.haystacks.remove(index);
+        let entry = self.entries.remove(index);
+        self.refilter();
+        self.selected = selected.min(self.matches.len().saturating_sub(1));
+        Some(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_caps_entries() {
+        let store = HistoryStore::open_in_memory(2).unwrap();
+        store.record("one", "uno", "EN", "ES", "test").unwrap();
+        store.record("two", "dos", "EN", "ES", "test").unwrap();
+        store.record("three", "tres", "EN", "ES", "test").unwrap();
+        let entries = store.recent(10).unwrap();
+        let sources: Vec<&str> = entries.iter().map(|e| e.source_text.as_str()).collect();
+        assert_eq!(sources, ["three", "two"]);
+    }
+
+    #[cfg(feature = "encrypted-history")]
+    #[test]
+    fn encrypted_history_needs_the_key() {
+        let path = std::env::temp_dir().join(format!("ptrui-history-{}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let store = HistoryStore::open(&path, 10, Some("correct horse")).unwrap();
+        store
+            .record("secret", "secreto", "EN", "ES", "test")
+            .unwrap();
+        drop(store);
+
+        assert!(HistoryStore::open(&path, 10, Some("wrong")).is_err());
+        assert!(HistoryStore::open(&path, 10, None).is_err());
+        let store = HistoryStore::open(&path, 10, Some("correct horse")).unwrap();
+        assert_eq!(store.recent(1).unwrap()[0].target_text, "secreto");
+        drop(store);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn purge_and_delete_remove_entries() {
+        let store = HistoryStore::open_in_memory(10).unwrap();
+        store.record("one", "uno", "EN", "ES", "test").unwrap();
+        store.record("two", "dos", "EN", "ES", "test").unwrap();
+        store
+            .conn
+            .execute(
+                "UPDATE history SET created_at = 100 WHERE source_text = 'one'",
+                [],
+            )
+            .unwrap();
+        assert_eq!(store.purge(Some(200)).unwrap(), 1);
+        let id = store.recent(1).unwrap()[0].id;
+        store.delete(id).unwrap();
+        assert!(store.recent(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn pinned_entries_survive_pruning_and_filter_by_tag() {
+        let store = HistoryStore::open_in_memory(1).unwrap();
+        store.record("keep", "guardar", "EN", "ES", "test").unwrap();
+        let id = store.recent(1).unwrap()[0].id;
+        store.set_pinned(id, true).unwrap();
+        store
+            .set_tags(id, &parse_tags("#Work, travel work"))
+            .unwrap();
+        store.record("two", "dos", "EN", "ES", "test").unwrap();
+        store.record("three", "tres", "EN", "ES", "test").unwrap();
+
+        let mut finder = HistoryFinder::new(store.recent(10).unwrap());
+        assert_eq!(finder.entries.len(), 2);
+        assert_eq!(finder.selected_entry().unwrap().tags, ["work", "travel"]);
+        finder.query = "* #trav".to_string();
+        finder.refilter();
+        assert_eq!(finder.matches.len(), 1);
+        finder.query = "#home".to_string();
+        finder.refilter();
+        assert!(finder.matches.is_empty());
+    }
+
+    #[test]
+    fn import_skips_known_translations() {
+        let store = HistoryStore::open_in_memory(10).unwrap();
+        store.record("hi", "hola", "EN", "ES", "test").unwrap();
+        let entry = store.recent(1).unwrap().remove(0);
+        let known = SyncedTranslation {
+            source_text: entry.source_text,
+            target_text: entry.target_text,
+            source_lang: entry.source_lang,
+            target_lang: entry.target_lang,
+            provider: entry.provider,
+            created_at: entry.created_at,
+        };
+        let new = SyncedTranslation {
+            source_text: "bye".to_string(),
+            created_at: 7,
+            ..known.clone()
+        };
+        assert_eq!(store.import(vec![known, new]).unwrap(), 1);
+        assert_eq!(store.recent(10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn sync_shares_deletions_and_remembers_removals() {
+        let dir = env::temp_dir().join(format!("ptrui-history-sync-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let machine = |name: &str| SyncDir {
+            dir: dir.clone(),
+            machine: name.to_string(),
+        };
+        let sources = |store: &HistoryStore| -> Vec<String> {
+            let entries = store.recent(10).unwrap();
+            entries.into_iter().map(|entry| entry.source_text).collect()
+        };
+        let mut desktop = HistoryStore::open_in_memory(10).unwrap();
+        desktop.attach_sync(machine("desktop")).unwrap();
+        desktop.record("one", "uno", "EN", "ES", "test").unwrap();
+        desktop.record("two", "dos", "EN", "ES", "test").unwrap();
+
+        let mut laptop = HistoryStore::open_in_memory(1).unwrap();
+        laptop.attach_sync(machine("laptop")).unwrap();
+        laptop.prune().unwrap();
+        assert_eq!(sources(&laptop), ["two"]);
+        // What was pruned here isn't imported again.
+        laptop.max_entries = 10;
+        laptop.attach_sync(machine("laptop")).unwrap();
+        assert_eq!(sources(&laptop), ["two"]);
+
+        // A deletion reaches the other machine and compacts its file.
+        let id = laptop.recent(1).unwrap()[0].id;
+        laptop.delete(id).unwrap();
+        desktop.attach_sync(machine("desktop")).unwrap();
+        assert_eq!(sources(&desktop), ["one"]);
+        let shared: Vec<SyncedTranslation> = machine("desktop").load_own(SYNC_KIND).unwrap();
+        assert_eq!(shared.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn usage_groups_by_day_pair_and_provider() {
+        let store = HistoryStore::open_in_memory(10).unwrap();
+        store.record("hola", "hello", "ES", "EN", "deepl").unwrap();
+        store.record("hi", "hola", "EN", "ES", "deepl").unwrap();
+        store.record("día", "day", "ES", "EN", "llm").unwrap();
+        let usage = store.usage().unwrap();
+        assert_eq!(usage.daily_chars.last(), Some(&9));
+        assert_eq!(usage.pairs[0], ("ES→EN".to_string(), 7));
+        assert_eq!(usage.providers[0], ("deepl".to_string(), 2));
+    }
+
+    #[test]
+    fn finder_matches_either_language() {
+        let store = HistoryStore::open_in_memory(10).unwrap();
+        store
+            .record("good morning", "buenos días", "EN", "ES", "test")
+            .unwrap();
+        store
+            .record("thank you", "gracias", "EN", "ES", "test")
+            .unwrap();
+        let mut finder = HistoryFinder::new(store.recent(10).unwrap());
+        assert_eq!(finder.matches.len(), 2);
+        finder.query = "días".to_string();
+        finder.refilter();
+        assert_eq!(finder.selected_entry().unwrap().source_text, "good morning");
+    }
+}
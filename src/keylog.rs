@@ -0,0 +1,111 @@
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crossterm::event::Event;
+use serde::{Deserialize, Serialize};
+
+/// One line of a `.keys` file: a terminal event and when it arrived after startup.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct RecordedEvent {
+    at_ms: u64,
+    event: Event,
+}
+
+/// Appends input events as JSON lines, so a session can be replayed as it was typed.
+pub struct KeyRecorder {
+    file: File,
+    started: Instant,
+}
+
+impl KeyRecorder {
+    pub fn create(path: &Path) -> Result<Self, String> {
+        let file =
+            File::create(path).map_err(|err| format!("Failed to create {:?}: {}", path, err))?;
+        Ok(Self {
+            file,
+            started: Instant::now(),
+        })
+    }
+
+    /// Writes straight through, so a crash still leaves the keys that led to it.
+    pub fn record(&mut self, event: &Event) -> Result<(), String> {
+        let line = RecordedEvent {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            event: event.clone(),
+        };
+        let json = serde_json::to_string(&line)
+            .map_err(|err| format!("Failed to encode a key event: {}", err))?;
+        writeln!(self.file, "{}", json).map_err(|err| format!("Failed to record keys: {}", err))
+    }
+}
+
+/// Feeds recorded events back at their original pace.
+pub struct KeyReplay {
+    events: VecDeque<RecordedEvent>,
+    started: Instant,
+}
+
+impl KeyReplay {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {:?}: {}", path, err))?;
+        Ok(Self {
+            events: parse_keys(&text)?,
+            started: Instant::now(),
+        })
+    }
+
+    /// When the next recorded event is due, or `None` once the recording has played out.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.events
+            .front()
+            .map(|next| self.started + Duration::from_millis(next.at_ms))
+    }
+
+    /// The next event, if it is due by `now`.
+    pub fn pop_due(&mut self, now: Instant) -> Option<Event> {
+        if self.next_due()? > now {
+            return None;
+        }
+        self.events.pop_front().map(|next| next.event)
+    }
+}
+
+fn parse_keys(text: &str) -> Result<VecDeque<RecordedEvent>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .map_err(|err| format!("Invalid key event on line {}: {}", index + 1, err))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn replays_recorded_events_on_time() {
+        let key = Event::Key(KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE));
+        let line = serde_json::to_string(&RecordedEvent {
+            at_ms: 40,
+            event: key.clone(),
+        })
+        .unwrap();
+        let mut replay = KeyReplay {
+            events: parse_keys(&format!("{}\n\n", line)).unwrap(),
+            started: Instant::now(),
+        };
+        let due = replay.next_due().unwrap();
+        assert_eq!(replay.pop_due(due - Duration::from_millis(1)), None);
+        assert_eq!(replay.pop_due(due), Some(key));
+        assert_eq!(replay.next_due(), None);
+        assert!(parse_keys("{\"at_ms\": 1}").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+/// Evens out platform differences in key events before the app sees them; `None` means the
+/// event should be ignored.
+pub fn normalize_key(key: KeyEvent) -> Option<KeyEvent> {
+    normalize_key_for(key, cfg!(windows))
+}
+
+fn normalize_key_for(mut key: KeyEvent, windows: bool) -> Option<KeyEvent> {
+    // Windows (and terminals speaking the kitty protocol) also report releases and bare
+    // modifier presses; acting on those would run keys twice or type nothing.
+    if key.kind == KeyEventKind::Release || matches!(key.code, KeyCode::Modifier(_)) {
+        return None;
+    }
+    // A held key repeats like a press everywhere else.
+    key.kind = KeyEventKind::Press;
+    // ConPTY reports AltGr as Ctrl+Alt, so `AltGr+q` on a German layout arrives as Ctrl+Alt+@
+    // and would be read as a shortcut. Real Ctrl+Alt chords with letters are left alone.
+    let altgr = KeyModifiers::CONTROL | KeyModifiers::ALT;
+    if windows
+        && key.modifiers.contains(altgr)
+        && matches!(key.code, KeyCode::Char(c) if !c.is_ascii_alphabetic())
+    {
+        key.modifiers -= altgr;
+    }
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEventState, ModifierKeyCode};
+
+    fn key(code: KeyCode, modifiers: KeyModifiers, kind: KeyEventKind) -> KeyEvent {
+        KeyEvent {
+            code,
+            modifiers,
+            kind,
+            state: KeyEventState::NONE,
+        }
+    }
+
+    #[test]
+    fn types_altgr_characters_on_windows() {
+        let altgr = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        let at = key(KeyCode::Char('@'), altgr, KeyEventKind::Press);
+        let typed = normalize_key_for(at, true).unwrap();
+        assert_eq!(typed.modifiers, KeyModifiers::NONE);
+        assert_eq!(normalize_key_for(at, false), Some(at));
+        let accented = key(
+            KeyCode::Char('ę'),
+            altgr | KeyModifiers::SHIFT,
+            KeyEventKind::Press,
+        );
+        assert_eq!(
+            normalize_key_for(accented, true).unwrap().modifiers,
+            KeyModifiers::SHIFT
+        );
+        let chord = key(KeyCode::Char('c'), altgr, KeyEventKind::Press);
+        assert_eq!(normalize_key_for(chord, true), Some(chord));
+    }
+
+    #[test]
+    fn drops_releases_and_bare_modifiers() {
+        let release = key(
+            KeyCode::Char('e'),
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        );
+        assert_eq!(normalize_key_for(release, true), None);
+        let shift = key(
+            KeyCode::Modifier(ModifierKeyCode::LeftShift),
+            KeyModifiers::SHIFT,
+            KeyEventKind::Press,
+        );
+        assert_eq!(normalize_key_for(shift, true), None);
+        let repeat = key(KeyCode::Char('j'), KeyModifiers::NONE, KeyEventKind::Repeat);
+        assert_eq!(
+            normalize_key_for(repeat, false).unwrap().kind,
+            KeyEventKind::Press
+        );
+    }
+}
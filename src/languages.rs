@@ -1,10 +1,26 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dates::{SECONDS_PER_DAY, unix_now};
+use crate::fuzzy::{FuzzyCandidate, query_graphemes};
+use crate::paths::data_dir;
+use crate::provider::TranslationProvider;
+
+// How long a fetched language list is trusted before asking the provider again.
+const LANGUAGE_CACHE_MAX_AGE: u64 = 7 * SECONDS_PER_DAY;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Language {
     pub name: &'static str,
     pub code: &'static str,
 }
 
-pub const LANGUAGES: &[Language] = &[
+/// Languages offered before (or without) a list from the provider.
+pub const BUILTIN_LANGUAGES: &[Language] = &[
     Language {
         name: "English",
         code: "EN",
@@ -59,45 +75,212 @@ pub const LANGUAGES: &[Language] = &[
     },
 ];
 
+/// The languages on offer and their picker candidates. Fetched languages are appended, so
+/// indices into the list stay valid when it grows.
+struct Catalog {
+    languages: Vec<Language>,
+    // "Name CODE" for each language, prepared once for the picker's per-keystroke filtering.
+    candidates: Vec<FuzzyCandidate>,
+}
+
+impl Catalog {
+    /// Leaked so `languages()` can hand out `'static` entries; a catalog is only replaced
+    /// when a fetched list adds languages, at most a couple of times per run.
+    fn leak(languages: Vec<Language>) -> &'static Self {
+        let candidates = languages
+            .iter()
+            .map(|language| FuzzyCandidate::new(&format!("{} {}", language.name, language.code)))
+            .collect();
+        Box::leak(Box::new(Self {
+            languages,
+            candidates,
+        }))
+    }
+}
+
+static CATALOG: RwLock<Option<&'static Catalog>> = RwLock::new(None);
+
+fn catalog() -> &'static Catalog {
+    if let Some(catalog) = *CATALOG.read().unwrap() {
+        return catalog;
+    }
+    let mut slot = CATALOG.write().unwrap();
+    let catalog: &'static Catalog =
+        slot.get_or_insert_with(|| Catalog::leak(BUILTIN_LANGUAGES.to_vec()));
+    catalog
+}
+
+/// The built-in languages plus any the provider reported.
+pub fn languages() -> &'static [Language] {
+    &catalog().languages
+}
+
+/// A language as a provider's languages endpoint (DeepL or LibreTranslate) describes it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchedLanguage {
+    #[serde(alias = "language")]
+    pub code: String,
+    pub name: String,
+}
+
+/// Adds fetched languages that aren't known yet, returning how many were added.
+pub fn merge_languages(fetched: &[FetchedLanguage]) -> usize {
+    let mut slot = CATALOG.write().unwrap();
+    let current = slot.map_or(BUILTIN_LANGUAGES, |catalog| catalog.languages.as_slice());
+    let merged = with_fetched(current, fetched);
+    let added = merged.len() - current.len();
+    if added > 0 {
+        *slot = Some(Catalog::leak(merged));
+    }
+    added
+}
+
+/// `current` followed by the fetched languages it lacks, with codes upper-cased.
+fn with_fetched(current: &[Language], fetched: &[FetchedLanguage]) -> Vec<Language> {
+    let mut merged = current.to_vec();
+    for language in fetched {
+        let code = language.code.trim().to_ascii_uppercase();
+        let name = language.name.trim();
+        if code.is_empty()
+            || name.is_empty()
+            || merged
+                .iter()
+                .any(|known| known.code.eq_ignore_ascii_case(&code))
+        {
+            continue;
+        }
+        merged.push(Language {
+            name: Box::leak(name.to_string().into_boxed_str()),
+            code: Box::leak(code.into_boxed_str()),
+        });
+    }
+    merged
+}
+
+/// The last list fetched from a provider, kept in `languages.json` in the data directory.
+#[derive(Debug, Serialize, Deserialize)]
+struct LanguageCache {
+    provider: String,
+    fetched_at: u64,
+    languages: Vec<FetchedLanguage>,
+}
+
+impl LanguageCache {
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("languages.json"))
+    }
+
+    fn load() -> Option<Self> {
+        let contents = fs::read_to_string(Self::path()?).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or_else(|| "Cannot locate a data directory".to_string())?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {:?}: {}", parent, err))?;
+        }
+        let contents = serde_json::to_string(self).map_err(|err| err.to_string())?;
+        fs::write(&path, contents).map_err(|err| format!("Failed to write {:?}: {}", path, err))
+    }
+}
+
+/// Merges the cached list, so the config file and saved sessions can name fetched languages.
+pub fn merge_cached_languages() {
+    if let Some(cache) = LanguageCache::load() {
+        merge_languages(&cache.languages);
+    }
+}
+
+/// Asks the provider for its languages on a worker thread when the cached list is missing,
+/// from another provider or old, merging and caching the answer. Failures keep the list
+/// as it is; the picker still offers the built-in languages.
+pub fn refresh_languages(provider: Arc<dyn TranslationProvider>) {
+    let name = provider.name();
+    let fresh = LanguageCache::load().is_some_and(|cache| {
+        cache.provider == name
+            && unix_now().saturating_sub(cache.fetched_at) < LANGUAGE_CACHE_MAX_AGE
+    });
+    if fresh {
+        return;
+    }
+    thread::spawn(move || {
+        if let Ok(languages) = provider.supported_languages() {
+            merge_languages(&languages);
+            let cache = LanguageCache {
+                provider: name,
+                fetched_at: unix_now(),
+                languages,
+            };
+            let _ = cache.save();
+        }
+    });
+}
+
 pub fn find_language_index(code: &str) -> Option<usize> {
-    LANGUAGES
+    languages()
         .iter()
         .position(|language| language.code.eq_ignore_ascii_case(code))
 }
 
+/// English name for a language code; unknown codes are passed through.
+pub fn language_name(code: &str) -> &str {
+    find_language_index(code)
+        .map(|index| languages()[index].name)
+        .unwrap_or(code)
+}
+
+/// The catalog's spelling of a case-insensitive language code.
+pub fn canonical_code(code: &str) -> Result<String, String> {
+    find_language_index(code)
+        .map(|index| languages()[index].code.to_string())
+        .ok_or_else(|| format!("Unknown language code {:?}", code))
+}
+
 pub fn filtered_language_indices(query: &str) -> Vec<usize> {
+    let catalog = catalog();
+    let languages = &catalog.languages;
     if query.trim().is_empty() {
-        return (0..LANGUAGES.len()).collect();
-    }
-    let mut matches: Vec<(usize, usize)> = Vec::new();
-    for (index, language) in LANGUAGES.iter().enumerate() {
-        let candidate = format!(
-            "{} {}",
-            language.name.to_ascii_lowercase(),
-            language.code.to_ascii_lowercase()
-        );
-        if let Some(score) = fuzzy_score(query, &candidate) {
-            matches.push((score, index));
-        }
+        return (0..languages.len()).collect();
     }
+    let query = query_graphemes(query);
+    let mut matches: Vec<(i32, usize)> = catalog
+        .candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| candidate.score(&query).map(|score| (score, index)))
+        .collect();
     matches.sort_by(|a, b| {
-        a.0.cmp(&b.0)
-            .then_with(|| LANGUAGES[a.1].name.cmp(LANGUAGES[b.1].name))
+        b.0.cmp(&a.0)
+            .then_with(|| languages[a.1].name.cmp(languages[b.1].name))
     });
     matches.into_iter().map(|(_, index)| index).collect()
 }
 
-fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
-    let mut score = 0usize;
-    let mut last_index = 0usize;
-    let query_lower = query.to_ascii_lowercase();
-    for needle in query_lower.chars() {
-        if let Some(found) = candidate[last_index..].find(needle) {
-            score += found;
-            last_index += found + 1;
-        } else {
-            return None;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_only_new_languages() {
+        let fetched = |code: &str, name: &str| FetchedLanguage {
+            code: code.to_string(),
+            name: name.to_string(),
+        };
+        let merged = with_fetched(
+            BUILTIN_LANGUAGES,
+            &[
+                fetched("de", "Deutsch"),
+                fetched("pt-br", "Portuguese (Brazilian)"),
+                fetched("", "Nameless"),
+            ],
+        );
+        assert_eq!(merged.len(), BUILTIN_LANGUAGES.len() + 1);
+        let added = merged.last().unwrap();
+        assert_eq!(
+            (added.code, added.name),
+            ("PT-BR", "Portuguese (Brazilian)")
+        );
     }
-    Some(score)
 }
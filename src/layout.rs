@@ -0,0 +1,105 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+// The smaller pane never shrinks below this share of the split.
+const MIN_SPLIT: u16 = 20;
+const MAX_SPLIT: u16 = 80;
+// How far one grow or shrink key moves the split.
+const SPLIT_STEP: u16 = 5;
+
+/// How the two translator panes share their area: side by side or stacked, and the
+/// percentage the left (or top) pane takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaneLayout {
+    pub stacked: bool,
+    pub split: u16,
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        Self {
+            stacked: false,
+            split: 50,
+        }
+    }
+}
+
+impl PaneLayout {
+    /// From the config's `layout` ("side-by-side" or "stacked") and `split` percentage.
+    pub fn new(name: Option<&str>, split: Option<u16>) -> Result<Self, String> {
+        let stacked = match name.map(str::trim) {
+            None | Some("side-by-side") => false,
+            Some("stacked") => true,
+            Some(other) => {
+                return Err(format!(
+                    "Unknown layout {:?} (use side-by-side or stacked)",
+                    other
+                ));
+            }
+        };
+        let split = split.unwrap_or(50);
+        if !(MIN_SPLIT..=MAX_SPLIT).contains(&split) {
+            return Err(format!(
+                "split must be between {} and {}",
+                MIN_SPLIT, MAX_SPLIT
+            ));
+        }
+        Ok(Self { stacked, split })
+    }
+
+    pub fn name(self) -> &'static str {
+        if self.stacked {
+            "stacked"
+        } else {
+            "side-by-side"
+        }
+    }
+
+    pub fn rotate(&mut self) {
+        self.stacked = !self.stacked;
+    }
+
+    /// Gives the left (or top) pane more room.
+    pub fn grow(&mut self) {
+        self.split = (self.split + SPLIT_STEP).min(MAX_SPLIT);
+    }
+
+    pub fn shrink(&mut self) {
+        self.split = self.split.saturating_sub(SPLIT_STEP).max(MIN_SPLIT);
+    }
+
+    /// The left (or top) and right (or bottom) pane areas.
+    pub fn panes(self, area: Rect) -> [Rect; 2] {
+        let direction = if self.stacked {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
+        let chunks = Layout::default()
+            .direction(direction)
+            .constraints([
+                Constraint::Percentage(self.split),
+                Constraint::Percentage(100 - self.split),
+            ])
+            .split(area);
+        [chunks[0], chunks[1]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_within_bounds() {
+        let mut layout = PaneLayout::new(Some("stacked"), Some(75)).unwrap();
+        layout.grow();
+        layout.grow();
+        assert_eq!(layout.split, MAX_SPLIT);
+        let [top, bottom] = layout.panes(Rect::new(0, 0, 40, 20));
+        assert_eq!((top.height, bottom.height), (16, 4));
+        layout.rotate();
+        assert_eq!(layout.name(), "side-by-side");
+        assert!(PaneLayout::new(Some("diagonal"), None).is_err());
+        assert!(PaneLayout::new(None, Some(95)).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! The translator's state, providers, vim emulation and drawing, shared by the `ptrui`
+//! binary, the tests and the benchmarks.
+
+pub mod alternatives;
+pub mod api;
+pub mod app;
+pub mod audit;
+pub mod browser;
+pub mod bundle;
+pub mod cache;
+pub mod chunk;
+pub mod cli;
+pub mod clipboard;
+pub mod command;
+pub mod config;
+pub mod dates;
+pub mod debounce;
+pub mod detect;
+pub mod dictionary;
+pub mod diff;
+pub mod draft;
+pub mod editor;
+pub mod export;
+pub mod filter;
+pub mod formality;
+pub mod frequency;
+pub mod fuzzy;
+pub mod gitmsg;
+pub mod gloss;
+pub mod history;
+pub mod keylog;
+pub mod keys;
+pub mod languages;
+pub mod layout;
+pub mod lock;
+pub mod mock;
+pub mod notify;
+pub mod ocr;
+pub mod oneshot;
+pub mod paths;
+pub mod phrasebook;
+pub mod pipe;
+pub mod presentation;
+pub mod profanity;
+pub mod provider;
+#[cfg(all(test, feature = "test-provider"))]
+mod provider_tests;
+pub mod qr;
+pub mod quality;
+pub mod quickkeys;
+pub mod quota;
+pub mod review;
+pub mod romanize;
+pub mod segment;
+pub mod server;
+pub mod session;
+pub mod speech;
+pub mod stats;
+pub mod sync;
+pub mod text_object;
+pub mod textarea;
+pub mod theme;
+pub mod thesaurus;
+pub mod tmux;
+pub mod tone;
+pub mod transcribe;
+pub mod translator;
+pub mod ui;
+#[cfg(test)]
+mod ui_snapshots;
+pub mod update;
+pub mod usage;
+pub mod vim;
+pub mod webhook;
+pub mod webpage;
+pub mod wordofday;
+
+pub use app::App;
+pub use provider::TranslationProvider;
+pub use ui::{draw_ui, style_panes};
+pub use vim::Vim;
@@ -0,0 +1,148 @@
+use std::env;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// Hides the panes after a stretch without input, so translations aren't left on a shared
+/// screen. Unlocking takes any key, or the passphrase when one is set.
+pub struct PrivacyLock {
+    idle_after: Duration,
+    passphrase: Option<String>,
+    last_input: Instant,
+    // Passphrase typed so far while locked; `None` while unlocked.
+    typed: Option<String>,
+    // Whether the last passphrase attempt was wrong.
+    pub failed: bool,
+}
+
+impl PrivacyLock {
+    pub fn new(idle_after: Duration, passphrase: Option<String>, now: Instant) -> Self {
+        Self {
+            idle_after,
+            passphrase,
+            last_input: now,
+            typed: None,
+            failed: false,
+        }
+    }
+
+    /// `PTRUI_LOCK_AFTER` (seconds idle) turns the lock on; `PTRUI_LOCK_PASSPHRASE` requires a
+    /// passphrase to unlock.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let Ok(value) = env::var("PTRUI_LOCK_AFTER") else {
+            return Ok(None);
+        };
+        let seconds = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid PTRUI_LOCK_AFTER {:?}, expected seconds", value))?;
+        if seconds == 0 {
+            return Ok(None);
+        }
+        let passphrase = env::var("PTRUI_LOCK_PASSPHRASE")
+            .ok()
+            .filter(|passphrase| !passphrase.is_empty());
+        Ok(Some(Self::new(
+            Duration::from_secs(seconds),
+            passphrase,
+            Instant::now(),
+        )))
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.typed.is_some()
+    }
+
+    pub fn needs_passphrase(&self) -> bool {
+        self.passphrase.is_some()
+    }
+
+    /// Number of passphrase characters typed, for masking on the lock screen.
+    pub fn typed_len(&self) -> usize {
+        self.typed
+            .as_deref()
+            .map_or(0, |typed| typed.chars().count())
+    }
+
+    /// Restarts the idle timer.
+    pub fn touch(&mut self, now: Instant) {
+        self.last_input = now;
+    }
+
+    /// When the lock will engage, while unlocked.
+    pub fn next_lock(&self) -> Option<Instant> {
+        (!self.is_locked()).then(|| self.last_input + self.idle_after)
+    }
+
+    /// Locks once idle long enough; returns whether it just locked.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        if self.next_lock().is_some_and(|due| due <= now) {
+            self.typed = Some(String::new());
+            self.failed = false;
+            return true;
+        }
+        false
+    }
+
+    /// Handles a key on the lock screen; returns whether it unlocked.
+    pub fn handle_key(&mut self, key: KeyEvent, now: Instant) -> bool {
+        let Some(typed) = self.typed.as_mut() else {
+            return true;
+        };
+        let unlocked = match (&self.passphrase, key.code) {
+            (None, _) => true,
+            (Some(passphrase), KeyCode::Enter) => {
+                let matches = typed == passphrase;
+                self.failed = !matches;
+                typed.clear();
+                matches
+            }
+            (Some(_), KeyCode::Backspace) => {
+                typed.pop();
+                false
+            }
+            (Some(_), KeyCode::Esc) => {
+                typed.clear();
+                false
+            }
+            (Some(_), KeyCode::Char(c)) => {
+                typed.push(c);
+                false
+            }
+            _ => false,
+        };
+        if unlocked {
+            self.typed = None;
+            self.failed = false;
+            self.last_input = now;
+        }
+        unlocked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn locks_when_idle_and_unlocks_with_the_passphrase() {
+        let start = Instant::now();
+        let mut lock = PrivacyLock::new(Duration::from_secs(60), Some("ok".to_string()), start);
+        assert!(!lock.tick(start + Duration::from_secs(59)));
+        assert!(lock.tick(start + Duration::from_secs(60)));
+        assert!(lock.is_locked());
+        let later = start + Duration::from_secs(61);
+        lock.handle_key(press(KeyCode::Char('n')), later);
+        assert!(!lock.handle_key(press(KeyCode::Enter), later));
+        assert!(lock.failed);
+        lock.handle_key(press(KeyCode::Char('o')), later);
+        lock.handle_key(press(KeyCode::Char('k')), later);
+        assert!(lock.handle_key(press(KeyCode::Enter), later));
+        assert_eq!(lock.next_lock(), Some(later + Duration::from_secs(60)));
+    }
+}
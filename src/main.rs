@@ -1,5 +1,7 @@
-use std::io::{self};
+use std::env;
+use std::io::{self, IsTerminal};
 
+use crossterm::event::{DisableBracketedPaste, EnableBracketedPaste};
 use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
@@ -7,27 +9,42 @@ use crossterm::terminal::{
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 
-mod api;
-mod app;
-mod languages;
-mod textarea;
-mod ui;
-mod vim;
+use ptrui::{app, cli, languages};
 
 fn main() -> io::Result<()> {
+    // Before parsing anything, so flags and the config file can name languages that only
+    // the provider offers.
+    languages::merge_cached_languages();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = cli::parse_args(&args).map_err(io::Error::other)?;
+    let cli::Command::Tui(options) = command else {
+        return cli::run(command).map_err(io::Error::other);
+    };
+    // `echo hello | ptrui` is a script expecting output, not a terminal session.
+    if !io::stdin().is_terminal() {
+        return Err(io::Error::other(
+            "Piped text needs a target language, e.g. echo hello | ptrui --to ES",
+        ));
+    }
+
     // Raw mode lets us read keys directly without line buffering.
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    // Switch to an alternate screen so we can draw a TUI.
-    execute!(stdout, EnterAlternateScreen)?;
+    // Switch to an alternate screen so we can draw a TUI, and receive pastes as one event
+    // instead of a burst of keystrokes.
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = app::run_app(&mut terminal);
+    let result = app::run_app(&mut terminal, options);
 
     // Always restore the terminal to a clean state.
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
     terminal.show_cursor()?;
 
     result
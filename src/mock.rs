@@ -0,0 +1,54 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::api::TranslateOptions;
+use crate::provider::TranslationProvider;
+
+/// `TRANSLATION_API_URL` value (and what `--offline` selects) for the mock provider.
+pub const MOCK_URL: &str = "mock";
+// `TranslationProvider::name` of the mock provider.
+pub const MOCK_NAME: &str = "mock";
+
+/// A provider that needs no network or API key: it "translates" by reversing each line, so
+/// results are deterministic and obviously not real.
+pub struct MockProvider;
+
+impl TranslationProvider for MockProvider {
+    fn translate_batch(
+        &self,
+        texts: &[&str],
+        _source_lang: &str,
+        _target_lang: &str,
+        _options: &TranslateOptions,
+    ) -> Result<Vec<String>, String> {
+        Ok(texts.iter().map(|text| reverse_lines(text)).collect())
+    }
+
+    fn name(&self) -> String {
+        MOCK_NAME.to_string()
+    }
+}
+
+fn reverse_lines(text: &str) -> String {
+    text.split('\n')
+        .map(|line| line.graphemes(true).rev().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverses_each_line() {
+        let translated = MockProvider
+            .translate_batch(
+                &["hola\nmañana", ""],
+                "ES",
+                "EN",
+                &TranslateOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(translated, ["aloh\nanañam", ""]);
+    }
+}
@@ -0,0 +1,57 @@
+use std::env;
+use std::time::Duration;
+
+use notify_rust::Notification;
+
+/// Desktop notification for jobs that ran long enough for the user to switch away.
+pub struct Notifier {
+    pub min_duration: Duration,
+}
+
+impl Notifier {
+    /// `PTRUI_NOTIFY_AFTER` opts in: notify when a job took at least this many seconds.
+    pub fn from_env() -> Option<Self> {
+        let seconds: u64 = env::var("PTRUI_NOTIFY_AFTER").ok()?.trim().parse().ok()?;
+        Some(Self {
+            min_duration: Duration::from_secs(seconds),
+        })
+    }
+
+    /// Fires a notification if the job was slow. Best effort: a missing notification
+    /// daemon must not turn a finished job into an error.
+    pub fn job_finished(&self, job: &str, elapsed: Duration, error: Option<&str>) {
+        if elapsed < self.min_duration {
+            return;
+        }
+        let body = match error {
+            Some(message) => format!(
+                "{} failed after {}: {}",
+                job,
+                format_elapsed(elapsed),
+                message
+            ),
+            None => format!("{} finished in {}", job, format_elapsed(elapsed)),
+        };
+        let _ = Notification::new().summary("ptrui").body(&body).show();
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_elapsed_time() {
+        assert_eq!(format_elapsed(Duration::from_secs(42)), "42s");
+        assert_eq!(format_elapsed(Duration::from_secs(185)), "3m 05s");
+    }
+}
@@ -0,0 +1,91 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+// Tesseract names its trained data by ISO 639-2 codes.
+const TESSERACT_LANGUAGES: &[(&str, &str)] = &[
+    ("EN", "eng"),
+    ("ES", "spa"),
+    ("FR", "fra"),
+    ("DE", "deu"),
+    ("IT", "ita"),
+    ("PT", "por"),
+    ("NL", "nld"),
+    ("PL", "pol"),
+    ("RU", "rus"),
+    ("JA", "jpn"),
+    ("ZH", "chi_sim"),
+    ("KO", "kor"),
+    ("SV", "swe"),
+];
+
+/// How to turn an image into text: an external command that prints the text on stdout.
+pub struct OcrSettings {
+    // Command line with `{image}` and optional `{lang}` placeholders.
+    pub command: String,
+}
+
+impl OcrSettings {
+    pub fn from_env() -> Self {
+        let command = env::var("PTRUI_OCR_COMMAND")
+            .unwrap_or_else(|_| "tesseract {image} - -l {lang}".to_string());
+        Self { command }
+    }
+
+    /// Builds the program and arguments; `{lang}` becomes the tesseract code for the language.
+    pub fn command_line(&self, image: &Path, language_code: &str) -> Vec<String> {
+        let language = TESSERACT_LANGUAGES
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(language_code))
+            .map_or("eng", |(_, tesseract)| tesseract);
+        let image = image.to_string_lossy();
+        self.command
+            .split_whitespace()
+            .map(|token| token.replace("{image}", &image).replace("{lang}", language))
+            .collect()
+    }
+}
+
+/// Runs the OCR command on an image and returns the recognized text.
+pub fn recognize_text(
+    settings: &OcrSettings,
+    image: &Path,
+    language_code: &str,
+) -> Result<String, String> {
+    if !image.is_file() {
+        return Err(format!("No image at {}", image.display()));
+    }
+    let args = settings.command_line(image, language_code);
+    let Some((program, rest)) = args.split_first() else {
+        return Err("PTRUI_OCR_COMMAND is empty".to_string());
+    };
+    let output = Command::new(program)
+        .args(rest)
+        .output()
+        .map_err(|err| format!("Failed to start {}: {}", program, err))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", program, stderr.trim()));
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if text.is_empty() {
+        return Err(format!("No text recognized in {}", image.display()));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_image_and_language_placeholders() {
+        let settings = OcrSettings {
+            command: "tesseract {image} - -l {lang}".to_string(),
+        };
+        assert_eq!(
+            settings.command_line(Path::new("shot.png"), "ja"),
+            ["tesseract", "shot.png", "-", "-l", "jpn"]
+        );
+    }
+}
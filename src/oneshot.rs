@@ -0,0 +1,139 @@
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::api::TranslateOptions;
+use crate::chunk::translate_chunked;
+use crate::config::Config;
+use crate::detect::detect_language;
+use crate::provider::{self, TranslationProvider};
+
+/// The result of a one-off translation, also printed as `--json`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Translated {
+    pub text: String,
+    pub from: String,
+    pub to: String,
+    // How sure the detection was, when the source language wasn't given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    pub provider: String,
+}
+
+/// Translates a file (`ptrui --file notes.txt --to DE`) or piped stdin (`echo hi | ptrui --to
+/// ES`) without the TUI, writing the result to `output` or stdout. Long texts go out in
+/// chunks like in the panes.
+pub fn run_translate(
+    input: Option<&Path>,
+    source_lang: Option<&str>,
+    target_lang: &str,
+    output: Option<&Path>,
+    json: bool,
+) -> Result<(), String> {
+    let text = match input {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?,
+        None if io::stdin().is_terminal() => {
+            return Err("Nothing to translate: pipe text into ptrui or pass --file".to_string());
+        }
+        None => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|err| format!("Failed to read stdin: {}", err))?;
+            text
+        }
+    };
+    let config = Config::load()?;
+    let provider = provider::from_config(&config)?;
+    let translated = translate_document(
+        provider.as_ref(),
+        &text,
+        source_lang,
+        target_lang,
+        config.chunk_chars(),
+    )?;
+    let mut out = if json {
+        serde_json::to_string(&translated).map_err(|err| err.to_string())? + "\n"
+    } else {
+        translated.text
+    };
+    // Piped text usually ends in a newline the shell expects back; the API may have dropped it.
+    if input.is_none() && !json && text.ends_with('\n') && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    match output {
+        Some(path) => fs::write(path, out)
+            .map_err(|err| format!("Failed to write {}: {}", path.display(), err)),
+        None => {
+            let mut stdout = io::stdout().lock();
+            stdout
+                .write_all(out.as_bytes())
+                .and_then(|_| stdout.flush())
+                .map_err(|err| format!("Failed to write stdout: {}", err))
+        }
+    }
+}
+
+/// Translates `text`, detecting the source language when none is given. Leading and trailing
+/// whitespace, which APIs tend to drop, is kept as it was.
+pub fn translate_document(
+    provider: &dyn TranslationProvider,
+    text: &str,
+    source_lang: Option<&str>,
+    target_lang: &str,
+    chunk_chars: usize,
+) -> Result<Translated, String> {
+    let body = text.trim();
+    let (source_lang, confidence) = match source_lang {
+        Some(code) => (code, None),
+        None if body.is_empty() => ("", None),
+        None => detect_language(body)
+            .map(|(code, confidence)| (code, Some(confidence)))
+            .ok_or_else(|| "Could not detect the source language; pass --from".to_string())?,
+    };
+    let translated = if body.is_empty() {
+        String::new()
+    } else {
+        let options = TranslateOptions::default();
+        translate_chunked(
+            body,
+            chunk_chars,
+            |chunk| provider.translate(chunk, source_lang, target_lang, &options),
+            |_, _| Ok(()),
+        )?
+    };
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[leading.len() + body.len()..];
+    Ok(Translated {
+        text: format!("{}{}{}", leading, translated, trailing),
+        from: source_lang.to_string(),
+        to: target_lang.to_string(),
+        confidence,
+        provider: provider.name(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockProvider;
+
+    #[test]
+    fn keeps_surrounding_whitespace() {
+        let translated =
+            translate_document(&MockProvider, "\nabc\n\ndef\n", Some("EN"), "DE", 4).unwrap();
+        assert_eq!(translated.text, "\ncba\n\nfed\n");
+        let detected =
+            translate_document(&MockProvider, "Der Hund ist nicht hier", None, "EN", 100).unwrap();
+        assert_eq!(detected.from, "DE");
+        assert!(detected.confidence.is_some());
+        assert!(
+            serde_json::to_string(&detected)
+                .unwrap()
+                .ends_with(r#""provider":"mock"}"#)
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Per-user data directory (`$XDG_DATA_HOME/ptrui`, falling back to `~/.local/share/ptrui`).
+pub fn data_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".local").join("share")))?;
+    Some(base.join("ptrui"))
+}
+
+/// Per-user config directory (`$XDG_CONFIG_HOME/ptrui`, falling back to `~/.config/ptrui`).
+pub fn config_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".config")))?;
+    Some(base.join("ptrui"))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dates::unix_now;
+use crate::paths::data_dir;
+use crate::sync::{SyncDir, append_jsonl, read_jsonl};
+
+const SYNC_KIND: &str = "phrasebook";
+
+/// A saved source/translation pair, the unit exported to flashcard decks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhraseEntry {
+    pub source: String,
+    pub target: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pronunciation: Option<String>,
+    // Seconds since the Unix epoch.
+    pub added_at: u64,
+}
+
+impl PhraseEntry {
+    pub fn new(
+        source: &str,
+        target: &str,
+        source_lang: &str,
+        target_lang: &str,
+        pronunciation: Option<String>,
+    ) -> Self {
+        Self {
+            source: source.to_string(),
+            target: target.to_string(),
+            source_lang: source_lang.to_string(),
+            target_lang: target_lang.to_string(),
+            pronunciation,
+            added_at: unix_now(),
+        }
+    }
+}
+
+pub fn phrasebook_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("phrasebook.jsonl"))
+}
+
+/// Appends one entry as a JSON line, also sharing it through the sync directory if set.
+pub fn append_phrase(entry: &PhraseEntry) -> Result<(), String> {
+    let path = phrasebook_path().ok_or_else(|| "Cannot locate a data directory".to_string())?;
+    append_jsonl(&path, entry)?;
+    if let Some(sync) = SyncDir::from_env() {
+        sync.append(SYNC_KIND, entry)?;
+    }
+    Ok(())
+}
+
+/// Reads every saved entry, merged with other machines' phrasebooks in the sync directory.
+pub fn load_phrases() -> Result<Vec<PhraseEntry>, String> {
+    let mut entries = match phrasebook_path().filter(|path| path.exists()) {
+        Some(path) => read_jsonl(&path)?,
+        None => Vec::new(),
+    };
+    if let Some(sync) = SyncDir::from_env() {
+        entries.extend(sync.load_all::<PhraseEntry>(SYNC_KIND)?);
+    }
+    Ok(merge_phrases(entries))
+}
+
+/// Keeps the first-added copy of each pair, oldest first.
+fn merge_phrases(mut entries: Vec<PhraseEntry>) -> Vec<PhraseEntry> {
+    entries.sort_by_key(|entry| entry.added_at);
+    let mut seen = HashSet::new();
+    entries.retain(|entry| {
+        seen.insert((
+            entry.source.clone(),
+            entry.target.clone(),
+            entry.source_lang.clone(),
+            entry.target_lang.clone(),
+        ))
+    });
+    entries
+}
+
+/// Renders entries as an Anki-importable TSV with Source, Target, Pronunciation and Tags columns.
+pub fn anki_tsv(entries: &[PhraseEntry]) -> String {
+    let mut out =
+        String::from("#separator:tab\n#html:true\n#columns:Source\tTarget\tPronunciation\tTags\n");
+    for entry in entries {
+        let tags = format!("ptrui {}-{}", entry.source_lang, entry.target_lang);
+        let fields = [
+            anki_field(&entry.source),
+            anki_field(&entry.target),
+            anki_field(entry.pronunciation.as_deref().unwrap_or_default()),
+            tags,
+        ];
+        out.push_str(&fields.join("\t"));
+        out.push('\n');
+    }
+    out
+}
+
+/// Escapes a field for Anki's HTML-enabled TSV import.
+fn anki_field(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\t', " ")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anki_tsv_escapes_fields() {
+        let mut entry = PhraseEntry::new("a\tb\nc", "<x>", "EN", "JA", Some("ekkusu".into()));
+        entry.added_at = 0;
+        let tsv = anki_tsv(&[entry]);
+        assert!(tsv.ends_with("a b<br>c\t&lt;x&gt;\tekkusu\tptrui EN-JA\n"));
+    }
+
+    #[test]
+    fn merge_drops_synced_duplicates() {
+        let mut first = PhraseEntry::new("hi", "hola", "EN", "ES", None);
+        first.added_at = 1;
+        let mut copy = first.clone();
+        copy.added_at = 5;
+        let mut other = PhraseEntry::new("bye", "adiós", "EN", "ES", None);
+        other.added_at = 3;
+        let merged = merge_phrases(vec![copy, other.clone(), first.clone()]);
+        assert_eq!(merged, [first, other]);
+    }
+}
@@ -0,0 +1,123 @@
+use std::io::{self, BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::api::TranslateOptions;
+use crate::cache::{TranslationCache, translate_cached};
+use crate::config::Config;
+use crate::detect::detect_language;
+use crate::languages::canonical_code;
+use crate::provider;
+
+/// One line of `ptrui pipe` input.
+#[derive(Debug, Deserialize)]
+struct PipeRequest {
+    text: String,
+    to: String,
+    // Detected from the text when omitted.
+    from: Option<String>,
+    context: Option<String>,
+    // Echoed back untouched so callers can match replies to requests.
+    id: Option<Value>,
+}
+
+/// One line of `ptrui pipe` output: either a translation or an error.
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct PipeReply {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Translates JSON lines from stdin to JSON lines on stdout with one warm HTTP client and
+/// cache, answering every input line in order. Bad lines get an `error` reply, not an exit.
+pub fn run_pipe() -> Result<(), String> {
+    let provider = provider::from_config(&Config::load()?)?;
+    let mut cache = TranslationCache::from_env()?;
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|err| format!("Failed to read stdin: {}", err))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<PipeRequest>(&line) {
+            Ok(request) => {
+                let id = request.id.clone();
+                translate_request(request, |text, from, to, options| {
+                    translate_cached(&mut cache, provider.as_ref(), text, from, to, options)
+                })
+                .unwrap_or_else(|error| PipeReply {
+                    id,
+                    error: Some(error),
+                    ..PipeReply::default()
+                })
+            }
+            Err(err) => PipeReply {
+                error: Some(format!("Invalid request: {}", err)),
+                ..PipeReply::default()
+            },
+        };
+        let reply = serde_json::to_string(&reply).map_err(|err| err.to_string())?;
+        writeln!(stdout, "{}", reply)
+            .and_then(|_| stdout.flush())
+            .map_err(|err| format!("Failed to write stdout: {}", err))?;
+    }
+    // Best effort: the totals only feed `ptrui usage`.
+    let _ = cache.stats.accumulate();
+    Ok(())
+}
+
+fn translate_request(
+    request: PipeRequest,
+    translate: impl FnOnce(&str, &str, &str, &TranslateOptions) -> Result<(String, String), String>,
+) -> Result<PipeReply, String> {
+    let to = canonical_code(&request.to)?;
+    let from = match &request.from {
+        Some(code) => canonical_code(code)?,
+        None => detect_language(&request.text)
+            .map(|(code, _)| code.to_string())
+            .ok_or_else(|| "Could not detect the source language; pass \"from\"".to_string())?,
+    };
+    let options = TranslateOptions {
+        context: request.context.as_deref(),
+        ..TranslateOptions::default()
+    };
+    let (text, provider) = translate(&request.text, &from, &to, &options)?;
+    Ok(PipeReply {
+        id: request.id,
+        text: Some(text),
+        from: Some(from),
+        to: Some(to),
+        provider: Some(provider),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_codes_and_echoes_ids() {
+        let request: PipeRequest =
+            serde_json::from_str(r#"{"text":"Der Hund ist nicht hier","to":"en","id":7}"#).unwrap();
+        let reply = translate_request(request, |text, from, to, _| {
+            assert_eq!((text, from, to), ("Der Hund ist nicht hier", "DE", "EN"));
+            Ok(("The dog is not here".to_string(), "cache".to_string()))
+        });
+        assert_eq!(
+            serde_json::to_string(&reply.unwrap()).unwrap(),
+            r#"{"id":7,"text":"The dog is not here","from":"DE","to":"EN","provider":"cache"}"#
+        );
+    }
+}
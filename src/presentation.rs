@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+use crate::history::HistoryEntry;
+
+/// Read-only full-screen view of a pair, optionally stepping through history entries.
+pub struct Presentation {
+    // Entries to step through, in presentation order; empty shows the live panes.
+    pub entries: Vec<HistoryEntry>,
+    pub index: usize,
+    // Auto-advance interval; `None` advances only on keypresses.
+    pub advance_every: Option<Duration>,
+    last_advance: Instant,
+    pub paused: bool,
+}
+
+impl Presentation {
+    /// Pinned entries when there are any (a prepared lesson), otherwise recent history;
+    /// either way oldest first, like slides.
+    pub fn new(mut entries: Vec<HistoryEntry>, advance_every: Option<Duration>) -> Self {
+        if entries.iter().any(|entry| entry.pinned) {
+            entries.retain(|entry| entry.pinned);
+        }
+        entries.sort_by_key(|entry| (entry.created_at, entry.id));
+        Self {
+            entries,
+            index: 0,
+            advance_every,
+            last_advance: Instant::now(),
+            paused: false,
+        }
+    }
+
+    pub fn current(&self) -> Option<&HistoryEntry> {
+        self.entries.get(self.index)
+    }
+
+    pub fn next(&mut self) {
+        if !self.entries.is_empty() {
+            self.index = (self.index + 1) % self.entries.len();
+        }
+        self.last_advance = Instant::now();
+    }
+
+    pub fn previous(&mut self) {
+        if !self.entries.is_empty() {
+            self.index = (self.index + self.entries.len() - 1) % self.entries.len();
+        }
+        self.last_advance = Instant::now();
+    }
+
+    /// When the next automatic advance is due, if one is.
+    pub fn next_advance(&self) -> Option<Instant> {
+        self.advance_every
+            .filter(|_| !self.paused)
+            .map(|interval| self.last_advance + interval)
+    }
+
+    /// Advances when the interval has passed; returns whether the slide changed.
+    pub fn tick(&mut self) -> bool {
+        match self.advance_every {
+            Some(interval) if !self.paused && self.last_advance.elapsed() >= interval => {
+                self.next();
+                self.entries.len() > 1
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: i64, pinned: bool) -> HistoryEntry {
+        HistoryEntry {
+            id,
+            source_text: format!("source {}", id),
+            target_text: format!("target {}", id),
+            source_lang: "EN".to_string(),
+            target_lang: "ES".to_string(),
+            provider: "test".to_string(),
+            created_at: id as u64,
+            pinned,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn presents_pinned_entries_oldest_first_and_wraps() {
+        let mut presentation = Presentation::new(
+            vec![entry(3, true), entry(2, false), entry(1, true)],
+            Some(Duration::ZERO),
+        );
+        assert_eq!(presentation.current().map(|entry| entry.id), Some(1));
+        assert!(presentation.tick());
+        assert_eq!(presentation.current().map(|entry| entry.id), Some(3));
+        presentation.next();
+        assert_eq!(presentation.current().map(|entry| entry.id), Some(1));
+        presentation.previous();
+        assert_eq!(presentation.current().map(|entry| entry.id), Some(3));
+    }
+}
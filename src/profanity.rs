@@ -0,0 +1,67 @@
+use std::collections::HashSet;
+use std::fs;
+
+use crate::frequency::word_runs;
+use crate::paths::data_dir;
+
+const BUNDLED_WORDS: &str = include_str!("data/profanity.txt");
+
+/// Local wordlist used to mask profanity in translations.
+pub struct ProfanityFilter {
+    words: HashSet<String>,
+}
+
+impl ProfanityFilter {
+    pub fn parse(contents: &str) -> Self {
+        let words = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_lowercase)
+            .collect();
+        Self { words }
+    }
+
+    /// The bundled list plus any words from `<data dir>/profanity.txt`.
+    pub fn load() -> Self {
+        let mut filter = Self::parse(BUNDLED_WORDS);
+        if let Some(extra) = data_dir()
+            .map(|dir| dir.join("profanity.txt"))
+            .and_then(|path| fs::read_to_string(path).ok())
+        {
+            filter.words.extend(Self::parse(&extra).words);
+        }
+        filter
+    }
+
+    /// Keeps the first letter of each listed word and masks the rest, e.g. `s***`.
+    pub fn mask(&self, text: &str) -> String {
+        text.split_inclusive('\n')
+            .flat_map(word_runs)
+            .map(|(run, is_word)| {
+                if is_word && self.words.contains(&run.to_lowercase()) {
+                    run.chars()
+                        .enumerate()
+                        .map(|(index, c)| if index == 0 { c } else { '*' })
+                        .collect()
+                } else {
+                    run.to_string()
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_listed_words_only() {
+        let filter = ProfanityFilter::parse("damn\nmierda\n");
+        assert_eq!(
+            filter.mask("Damn it,\n¡qué mierda! Damnation."),
+            "D*** it,\n¡qué m*****! Damnation."
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::api::{LlmApi, PtruiApi, TranslateOptions};
+use crate::config::Config;
+use crate::languages::FetchedLanguage;
+use crate::mock::{MOCK_NAME, MOCK_URL, MockProvider};
+use crate::quota::ApiQuota;
+
+/// A translation backend. The HTTP API (`PtruiApi`) is the usual one; anything else that
+/// implements this can drive the panes, the cache and the batch commands.
+pub trait TranslationProvider: Send + Sync {
+    /// Translates several independent texts, returning them in the same order.
+    fn translate_batch(
+        &self,
+        texts: &[&str],
+        source_lang: &str,
+        target_lang: &str,
+        options: &TranslateOptions,
+    ) -> Result<Vec<String>, String>;
+
+    /// Recorded with each translation in history and shown on the usage dashboard.
+    fn name(&self) -> String;
+
+    /// Languages the backend supports, for the picker. Providers that can't say return none
+    /// and the built-in list is used.
+    fn supported_languages(&self) -> Result<Vec<FetchedLanguage>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Characters used and allowed this billing period, for backends that report it.
+    fn quota(&self) -> Result<Option<ApiQuota>, String> {
+        Ok(None)
+    }
+
+    /// The HTTP client of backends that have one, so other lookups can share its pool.
+    fn http_client(&self) -> Option<reqwest::blocking::Client> {
+        None
+    }
+
+    fn translate(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        options: &TranslateOptions,
+    ) -> Result<String, String> {
+        self.translate_batch(&[text], source_lang, target_lang, options)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "API response missing translations".to_string())
+    }
+}
+
+/// The provider the configuration selects: the mock one for `url = "mock"`, a chat model
+/// when one is set, else the translation API.
+pub fn from_config(config: &Config) -> Result<Box<dyn TranslationProvider>, String> {
+    if config.api.url.as_deref() == Some(MOCK_URL) {
+        return Ok(Box::new(MockProvider));
+    }
+    if config.llm.model.is_some() {
+        return Ok(Box::new(LlmApi::from_config(config)?));
+    }
+    Ok(Box::new(PtruiApi::from_config(config)?))
+}
+
+/// What the `cycle-provider` quick action steps through: the configured provider, then the
+/// mock one so the panes keep working offline.
+pub fn available(config: &Config) -> Result<Vec<Box<dyn TranslationProvider>>, String> {
+    let mut providers = vec![from_config(config)?];
+    if providers[0].name() != MOCK_NAME {
+        providers.push(Box::new(MockProvider));
+    }
+    Ok(providers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Shout;
+
+    impl TranslationProvider for Shout {
+        fn translate_batch(
+            &self,
+            texts: &[&str],
+            _source_lang: &str,
+            _target_lang: &str,
+            _options: &TranslateOptions,
+        ) -> Result<Vec<String>, String> {
+            Ok(texts.iter().map(|text| text.to_uppercase()).collect())
+        }
+
+        fn name(&self) -> String {
+            "shout".to_string()
+        }
+    }
+
+    #[test]
+    fn single_translations_go_through_the_batch() {
+        let provider: &dyn TranslationProvider = &Shout;
+        assert_eq!(
+            provider.translate("hola", "ES", "EN", &TranslateOptions::default()),
+            Ok("HOLA".to_string())
+        );
+    }
+
+    #[test]
+    fn the_mock_provider_is_always_available() {
+        let names = |url: &str| {
+            let mut config = Config::default();
+            config.api.url = Some(url.to_string());
+            available(&config)
+                .unwrap()
+                .iter()
+                .map(|provider| provider.name())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(MOCK_URL), [MOCK_NAME]);
+        let names = names("http://localhost:9/translate");
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[1], MOCK_NAME);
+    }
+}
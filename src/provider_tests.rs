@@ -0,0 +1,301 @@
+//! Exercises the HTTP provider against a local wiremock server, so request building and error
+//! handling are covered without a real API key. Run with `cargo test --features test-provider`.
+
+use std::net::TcpListener;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+use crate::api::{
+    LlmApi, PtruiApi, TRANSLATION_API_TIMEOUTS, Timeouts, TranslateOptions, is_unreachable,
+};
+use crate::config::{ApiConfig, Config, LlmConfig};
+use crate::filter::translate_lines;
+use crate::provider::TranslationProvider;
+use crate::tone::Tone;
+
+/// Answers like a translation API by upper-casing every text it is sent.
+struct Shout;
+
+impl Respond for Shout {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        let translations: Vec<Value> = body["text"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|text| json!({ "text": text.as_str().unwrap().to_uppercase() }))
+            .collect();
+        ResponseTemplate::new(200).set_body_json(json!({ "translations": translations }))
+    }
+}
+
+/// Runs the blocking client off the async runtime, which reqwest's blocking client requires.
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    tokio::task::spawn_blocking(work).await.unwrap()
+}
+
+fn api(url: String, auth_key: Option<&str>, auth_header: Option<&str>) -> PtruiApi {
+    PtruiApi::new(
+        url,
+        auth_key.map(str::to_string),
+        auth_header.map(str::to_string),
+        TRANSLATION_API_TIMEOUTS,
+    )
+    .unwrap()
+}
+
+fn translate(api: &PtruiApi, text: &str) -> Result<String, String> {
+    api.translate(text, "EN", "ES", &TranslateOptions::default())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sends_payload_and_deepl_auth_header() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v2/translate"))
+        .and(header("Authorization", "DeepL-Auth-Key secret"))
+        .and(body_json(
+            json!({ "text": ["hello"], "source_lang": "EN", "target_lang": "ES" }),
+        ))
+        .respond_with(Shout)
+        .expect(1)
+        .mount(&server)
+        .await;
+    let url = format!("{}/v2/translate", server.uri());
+    let result = blocking(move || translate(&api(url, Some("secret"), None), "hello")).await;
+    assert_eq!(result.unwrap(), "HELLO");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn keeps_the_tone_out_of_the_payload() {
+    let server = MockServer::start().await;
+    Mock::given(body_json(
+        json!({ "text": ["hello"], "source_lang": "EN", "target_lang": "ES" }),
+    ))
+    .respond_with(Shout)
+    .expect(1)
+    .mount(&server)
+    .await;
+    let url = server.uri();
+    let result = blocking(move || {
+        let options = TranslateOptions {
+            tone: Tone::Casual.instruction(),
+            ..TranslateOptions::default()
+        };
+        api(url, None, None).translate("hello", "EN", "ES", &options)
+    })
+    .await;
+    assert_eq!(result.unwrap(), "HELLO");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sends_key_in_a_custom_header() {
+    let server = MockServer::start().await;
+    Mock::given(header("X-Api-Key", "secret"))
+        .respond_with(Shout)
+        .expect(1)
+        .mount(&server)
+        .await;
+    let url = server.uri();
+    let result =
+        blocking(move || translate(&api(url, Some("secret"), Some("X-Api-Key")), "hi")).await;
+    assert_eq!(result.unwrap(), "HI");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_error_statuses_with_the_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(456).set_body_string("Quota exceeded"))
+        .mount(&server)
+        .await;
+    let url = server.uri();
+    let error = blocking(move || translate(&api(url, None, None), "hi"))
+        .await
+        .unwrap_err();
+    assert!(error.contains("456"), "{}", error);
+    assert!(error.contains("Quota exceeded"), "{}", error);
+    assert!(!is_unreachable(&error));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_responses_missing_translations() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "translations": [] })))
+        .mount(&server)
+        .await;
+    let url = server.uri();
+    let error = blocking(move || translate(&api(url, None, None), "hi"))
+        .await
+        .unwrap_err();
+    assert_eq!(error, "API response missing translations");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn closed_port_is_reported_as_unreachable() {
+    let port = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let url = format!("http://127.0.0.1:{}/v2/translate", port);
+    let error = blocking(move || translate(&api(url, None, None), "hi"))
+        .await
+        .unwrap_err();
+    assert!(is_unreachable(&error), "{}", error);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn slow_responses_hit_the_deadline() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "translations": [{ "text": "HI" }] }))
+                .set_delay(Duration::from_secs(1)),
+        )
+        .mount(&server)
+        .await;
+    let url = server.uri();
+    let timeouts = Timeouts {
+        deadline: Duration::from_millis(200),
+        ..TRANSLATION_API_TIMEOUTS
+    };
+    let result = blocking(move || {
+        let api = PtruiApi::new(url, None, None, timeouts).unwrap();
+        translate(&api, "hi")
+    })
+    .await;
+    assert!(result.unwrap_err().contains("0.2s deadline"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn quiet_servers_hit_the_read_timeout() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "translations": [{ "text": "HI" }] }))
+                .set_delay(Duration::from_secs(1)),
+        )
+        .mount(&server)
+        .await;
+    let url = server.uri();
+    let timeouts = Timeouts {
+        read: Duration::from_millis(200),
+        ..TRANSLATION_API_TIMEOUTS
+    };
+    let result = blocking(move || {
+        let api = PtruiApi::new(url, None, None, timeouts).unwrap();
+        translate(&api, "hi")
+    })
+    .await;
+    assert!(result.unwrap_err().contains("sent nothing for 0.2s"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn lookups_use_their_own_deadline() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "translations": [{ "text": "HI" }] }))
+                .set_delay(Duration::from_millis(500)),
+        )
+        .mount(&server)
+        .await;
+    let url = server.uri();
+    let (lookup, document) = blocking(move || {
+        let api = api(url, None, None);
+        let lookup = TranslateOptions {
+            deadline: Some(Duration::from_millis(200)),
+            ..TranslateOptions::lookup()
+        };
+        (
+            api.translate("hi", "EN", "ES", &lookup),
+            api.translate("hi", "EN", "ES", &TranslateOptions::default()),
+        )
+    })
+    .await;
+    assert!(lookup.unwrap_err().contains("deadline"));
+    assert_eq!(document.unwrap(), "HI");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn documents_are_sent_in_chunks() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(Shout)
+        .expect(3)
+        .mount(&server)
+        .await;
+    let url = server.uri();
+    let translated = blocking(move || {
+        let lines: Vec<String> = (0..120).map(|index| format!("line {}", index)).collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        translate_lines(&api(url, None, None), &lines, "EN", "ES")
+    })
+    .await
+    .unwrap();
+    assert_eq!(translated.len(), 120);
+    assert_eq!(translated[119], "LINE 119");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn translates_through_a_chat_model() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(header("Authorization", "Bearer secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(
+            json!({ "choices": [{ "message": { "role": "assistant", "content": " Hola\n" } }] }),
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let config = Config {
+        api: ApiConfig {
+            url: Some(format!("{}/v1/chat/completions", server.uri())),
+            key: Some("secret".to_string()),
+            auth_header: None,
+            ..ApiConfig::default()
+        },
+        llm: LlmConfig {
+            model: Some("llama3".to_string()),
+            ..LlmConfig::default()
+        },
+        ..Config::default()
+    };
+    let result = blocking(move || {
+        let llm = LlmApi::from_config(&config).unwrap();
+        translate_lines(&llm, &["hello"], "EN", "ES")
+    })
+    .await;
+    assert_eq!(result.unwrap(), ["Hola"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reads_the_usage_endpoint() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v2/usage"))
+        .and(header("Authorization", "DeepL-Auth-Key secret"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "character_count": 1200, "character_limit": 500000 })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    let url = format!("{}/v2/translate", server.uri());
+    let quota = blocking(move || api(url, Some("secret"), None).quota()).await;
+    assert_eq!(quota.unwrap().map(|quota| quota.remaining()), Some(498_800));
+
+    // Servers without a usage endpoint report no quota rather than an error.
+    let url = format!("{}/translate", server.uri());
+    let quota = blocking(move || api(url, None, None).quota()).await;
+    assert_eq!(quota, Ok(None));
+}
@@ -0,0 +1,30 @@
+use qrcode::QrCode;
+use qrcode::render::unicode::Dense1x2;
+
+/// Renders text as a QR code of half-height unicode blocks, two modules per character cell.
+/// Colors are inverted so the code scans when drawn light-on-dark.
+pub fn render_qr(text: &str) -> Result<Vec<String>, String> {
+    let code = QrCode::new(text.as_bytes())
+        .map_err(|err| format!("Cannot encode as a QR code: {}", err))?;
+    let rendered = code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .quiet_zone(true)
+        .build();
+    Ok(rendered.lines().map(str::to_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_square_blocks() {
+        let lines = render_qr("Calle Mayor 1, Madrid").unwrap();
+        let width = lines[0].chars().count();
+        assert!(lines.iter().all(|line| line.chars().count() == width));
+        // Each text row holds two module rows.
+        assert_eq!(lines.len(), width.div_ceil(2));
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::api::TranslateOptions;
+use crate::provider::TranslationProvider;
+use crate::segment::split_sentences;
+
+/// Round-trip quality estimate for one translated sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentQuality {
+    // Line of the target text the sentence is on.
+    pub row: usize,
+    pub segment: String,
+    pub back_translation: String,
+    // 0.0 (meaning lost) to 1.0 (round trip matches the source).
+    pub score: f32,
+}
+
+impl SegmentQuality {
+    pub fn band(&self) -> QualityBand {
+        if self.score >= 0.7 {
+            QualityBand::Good
+        } else if self.score >= 0.4 {
+            QualityBand::Review
+        } else {
+            QualityBand::Poor
+        }
+    }
+}
+
+// Ordered from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QualityBand {
+    Good,
+    Review,
+    Poor,
+}
+
+/// Back-translates every target sentence and scores it against the closest source sentence.
+pub fn estimate_quality(
+    provider: &dyn TranslationProvider,
+    source_text: &str,
+    target_text: &str,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<Vec<SegmentQuality>, String> {
+    let segments: Vec<(usize, &str)> = target_text
+        .lines()
+        .enumerate()
+        .flat_map(|(row, line)| split_sentences(line).into_iter().map(move |s| (row, s)))
+        .collect();
+    if segments.is_empty() {
+        return Ok(Vec::new());
+    }
+    let texts: Vec<&str> = segments.iter().map(|(_, segment)| *segment).collect();
+    let back_translations = provider.translate_batch(
+        &texts,
+        target_lang,
+        source_lang,
+        &TranslateOptions::lookup(),
+    )?;
+    let sources = split_sentences(source_text);
+    Ok(segments
+        .iter()
+        .zip(back_translations)
+        .map(|((row, segment), back_translation)| {
+            let score = sources
+                .iter()
+                .map(|source| similarity(source, &back_translation))
+                .fold(0.0, f32::max);
+            SegmentQuality {
+                row: *row,
+                segment: segment.to_string(),
+                back_translation,
+                score,
+            }
+        })
+        .collect())
+}
+
+/// Estimates shown as markers in the target pane's gutter, for as long as the pane holds the
+/// text they were made for.
+pub struct QualityMarks {
+    lines: Vec<String>,
+    pub segments: Vec<SegmentQuality>,
+}
+
+impl QualityMarks {
+    pub fn new(lines: &[String], segments: Vec<SegmentQuality>) -> Self {
+        Self {
+            lines: lines.to_vec(),
+            segments,
+        }
+    }
+
+    /// The worst band on each row with a sentence, unless `lines` changed since.
+    pub fn rows(&self, lines: &[String]) -> Option<BTreeMap<usize, QualityBand>> {
+        if lines != self.lines {
+            return None;
+        }
+        let mut rows = BTreeMap::new();
+        for segment in &self.segments {
+            let band = rows.entry(segment.row).or_insert(QualityBand::Good);
+            *band = (*band).max(segment.band());
+        }
+        Some(rows)
+    }
+
+    /// Sentences that scored below good.
+    pub fn flagged(&self) -> usize {
+        self.segments
+            .iter()
+            .filter(|segment| segment.band() != QualityBand::Good)
+            .count()
+    }
+}
+
+/// Dice coefficient over lowercase word multisets.
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let count = |text: &str| {
+        let mut words: HashMap<String, usize> = HashMap::new();
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            *words.entry(word.to_lowercase()).or_default() += 1;
+        }
+        words
+    };
+    let (left, right) = (count(a), count(b));
+    let total: usize = left.values().sum::<usize>() + right.values().sum::<usize>();
+    if total == 0 {
+        return 1.0;
+    }
+    let shared: usize = left
+        .iter()
+        .map(|(word, n)| (*n).min(right.get(word).copied().unwrap_or(0)))
+        .sum();
+    (2 * shared) as f32 / total as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn similarity_is_word_overlap() {
+        assert_eq!(similarity("The cat sat.", "the cat sat"), 1.0);
+        assert_eq!(similarity("red car", "blue boat"), 0.0);
+        assert!((similarity("a big dog", "a small dog") - 2.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn marks_the_worst_sentence_of_each_row() {
+        let segment = |row, score| SegmentQuality {
+            row,
+            segment: String::new(),
+            back_translation: String::new(),
+            score,
+        };
+        let lines = ["Hola. Adiós.".to_string(), "Gracias.".to_string()];
+        let marks = QualityMarks::new(
+            &lines,
+            vec![segment(0, 0.9), segment(0, 0.1), segment(1, 0.5)],
+        );
+        assert_eq!(
+            marks.rows(&lines),
+            Some(BTreeMap::from([
+                (0, QualityBand::Poor),
+                (1, QualityBand::Review)
+            ]))
+        );
+        assert_eq!(marks.flagged(), 2);
+        assert_eq!(marks.rows(&lines[..1]), None);
+    }
+}
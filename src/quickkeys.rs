@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+/// High-level actions that can be bound to F1–F12, working the same in every vim mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickAction {
+    TranslateNow,
+    SwapText,
+    CycleTone,
+    CycleFormality,
+    CycleProvider,
+    History,
+    ToggleLayout,
+    Quality,
+    Speak,
+    QrCode,
+}
+
+impl QuickAction {
+    pub const ALL: [QuickAction; 10] = [
+        QuickAction::TranslateNow,
+        QuickAction::SwapText,
+        QuickAction::CycleTone,
+        QuickAction::CycleFormality,
+        QuickAction::CycleProvider,
+        QuickAction::History,
+        QuickAction::ToggleLayout,
+        QuickAction::Quality,
+        QuickAction::Speak,
+        QuickAction::QrCode,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::TranslateNow => "translate-now",
+            Self::SwapText => "swap-text",
+            Self::CycleTone => "cycle-tone",
+            Self::CycleFormality => "cycle-formality",
+            Self::CycleProvider => "cycle-provider",
+            Self::History => "history",
+            Self::ToggleLayout => "toggle-layout",
+            Self::Quality => "quality",
+            Self::Speak => "speak",
+            Self::QrCode => "qr",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Actions bound to F1–F12.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionKeys {
+    actions: [Option<QuickAction>; 12],
+}
+
+impl FunctionKeys {
+    /// Splits a `PTRUI_FKEYS` list like `F5=translate-now,F6=swap-text` into key and action.
+    pub fn bindings(spec: &str) -> Result<BTreeMap<String, String>, String> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|binding| {
+                let (key, action) = binding.split_once('=').ok_or_else(|| {
+                    format!(
+                        "Invalid PTRUI_FKEYS binding {:?}, expected F<n>=<action>",
+                        binding
+                    )
+                })?;
+                Ok((key.trim().to_string(), action.trim().to_string()))
+            })
+            .collect()
+    }
+
+    /// Keys from `[keys]` in the config file or from `PTRUI_FKEYS`.
+    pub fn from_bindings(bindings: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut keys = Self::default();
+        for (key, action) in bindings {
+            let index = key
+                .trim()
+                .strip_prefix(['F', 'f'])
+                .and_then(|number| number.parse::<usize>().ok())
+                .filter(|number| (1..=12).contains(number))
+                .ok_or_else(|| format!("Invalid function key {:?}, expected F1 to F12", key))?;
+            let action = QuickAction::parse(action.trim()).ok_or_else(|| {
+                let names: Vec<&str> = QuickAction::ALL
+                    .iter()
+                    .map(|action| action.name())
+                    .collect();
+                format!(
+                    "Unknown quick action {:?}; expected one of {}",
+                    action.trim(),
+                    names.join(", ")
+                )
+            })?;
+            keys.actions[index - 1] = Some(action);
+        }
+        Ok(keys)
+    }
+
+    /// The action bound to F`number`, if any.
+    pub fn action(&self, number: u8) -> Option<QuickAction> {
+        let index = usize::from(number).checked_sub(1)?;
+        self.actions.get(index).copied().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(spec: &str) -> Result<FunctionKeys, String> {
+        FunctionKeys::from_bindings(&FunctionKeys::bindings(spec)?)
+    }
+
+    #[test]
+    fn parses_bindings() {
+        let keys = parse("F5=translate-now, f12=Swap-Text,").unwrap();
+        assert_eq!(keys.action(5), Some(QuickAction::TranslateNow));
+        assert_eq!(keys.action(12), Some(QuickAction::SwapText));
+        let keys = parse("F2=cycle-formality,F3=cycle-provider,F4=toggle-layout").unwrap();
+        assert_eq!(keys.action(2), Some(QuickAction::CycleFormality));
+        assert_eq!(keys.action(3), Some(QuickAction::CycleProvider));
+        assert_eq!(keys.action(4), Some(QuickAction::ToggleLayout));
+        assert_eq!(keys.action(1), None);
+        assert_eq!(keys.action(0), None);
+        assert!(parse("F13=history").is_err());
+        assert!(parse("F1=launch").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::provider::TranslationProvider;
+
+// Usage only moves with translations, so there's no point asking more often.
+const QUOTA_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Characters translated and allowed this billing period, from DeepL's `/usage` endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct ApiQuota {
+    #[serde(rename = "character_count")]
+    pub used: u64,
+    #[serde(rename = "character_limit")]
+    pub limit: u64,
+}
+
+impl ApiQuota {
+    pub fn remaining(self) -> u64 {
+        self.limit.saturating_sub(self.used)
+    }
+
+    /// For the status bar, e.g. `quota 12.5k/500k left`.
+    pub fn label(self) -> String {
+        format!(
+            "quota {}/{} left",
+            abbreviate(self.remaining()),
+            abbreviate(self.limit)
+        )
+    }
+}
+
+fn abbreviate(count: u64) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => trim_decimal(count as f64 / 1_000.0, "k"),
+        _ => trim_decimal(count as f64 / 1_000_000.0, "M"),
+    }
+}
+
+fn trim_decimal(value: f64, suffix: &str) -> String {
+    let text = format!("{:.1}", value);
+    format!("{}{}", text.trim_end_matches(".0"), suffix)
+}
+
+/// Asks the provider for its quota on a background thread, so a slow usage endpoint never
+/// holds up typing. Checks are throttled to one a minute.
+pub struct QuotaWatcher {
+    provider: Arc<dyn TranslationProvider>,
+    sender: Sender<ApiQuota>,
+    receiver: Receiver<ApiQuota>,
+    last_check: Option<Instant>,
+}
+
+impl QuotaWatcher {
+    pub fn new(provider: Arc<dyn TranslationProvider>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            provider,
+            sender,
+            receiver,
+            last_check: None,
+        }
+    }
+
+    /// Starts a check unless one ran within the last minute.
+    pub fn check(&mut self) {
+        if self
+            .last_check
+            .is_some_and(|last| last.elapsed() < QUOTA_CHECK_INTERVAL)
+        {
+            return;
+        }
+        self.last_check = Some(Instant::now());
+        let provider = Arc::clone(&self.provider);
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            // Providers without a quota, or a failed check, leave the status bar without one.
+            if let Ok(Some(quota)) = provider.quota() {
+                let _ = sender.send(quota);
+            }
+        });
+    }
+
+    /// The newest answer, without waiting.
+    pub fn try_recv(&self) -> Option<ApiQuota> {
+        self.receiver.try_iter().last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_the_remaining_characters() {
+        let quota: ApiQuota =
+            serde_json::from_str(r#"{ "character_count": 487500, "character_limit": 500000 }"#)
+                .unwrap();
+        assert_eq!(quota.remaining(), 12_500);
+        assert_eq!(quota.label(), "quota 12.5k/500k left");
+        assert_eq!(abbreviate(999), "999");
+        assert_eq!(abbreviate(1_000_000), "1M");
+    }
+}
@@ -0,0 +1,72 @@
+use crate::phrasebook::PhraseEntry;
+
+/// Flashcard review of the phrasebook: each card shows its source until the answer is revealed.
+pub struct Review {
+    pub cards: Vec<PhraseEntry>,
+    pub index: usize,
+    pub revealed: bool,
+}
+
+impl Review {
+    /// Oldest cards first, the order they were saved in.
+    pub fn new(mut cards: Vec<PhraseEntry>) -> Self {
+        cards.sort_by_key(|card| card.added_at);
+        Self {
+            cards,
+            index: 0,
+            revealed: false,
+        }
+    }
+
+    pub fn current(&self) -> Option<&PhraseEntry> {
+        self.cards.get(self.index)
+    }
+
+    /// Shows the answer; true the first time, when the card counts as reviewed.
+    pub fn reveal(&mut self) -> bool {
+        let first = !self.revealed && !self.cards.is_empty();
+        self.revealed = true;
+        first
+    }
+
+    pub fn next(&mut self) {
+        if !self.cards.is_empty() {
+            self.index = (self.index + 1) % self.cards.len();
+        }
+        self.revealed = false;
+    }
+
+    pub fn previous(&mut self) {
+        if !self.cards.is_empty() {
+            self.index = (self.index + self.cards.len() - 1) % self.cards.len();
+        }
+        self.revealed = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(source: &str, added_at: u64) -> PhraseEntry {
+        PhraseEntry {
+            added_at,
+            ..PhraseEntry::new(source, "target", "EN", "ES", None)
+        }
+    }
+
+    #[test]
+    fn reviews_oldest_first_counting_each_reveal_once() {
+        let mut review = Review::new(vec![card("two", 2), card("one", 1)]);
+        assert_eq!(review.current().unwrap().source, "one");
+        assert!(review.reveal());
+        assert!(!review.reveal());
+        review.next();
+        assert_eq!(review.current().unwrap().source, "two");
+        assert!(!review.revealed);
+        review.next();
+        assert_eq!(review.current().unwrap().source, "one");
+        review.previous();
+        assert_eq!(review.current().unwrap().source, "two");
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+const PINYIN_TABLE: &str = include_str!("data/pinyin.txt");
+const FURIGANA_TABLE: &str = include_str!("data/furigana.txt");
+
+static PINYIN: LazyLock<HashMap<char, &'static str>> = LazyLock::new(|| {
+    PINYIN_TABLE
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (hanzi, reading) = line.split_once(' ')?;
+            Some((hanzi.chars().next()?, reading.trim()))
+        })
+        .collect()
+});
+
+static FURIGANA: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
+    FURIGANA_TABLE
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (word, reading) = line.split_once(' ')?;
+            Some((word, reading.trim()))
+        })
+        .collect()
+});
+
+/// Which reading aid accompanies the translation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadingAid {
+    #[default]
+    Off,
+    Romanization,
+    Ruby,
+}
+
+impl ReadingAid {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Romanization,
+            Self::Romanization => Self::Ruby,
+            Self::Ruby => Self::Off,
+        }
+    }
+
+    /// Row title for text in the given language.
+    pub fn title(self, code: &str) -> &'static str {
+        match self {
+            Self::Off => "",
+            // Kanji readings depend on context, so only kana is romanized.
+            Self::Romanization if code == "JA" => "Romanization (kana only, kanji as written)",
+            Self::Romanization => "Romanization",
+            Self::Ruby => "Readings",
+        }
+    }
+
+    /// Renders the aid for text in the given language, if that script has one.
+    pub fn apply(self, code: &str, text: &str) -> Option<String> {
+        match self {
+            Self::Off => None,
+            Self::Romanization => romanize(code, text),
+            Self::Ruby => ruby(code, text),
+        }
+    }
+}
+
+/// Annotates kanji/hanzi with their readings in brackets, e.g. `日本[にほん]`.
+pub fn ruby(code: &str, text: &str) -> Option<String> {
+    match code {
+        "JA" => Some(annotate_furigana(text)),
+        "ZH" => Some(
+            text.chars()
+                .map(|c| match pinyin(c) {
+                    Some(reading) => format!("{}[{}]", c, reading),
+                    None => c.to_string(),
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Greedy longest-match lookup against the bundled furigana dictionary.
+fn annotate_furigana(text: &str) -> String {
+    const LONGEST_ENTRY: usize = 4;
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let longest = LONGEST_ENTRY.min(chars.len() - index);
+        let found = (1..=longest).rev().find_map(|len| {
+            let word: String = chars[index..index + len].iter().collect();
+            FURIGANA
+                .get(word.as_str())
+                .map(|reading| (word, *reading, len))
+        });
+        match found {
+            Some((word, reading, len)) => {
+                out.push_str(&word);
+                out.push('[');
+                out.push_str(reading);
+                out.push(']');
+                index += len;
+            }
+            None => {
+                out.push(chars[index]);
+                index += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Romanizes text written in the given language, leaving unknown characters untouched. Japanese
+/// only has its kana romanized; kanji stay as written, see `ruby` for their readings.
+pub fn romanize(code: &str, text: &str) -> Option<String> {
+    match code {
+        "JA" => Some(romanize_kana(text)),
+        "ZH" => Some(romanize_hanzi(text)),
+        "KO" => Some(romanize_hangul(text)),
+        "RU" => Some(transliterate_cyrillic(text)),
+        _ => None,
+    }
+}
+
+/// Hanyu Pinyin for a single hanzi from the bundled table of common characters.
+pub fn pinyin(hanzi: char) -> Option<&'static str> {
+    PINYIN.get(&hanzi).copied()
+}
+
+fn romanize_hanzi(text: &str) -> String {
+    let mut out = String::new();
+    let mut previous_was_hanzi = false;
+    for c in text.chars() {
+        match pinyin(c) {
+            Some(reading) => {
+                if previous_was_hanzi {
+                    out.push(' ');
+                }
+                out.push_str(reading);
+                previous_was_hanzi = true;
+            }
+            None => {
+                out.push(full_width_punctuation(c).unwrap_or(c));
+                previous_was_hanzi = false;
+            }
+        }
+    }
+    out
+}
+
+fn full_width_punctuation(c: char) -> Option<char> {
+    Some(match c {
+        '。' => '.',
+        '，' | '、' => ',',
+        '？' => '?',
+        '！' => '!',
+        '：' => ':',
+        '；' => ';',
+        '「' | '」' | '『' | '』' => '"',
+        '（' => '(',
+        '）' => ')',
+        '　' => ' ',
+        _ => return None,
+    })
+}
+
+#[rustfmt::skip]
+const HIRAGANA: &[(&str, &str)] = &[
+    ("きゃ", "kya"), ("きゅ", "kyu"), ("きょ", "kyo"),
+    ("しゃ", "sha"), ("しゅ", "shu"), ("しょ", "sho"),
+    ("ちゃ", "cha"), ("ちゅ", "chu"), ("ちょ", "cho"),
+    ("にゃ", "nya"), ("にゅ", "nyu"), ("にょ", "nyo"),
+    ("ひゃ", "hya"), ("ひゅ", "hyu"), ("ひょ", "hyo"),
+    ("みゃ", "mya"), ("みゅ", "myu"), ("みょ", "myo"),
+    ("りゃ", "rya"), ("りゅ", "ryu"), ("りょ", "ryo"),
+    ("ぎゃ", "gya"), ("ぎゅ", "gyu"), ("ぎょ", "gyo"),
+    ("じゃ", "ja"), ("じゅ", "ju"), ("じょ", "jo"),
+    ("びゃ", "bya"), ("びゅ", "byu"), ("びょ", "byo"),
+    ("ぴゃ", "pya"), ("ぴゅ", "pyu"), ("ぴょ", "pyo"),
+    ("あ", "a"), ("い", "i"), ("う", "u"), ("え", "e"), ("お", "o"),
+    ("か", "ka"), ("き", "ki"), ("く", "ku"), ("け", "ke"), ("こ", "ko"),
+    ("さ", "sa"), ("し", "shi"), ("す", "su"), ("せ", "se"), ("そ", "so"),
+    ("た", "ta"), ("ち", "chi"), ("つ", "tsu"), ("て", "te"), ("と", "to"),
+    ("な", "na"), ("に", "ni"), ("ぬ", "nu"), ("ね", "ne"), ("の", "no"),
+    ("は", "ha"), ("ひ", "hi"), ("ふ", "fu"), ("へ", "he"), ("ほ", "ho"),
+    ("ま", "ma"), ("み", "mi"), ("む", "mu"), ("め", "me"), ("も", "mo"),
+    ("や", "ya"), ("ゆ", "yu"), ("よ", "yo"),
+    ("ら", "ra"), ("り", "ri"), ("る", "ru"), ("れ", "re"), ("ろ", "ro"),
+    ("わ", "wa"), ("を", "o"), ("ん", "n"),
+    ("が", "ga"), ("ぎ", "gi"), ("ぐ", "gu"), ("げ", "ge"), ("ご", "go"),
+    ("ざ", "za"), ("じ", "ji"), ("ず", "zu"), ("ぜ", "ze"), ("ぞ", "zo"),
+    ("だ", "da"), ("ぢ", "ji"), ("づ", "zu"), ("で", "de"), ("ど", "do"),
+    ("ば", "ba"), ("び", "bi"), ("ぶ", "bu"), ("べ", "be"), ("ぼ", "bo"),
+    ("ぱ", "pa"), ("ぴ", "pi"), ("ぷ", "pu"), ("ぺ", "pe"), ("ぽ", "po"),
+    ("ぁ", "a"), ("ぃ", "i"), ("ぅ", "u"), ("ぇ", "e"), ("ぉ", "o"),
+    ("ゃ", "ya"), ("ゅ", "yu"), ("ょ", "yo"),
+];
+
+/// Maps katakana to the matching hiragana so both scripts share one table.
+fn to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+fn romanize_kana(text: &str) -> String {
+    let chars: Vec<char> = text.chars().map(to_hiragana).collect();
+    let mut out = String::new();
+    let mut index = 0;
+    let mut double_next = false;
+    while index < chars.len() {
+        let c = chars[index];
+        if c == 'っ' {
+            double_next = true;
+            index += 1;
+            continue;
+        }
+        if c == 'ー' {
+            if let Some(vowel) = out.chars().last().filter(|v| "aiueo".contains(*v)) {
+                out.push(vowel);
+            }
+            index += 1;
+            continue;
+        }
+        let pair: String = chars[index..chars.len().min(index + 2)].iter().collect();
+        let single = c.to_string();
+        let found = HIRAGANA
+            .iter()
+            .find(|(kana, _)| *kana == pair)
+            .map(|(_, roman)| (*roman, 2))
+            .or_else(|| {
+                HIRAGANA
+                    .iter()
+                    .find(|(kana, _)| *kana == single)
+                    .map(|(_, roman)| (*roman, 1))
+            });
+        match found {
+            Some((roman, consumed)) => {
+                if double_next {
+                    // Sokuon doubles the following consonant ("tch" for ch).
+                    match roman.chars().next() {
+                        Some('c') => out.push('t'),
+                        Some(first) if !"aiueo".contains(first) => out.push(first),
+                        _ => {}
+                    }
+                }
+                out.push_str(roman);
+                index += consumed;
+            }
+            None => {
+                out.push(full_width_punctuation(c).unwrap_or(c));
+                index += 1;
+            }
+        }
+        double_next = false;
+    }
+    out
+}
+
+const HANGUL_INITIALS: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p",
+    "h",
+];
+const HANGUL_VOWELS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we",
+    "wi", "yu", "eu", "ui", "i",
+];
+// Final consonants when followed by another consonant or the end of a word.
+const HANGUL_FINALS: [&str; 28] = [
+    "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p", "p",
+    "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+];
+// Final consonants carried over onto a following silent ㅇ initial.
+const HANGUL_FINALS_LIAISON: [&str; 28] = [
+    "", "g", "kk", "ks", "n", "nj", "n", "d", "r", "lg", "lm", "lb", "ls", "lt", "lp", "r", "m",
+    "b", "ps", "s", "ss", "ng", "j", "ch", "k", "t", "p", "",
+];
+
+fn hangul_parts(c: char) -> Option<(usize, usize, usize)> {
+    let code = (c as u32).checked_sub(0xAC00)?;
+    if code >= 11172 {
+        return None;
+    }
+    let code = code as usize;
+    Some((code / (21 * 28), (code % (21 * 28)) / 28, code % 28))
+}
+
+/// Revised Romanization of Korean, with liaison into silent initials.
+fn romanize_hangul(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        let Some((initial, vowel, final_)) = hangul_parts(c) else {
+            out.push(c);
+            continue;
+        };
+        out.push_str(HANGUL_INITIALS[initial]);
+        out.push_str(HANGUL_VOWELS[vowel]);
+        let next_is_silent = chars
+            .get(index + 1)
+            .and_then(|next| hangul_parts(*next))
+            .is_some_and(|(next_initial, _, _)| next_initial == 11);
+        if next_is_silent {
+            out.push_str(HANGUL_FINALS_LIAISON[final_]);
+        } else {
+            out.push_str(HANGUL_FINALS[final_]);
+        }
+    }
+    out
+}
+
+fn cyrillic(c: char) -> Option<&'static str> {
+    Some(match c {
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' => "g",
+        'д' => "d",
+        'е' => "e",
+        'ё' => "yo",
+        'ж' => "zh",
+        'з' => "z",
+        'и' => "i",
+        'й' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' => "\"",
+        'ы' => "y",
+        'ь' => "'",
+        'э' => "e",
+        'ю' => "yu",
+        'я' => "ya",
+        _ => return None,
+    })
+}
+
+fn transliterate_cyrillic(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        match cyrillic(lower) {
+            Some(latin) if lower != c => {
+                let mut letters = latin.chars();
+                if let Some(first) = letters.next() {
+                    out.extend(first.to_uppercase());
+                    out.push_str(letters.as_str());
+                }
+            }
+            Some(latin) => out.push_str(latin),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn romanizes_each_script() {
+        assert_eq!(romanize("RU", "Привет, мир").unwrap(), "Privet, mir");
+        assert_eq!(romanize("KO", "한국어").unwrap(), "hangugeo");
+        assert_eq!(
+            romanize("JA", "きっぷ と コーヒー").unwrap(),
+            "kippu to koohii"
+        );
+        assert_eq!(romanize("ZH", "你好。").unwrap(), "nǐ hǎo.");
+        assert_eq!(romanize("ES", "hola"), None);
+    }
+
+    #[test]
+    fn ruby_prefers_longest_dictionary_entry() {
+        assert_eq!(ruby("JA", "日本語です").unwrap(), "日本語[にほんご]です");
+        assert_eq!(ruby("ZH", "你好!").unwrap(), "你[nǐ]好[hǎo]!");
+        assert_eq!(ruby("KO", "한국어"), None);
+    }
+}
@@ -0,0 +1,73 @@
+/// Splits text into sentences, keeping terminal punctuation with each sentence.
+///
+/// Line breaks always end a sentence, so segments never span lines.
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    for line in text.lines() {
+        let mut start = 0;
+        let mut chars = line.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            let terminal = matches!(c, '.' | '!' | '?' | '。' | '！' | '？' | '…');
+            if !terminal {
+                continue;
+            }
+            // Keep runs like "?!" or "..." and closing quotes together.
+            while let Some(&(_, next)) = chars.peek() {
+                if matches!(next, '.' | '!' | '?' | '"' | '\'' | '»' | '”' | ')' | '」') {
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let end = chars.peek().map(|(next, _)| *next).unwrap_or(line.len());
+            // Full-width punctuation ends a sentence on its own; ASCII needs a following space.
+            let boundary =
+                !c.is_ascii() || chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+            if boundary && end > index {
+                let sentence = line[start..end].trim();
+                if !sentence.is_empty() {
+                    sentences.push(sentence);
+                }
+                start = end;
+            }
+        }
+        let rest = line[start..].trim();
+        if !rest.is_empty() {
+            sentences.push(rest);
+        }
+    }
+    sentences
+}
+
+/// Pairs the n-th source sentence with the n-th translated sentence; the shorter side is
+/// padded with empty sentences.
+pub fn align_sentences<'a>(source: &'a str, target: &'a str) -> Vec<[&'a str; 2]> {
+    let source = split_sentences(source);
+    let target = split_sentences(target);
+    (0..source.len().max(target.len()))
+        .map(|i| {
+            [
+                source.get(i).copied().unwrap_or_default(),
+                target.get(i).copied().unwrap_or_default(),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_terminal_punctuation() {
+        assert_eq!(
+            split_sentences("Hi there. How are you?! Fine, 3.5 stars\nNext line"),
+            ["Hi there.", "How are you?!", "Fine, 3.5 stars", "Next line"]
+        );
+        assert_eq!(split_sentences("你好。再见！"), ["你好。", "再见！"]);
+        assert_eq!(
+            align_sentences("Hi. Bye.", "Hola."),
+            [["Hi.", "Hola."], ["Bye.", ""]]
+        );
+    }
+}
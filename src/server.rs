@@ -0,0 +1,293 @@
+use std::io::{self, BufRead, Write};
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::api::TranslateOptions;
+use crate::cache::{TranslationCache, translate_cached};
+use crate::config::Config;
+use crate::detect::detect_language;
+use crate::languages::{canonical_code, languages};
+use crate::provider::{self, TranslationProvider};
+use crate::tone::Tone;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+// Application error: the provider or cache failed to produce a translation.
+const TRANSLATION_FAILED: i64 = -32000;
+
+#[derive(Debug, Deserialize)]
+struct TranslateParams {
+    text: String,
+    target_lang: String,
+    // Detected from the text when omitted.
+    source_lang: Option<String>,
+    context: Option<String>,
+    tone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DetectParams {
+    text: String,
+}
+
+/// How messages are delimited on stdin; replies use the same framing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    // `Content-Length: N` headers, as language servers use.
+    Headers,
+    // One JSON message per line.
+    Lines,
+}
+
+/// JSON-RPC 2.0 service over stdio exposing ptrui's provider and cache to editor plugins.
+pub struct Server {
+    // Kept as an error so `languages` and `detect` still work without API settings.
+    provider: Result<Box<dyn TranslationProvider>, String>,
+    cache: TranslationCache,
+}
+
+impl Server {
+    pub fn new(
+        provider: Result<Box<dyn TranslationProvider>, String>,
+        cache: TranslationCache,
+    ) -> Self {
+        Self { provider, cache }
+    }
+
+    /// Answers one message; notifications (no `id`) get no reply.
+    pub fn handle(&mut self, message: &Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return Some(error_reply(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "missing method",
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let result = self.dispatch(method, params);
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_reply(id, code, &message),
+        })
+    }
+
+    fn dispatch(&mut self, method: &str, params: Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "serverInfo": { "name": "ptrui", "version": env!("CARGO_PKG_VERSION") },
+                "methods": ["translate", "detect", "languages"],
+            })),
+            // Nothing to tear down before `exit`; accepted for language-client compatibility.
+            "initialized" | "shutdown" => Ok(Value::Null),
+            "languages" => Ok(Value::Array(
+                languages()
+                    .iter()
+                    .map(|language| json!({ "code": language.code, "name": language.name }))
+                    .collect(),
+            )),
+            "detect" => {
+                let params: DetectParams = parse_params(params)?;
+                Ok(match detect_language(&params.text) {
+                    Some((code, confidence)) => {
+                        json!({ "language": code, "confidence": confidence })
+                    }
+                    None => json!({ "language": null, "confidence": 0.0 }),
+                })
+            }
+            "translate" => self.translate(parse_params(params)?),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method {:?}", method))),
+        }
+    }
+
+    fn translate(&mut self, params: TranslateParams) -> Result<Value, (i64, String)> {
+        let provider = self
+            .provider
+            .as_ref()
+            .map_err(|message| (TRANSLATION_FAILED, message.clone()))?;
+        let target_lang = language_code(&params.target_lang)?;
+        let source_lang = match &params.source_lang {
+            Some(code) => language_code(code)?,
+            None => detect_language(&params.text)
+                .map(|(code, _)| code.to_string())
+                .ok_or_else(|| {
+                    (
+                        INVALID_PARAMS,
+                        "could not detect the source language; pass source_lang".to_string(),
+                    )
+                })?,
+        };
+        let tone = match &params.tone {
+            Some(label) => Tone::ALL
+                .into_iter()
+                .find(|tone| tone.label().eq_ignore_ascii_case(label))
+                .ok_or_else(|| (INVALID_PARAMS, format!("unknown tone {:?}", label)))?,
+            None => Tone::Neutral,
+        };
+        let options = TranslateOptions {
+            context: params.context.as_deref(),
+            tone: tone.instruction(),
+            ..TranslateOptions::default()
+        };
+        let (text, provider) = translate_cached(
+            &mut self.cache,
+            provider.as_ref(),
+            &params.text,
+            &source_lang,
+            &target_lang,
+            &options,
+        )
+        .map_err(|message| (TRANSLATION_FAILED, message))?;
+        Ok(json!({
+            "text": text,
+            "source_lang": source_lang,
+            "target_lang": target_lang,
+            "provider": provider,
+        }))
+    }
+}
+
+/// Serves requests on stdin until `exit` or end of input.
+pub fn run_server() -> Result<(), String> {
+    let mut server = Server::new(
+        Config::load().and_then(|config| provider::from_config(&config)),
+        TranslationCache::from_env()?,
+    );
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut stdout = io::stdout().lock();
+    while let Some((framing, body)) = read_message(&mut input)? {
+        let reply = match serde_json::from_str::<Value>(&body) {
+            Ok(message) if message.get("method").and_then(Value::as_str) == Some("exit") => {
+                break;
+            }
+            Ok(message) => server.handle(&message),
+            Err(err) => Some(error_reply(Value::Null, PARSE_ERROR, &err.to_string())),
+        };
+        if let Some(reply) = reply {
+            write_message(&mut stdout, framing, &reply)?;
+        }
+    }
+    // Best effort: the totals only feed `ptrui usage`.
+    let _ = server.cache.stats.accumulate();
+    Ok(())
+}
+
+fn read_message(input: &mut impl BufRead) -> Result<Option<(Framing, String)>, String> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line).map_err(|err| err.to_string())? == 0 {
+            return Ok(None);
+        }
+        if !line.trim().is_empty() {
+            break;
+        }
+    }
+    let Some(length) = content_length(&line) else {
+        return Ok(Some((Framing::Lines, line.trim().to_string())));
+    };
+    let mut length = length?;
+    // Skip any other headers up to the blank separator line.
+    loop {
+        line.clear();
+        if input.read_line(&mut line).map_err(|err| err.to_string())? == 0 {
+            return Ok(None);
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some(value) = content_length(&line) {
+            length = value?;
+        }
+    }
+    let mut body = vec![0; length];
+    input
+        .read_exact(&mut body)
+        .map_err(|err| format!("Truncated message: {}", err))?;
+    let body = String::from_utf8(body).map_err(|err| err.to_string())?;
+    Ok(Some((Framing::Headers, body)))
+}
+
+fn content_length(line: &str) -> Option<Result<usize, String>> {
+    let (name, value) = line.split_once(':')?;
+    if !name.trim().eq_ignore_ascii_case("content-length") {
+        return None;
+    }
+    Some(
+        value
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid Content-Length: {}", value.trim())),
+    )
+}
+
+fn write_message(output: &mut impl Write, framing: Framing, reply: &Value) -> Result<(), String> {
+    let body = reply.to_string();
+    let written = match framing {
+        Framing::Headers => write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body),
+        Framing::Lines => writeln!(output, "{}", body),
+    };
+    written
+        .and_then(|_| output.flush())
+        .map_err(|err| format!("Failed to write stdout: {}", err))
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|err| (INVALID_PARAMS, err.to_string()))
+}
+
+fn language_code(code: &str) -> Result<String, (i64, String)> {
+    canonical_code(code).map_err(|message| (INVALID_PARAMS, message))
+}
+
+fn error_reply(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> Server {
+        Server::new(Err("no API".to_string()), TranslationCache::new(0))
+    }
+
+    #[test]
+    fn answers_requests_without_an_api() {
+        let mut server = server();
+        let reply = server.handle(&json!({
+            "jsonrpc": "2.0", "id": 1, "method": "detect", "params": { "text": "こんにちは" }
+        }));
+        assert_eq!(reply.unwrap()["result"]["language"], "JA");
+        let reply = server.handle(&json!({ "jsonrpc": "2.0", "id": 2, "method": "translate",
+            "params": { "text": "hi", "target_lang": "DE" } }));
+        assert_eq!(reply.unwrap()["error"]["code"], TRANSLATION_FAILED);
+        let reply = server.handle(&json!({ "jsonrpc": "2.0", "id": 3, "method": "nope" }));
+        assert_eq!(reply.unwrap()["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(
+            server.handle(&json!({ "jsonrpc": "2.0", "method": "initialized" })),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_both_framings() {
+        let mut input = io::Cursor::new(
+            "{\"id\":1}\nContent-Length: 8\r\nContent-Type: x\r\n\r\n{\"id\":2}".as_bytes(),
+        );
+        assert_eq!(
+            read_message(&mut input),
+            Ok(Some((Framing::Lines, "{\"id\":1}".to_string())))
+        );
+        assert_eq!(
+            read_message(&mut input),
+            Ok(Some((Framing::Headers, "{\"id\":2}".to_string())))
+        );
+        assert_eq!(read_message(&mut input), Ok(None));
+    }
+}
@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::draft::Draft;
+use crate::paths::data_dir;
+
+/// A named workspace: both panes, their languages and the provider it was translated with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub name: String,
+    #[serde(flatten)]
+    pub panes: Draft,
+    pub provider: String,
+}
+
+impl Session {
+    pub fn dir() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("sessions"))
+    }
+
+    fn path(name: &str) -> Result<PathBuf, String> {
+        validate_name(name)?;
+        let dir = Self::dir().ok_or_else(|| "Cannot locate a data directory".to_string())?;
+        Ok(dir.join(format!("{}.json", name)))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path(&self.name)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {:?}: {}", parent, err))?;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(|err| err.to_string())?;
+        fs::write(&path, contents).map_err(|err| format!("Failed to write {:?}: {}", path, err))
+    }
+
+    pub fn load(name: &str) -> Result<Self, String> {
+        let path = Self::path(name)?;
+        let contents = fs::read_to_string(&path).map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => format!("No session named {:?}", name),
+            _ => format!("Failed to read {:?}: {}", path, err),
+        })?;
+        serde_json::from_str(&contents)
+            .map_err(|err| format!("Invalid session {:?}: {}", name, err))
+    }
+
+    pub fn delete(name: &str) -> Result<(), String> {
+        let path = Self::path(name)?;
+        fs::remove_file(&path).map_err(|err| format!("Failed to remove {:?}: {}", path, err))
+    }
+
+    /// Every saved session, most recently saved first.
+    pub fn list() -> Result<Vec<Self>, String> {
+        let Some(dir) = Self::dir() else {
+            return Ok(Vec::new());
+        };
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(format!("Failed to read {:?}: {}", dir, err)),
+        };
+        let mut sessions: Vec<Self> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .filter_map(|contents| serde_json::from_str(&contents).ok())
+            .collect();
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.panes.saved_at));
+        Ok(sessions)
+    }
+}
+
+/// Session names become file names, so keep them to a safe, portable set.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid session name {:?}: use letters, digits, '-', '_' or '.'",
+            name
+        ))
+    }
+}
+
+/// Popup listing saved sessions.
+pub struct SessionPicker {
+    pub sessions: Vec<Session>,
+    pub selected: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_unsafe_names() {
+        assert!(validate_name("thesis-2024").is_ok());
+        assert!(validate_name("../escape").is_err());
+        assert!(validate_name(".hidden").is_err());
+        assert!(validate_name("").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// How to speak text aloud: an external command plus per-language voices.
+pub struct SpeechSettings {
+    // Command line with an optional `{voice}` placeholder; the text is sent on stdin.
+    pub command: String,
+    // Language code (e.g. "ES") to voice name understood by the command.
+    pub voices: HashMap<String, String>,
+}
+
+impl SpeechSettings {
+    pub fn from_env() -> Self {
+        let command = env::var("PTRUI_TTS_COMMAND").unwrap_or_else(|_| default_command().into());
+        let voices = env::var("PTRUI_TTS_VOICES")
+            .map(|value| parse_voices(&value))
+            .unwrap_or_default();
+        Self { command, voices }
+    }
+
+    /// Builds the program and arguments for a language, dropping `-v {voice}` when unmapped.
+    pub fn command_line(&self, language_code: &str) -> Vec<String> {
+        let voice = self.voices.get(&language_code.to_ascii_uppercase());
+        let mut args: Vec<String> = Vec::new();
+        for token in self.command.split_whitespace() {
+            if token.contains("{voice}") {
+                match voice {
+                    Some(voice) => args.push(token.replace("{voice}", voice)),
+                    None => {
+                        if args.last().is_some_and(|flag| flag.starts_with('-')) {
+                            args.pop();
+                        }
+                    }
+                }
+            } else {
+                args.push(token.to_string());
+            }
+        }
+        args
+    }
+}
+
+fn default_command() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "say -v {voice}"
+    } else {
+        "espeak-ng -v {voice} --stdin"
+    }
+}
+
+/// Parses `ES=es-419,JA=Kyoko` into a code-to-voice map.
+fn parse_voices(value: &str) -> HashMap<String, String> {
+    value
+        .split(',')
+        .filter_map(|pair| {
+            let (code, voice) = pair.split_once('=')?;
+            Some((code.trim().to_ascii_uppercase(), voice.trim().to_string()))
+        })
+        .filter(|(code, voice)| !code.is_empty() && !voice.is_empty())
+        .collect()
+}
+
+/// Starts speaking in the background so the UI keeps running during playback.
+pub fn speak(settings: &SpeechSettings, text: &str, language_code: &str) -> Result<(), String> {
+    let args = settings.command_line(language_code);
+    let Some((program, rest)) = args.split_first() else {
+        return Err("PTRUI_TTS_COMMAND is empty".to_string());
+    };
+    let mut child = Command::new(program)
+        .args(rest)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("Failed to start {}: {}", program, err))?;
+    let mut stdin = child.stdin.take();
+    let text = text.to_string();
+    thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            let _ = stdin.write_all(text.as_bytes());
+        }
+        drop(stdin);
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(command: &str, voices: &str) -> SpeechSettings {
+        SpeechSettings {
+            command: command.to_string(),
+            voices: parse_voices(voices),
+        }
+    }
+
+    #[test]
+    fn parses_voices_by_uppercase_code() {
+        let voices = parse_voices(" es = es-419,ja=Kyoko,=x,FR=,broken");
+        assert_eq!(voices.len(), 2);
+        assert_eq!(voices["ES"], "es-419");
+        assert_eq!(voices["JA"], "Kyoko");
+    }
+
+    #[test]
+    fn fills_the_voice_or_drops_its_flag() {
+        let speech = settings("espeak-ng -v {voice} --stdin", "ES=es-419");
+        assert_eq!(
+            speech.command_line("es"),
+            ["espeak-ng", "-v", "es-419", "--stdin"]
+        );
+        assert_eq!(speech.command_line("DE"), ["espeak-ng", "--stdin"]);
+        let speech = settings("tts --voice={voice}", "");
+        assert_eq!(speech.command_line("DE"), ["tts"]);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dates::{day_number, format_day, unix_now};
+use crate::paths::data_dir;
+
+/// Practice counters for a single (UTC) day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayStats {
+    pub chars_translated: u64,
+    // Phrasebook flashcards revealed in `:review`.
+    #[serde(default)]
+    pub cards_reviewed: u64,
+}
+
+/// Daily practice history, keyed by day number since the Unix epoch.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PracticeStats {
+    pub days: BTreeMap<i64, DayStats>,
+}
+
+impl PracticeStats {
+    pub fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("stats.json"))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or_else(|| "Cannot locate a data directory".to_string())?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {:?}: {}", parent, err))?;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(|err| err.to_string())?;
+        fs::write(&path, contents).map_err(|err| format!("Failed to write {:?}: {}", path, err))
+    }
+
+    fn today_mut(&mut self) -> &mut DayStats {
+        self.days.entry(day_number(unix_now())).or_default()
+    }
+
+    pub fn record_chars(&mut self, chars: usize) {
+        self.today_mut().chars_translated += chars as u64;
+    }
+
+    pub fn record_review(&mut self) {
+        self.today_mut().cards_reviewed += 1;
+    }
+
+    pub fn day(&self, day: i64) -> DayStats {
+        self.days.get(&day).copied().unwrap_or_default()
+    }
+
+    /// Consecutive active days ending today, or yesterday if today has no activity yet.
+    pub fn streak(&self, today: i64) -> u64 {
+        let mut day = if self.days.contains_key(&today) {
+            today
+        } else {
+            today - 1
+        };
+        let mut streak = 0;
+        while self.days.contains_key(&day) {
+            streak += 1;
+            day -= 1;
+        }
+        streak
+    }
+
+    pub fn longest_streak(&self) -> u64 {
+        let mut longest = 0;
+        let mut current = 0;
+        let mut previous = None;
+        for &day in self.days.keys() {
+            current = if previous == Some(day - 1) {
+                current + 1
+            } else {
+                1
+            };
+            longest = longest.max(current);
+            previous = Some(day);
+        }
+        longest
+    }
+
+    /// Human-readable summary lines shared by the stats panel and `ptrui stats`.
+    pub fn summary_lines(&self) -> Vec<String> {
+        let today = day_number(unix_now());
+        let today_stats = self.day(today);
+        let total_chars: u64 = self.days.values().map(|day| day.chars_translated).sum();
+        let total_cards: u64 = self.days.values().map(|day| day.cards_reviewed).sum();
+        let mut lines = vec![
+            format!(
+                "Today: {} chars translated, {} cards reviewed",
+                today_stats.chars_translated, today_stats.cards_reviewed
+            ),
+            format!(
+                "Streak: {} days (longest {})",
+                self.streak(today),
+                self.longest_streak()
+            ),
+            format!(
+                "All time: {} chars over {} days, {} cards",
+                total_chars,
+                self.days.len(),
+                total_cards
+            ),
+            String::new(),
+            "Last 7 days:".to_string(),
+        ];
+        for day in (today - 6)..=today {
+            let stats = self.day(day);
+            lines.push(format!(
+                "  {}  {:>6} chars  {:>3} cards",
+                format_day(day),
+                stats.chars_translated,
+                stats.cards_reviewed
+            ));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streak_counts_consecutive_days() {
+        let mut stats = PracticeStats::default();
+        for day in [10, 11, 12, 20, 21] {
+            stats.days.insert(day, DayStats::default());
+        }
+        assert_eq!(stats.streak(21), 2);
+        assert_eq!(stats.streak(22), 2);
+        assert_eq!(stats.streak(23), 0);
+        assert_eq!(stats.longest_streak(), 3);
+    }
+}
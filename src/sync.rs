@@ -0,0 +1,175 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// A shared directory (Syncthing, git, a network drive) that machines merge data through.
+///
+/// Each machine only ever appends to its own `<kind>-<machine>.jsonl` files, so file-level
+/// sync never has to resolve conflicts; readers merge every machine's file on load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncDir {
+    pub dir: PathBuf,
+    pub machine: String,
+}
+
+impl SyncDir {
+    /// Reads `PTRUI_SYNC_DIR`, naming this machine by `PTRUI_SYNC_NAME` or the hostname.
+    pub fn from_env() -> Option<Self> {
+        let dir = env::var_os("PTRUI_SYNC_DIR").filter(|value| !value.is_empty())?;
+        let machine = env::var("PTRUI_SYNC_NAME")
+            .ok()
+            .or_else(|| env::var("HOSTNAME").ok())
+            .or_else(|| fs::read_to_string("/etc/hostname").ok())
+            .map(|name| sanitize_machine(&name))
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "default".to_string());
+        Some(Self {
+            dir: PathBuf::from(dir),
+            machine,
+        })
+    }
+
+    pub fn own_file(&self, kind: &str) -> PathBuf {
+        self.dir.join(format!("{}-{}.jsonl", kind, self.machine))
+    }
+
+    pub fn append<T: Serialize>(&self, kind: &str, record: &T) -> Result<(), String> {
+        append_jsonl(&self.own_file(kind), record)
+    }
+
+    /// Records of one kind from every machine, this one included.
+    pub fn load_all<T: DeserializeOwned>(&self, kind: &str) -> Result<Vec<T>, String> {
+        self.load_files(kind, |_| true)
+    }
+
+    /// Records of one kind written by this machine.
+    pub fn load_own<T: DeserializeOwned>(&self, kind: &str) -> Result<Vec<T>, String> {
+        let own = self.own_file(kind);
+        self.load_files(kind, |path| path == own)
+    }
+
+    /// Records of one kind written by the other machines.
+    pub fn load_others<T: DeserializeOwned>(&self, kind: &str) -> Result<Vec<T>, String> {
+        let own = self.own_file(kind);
+        self.load_files(kind, |path| path != own)
+    }
+
+    /// Rewrites this machine's file keeping only the records that pass `keep`.
+    pub fn retain_own<T: Serialize + DeserializeOwned>(
+        &self,
+        kind: &str,
+        keep: impl Fn(&T) -> bool,
+    ) -> Result<(), String> {
+        let path = self.own_file(kind);
+        if !path.exists() {
+            return Ok(());
+        }
+        let mut contents = String::new();
+        for record in read_jsonl::<T>(&path)?.iter().filter(|record| keep(record)) {
+            contents.push_str(&serde_json::to_string(record).map_err(|err| err.to_string())?);
+            contents.push('\n');
+        }
+        fs::write(&path, contents).map_err(|err| format!("Failed to write {:?}: {}", path, err))
+    }
+
+    fn load_files<T: DeserializeOwned>(
+        &self,
+        kind: &str,
+        include: impl Fn(&Path) -> bool,
+    ) -> Result<Vec<T>, String> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(format!("Failed to read {:?}: {}", self.dir, err)),
+        };
+        let prefix = format!("{}-", kind);
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".jsonl"))
+            })
+            .filter(|path| include(path))
+            .collect();
+        paths.sort();
+        let mut records = Vec::new();
+        for path in paths {
+            records.extend(read_jsonl(&path)?);
+        }
+        Ok(records)
+    }
+}
+
+/// Keeps machine names safe to use in file names.
+fn sanitize_machine(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Appends one record as a JSON line, creating parent directories on first use.
+pub fn append_jsonl<T: Serialize>(path: &Path, record: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Failed to create {:?}: {}", parent, err))?;
+    }
+    let line = serde_json::to_string(record).map_err(|err| err.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|err| format!("Failed to open {:?}: {}", path, err))?;
+    writeln!(file, "{}", line).map_err(|err| format!("Failed to write {:?}: {}", path, err))
+}
+
+/// Reads every record, skipping lines that no longer parse (e.g. a half-synced last line).
+pub fn read_jsonl<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>, String> {
+    let file = fs::File::open(path).map_err(|err| format!("Failed to open {:?}: {}", path, err))?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_files_from_every_machine() {
+        let dir = env::temp_dir().join(format!("ptrui-sync-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let laptop = SyncDir {
+            dir: dir.clone(),
+            machine: "laptop".to_string(),
+        };
+        let desktop = SyncDir {
+            machine: "desktop".to_string(),
+            ..laptop.clone()
+        };
+        laptop.append("phrasebook", &1).unwrap();
+        desktop.append("phrasebook", &2).unwrap();
+        desktop.append("history", &3).unwrap();
+
+        assert_eq!(laptop.load_all::<i32>("phrasebook").unwrap(), [2, 1]);
+        assert_eq!(laptop.load_others::<i32>("phrasebook").unwrap(), [2]);
+        assert_eq!(laptop.load_own::<i32>("phrasebook").unwrap(), [1]);
+        desktop.retain_own::<i32>("phrasebook", |_| false).unwrap();
+        assert_eq!(laptop.load_all::<i32>("phrasebook").unwrap(), [1]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
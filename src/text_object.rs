@@ -0,0 +1,187 @@
+/// A (row, column) position in a textarea, columns counted in characters.
+pub type Position = (usize, usize);
+
+/// The range vim's `i`/`a` text objects cover around `cursor`, end exclusive: words (`w`,
+/// `W`), quoted strings (`"`, `'`, `` ` ``) and bracket pairs (`(`/`)`/`b`, `[`/`]`,
+/// `{`/`}`/`B`, `<`/`>`). Words and quotes stay on the cursor's line; brackets may span lines.
+pub fn text_object(
+    lines: &[String],
+    cursor: Position,
+    object: char,
+    around: bool,
+) -> Option<(Position, Position)> {
+    let line: Vec<char> = lines.get(cursor.0)?.chars().collect();
+    // The textarea's cursor can sit just past the last character, where vim's never does.
+    let (row, col) = (cursor.0, cursor.1.min(line.len().saturating_sub(1)));
+    let on_line = |(start, end)| Some(((row, start), (row, end)));
+    match object {
+        'w' => on_line(word(&line, col, around, is_word_char)?),
+        'W' => on_line(word(&line, col, around, |c| !c.is_whitespace())?),
+        '"' | '\'' | '`' => on_line(quoted(&line, col, object, around)?),
+        '(' | ')' | 'b' => brackets(lines, (row, col), '(', ')', around),
+        '[' | ']' => brackets(lines, (row, col), '[', ']', around),
+        '{' | '}' | 'B' => brackets(lines, (row, col), '{', '}', around),
+        '<' | '>' => brackets(lines, (row, col), '<', '>', around),
+        _ => None,
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The run of word characters, punctuation or blanks under the cursor; `around` adds the
+/// blanks after it, or before it when there are none after.
+fn word(
+    line: &[char],
+    col: usize,
+    around: bool,
+    is_word: impl Fn(char) -> bool,
+) -> Option<(usize, usize)> {
+    let at = *line.get(col)?;
+    let class = |c: char| {
+        if c.is_whitespace() {
+            0
+        } else if is_word(c) {
+            1
+        } else {
+            2
+        }
+    };
+    let same = |c: char| class(c) == class(at);
+    let mut start = col;
+    while start > 0 && same(line[start - 1]) {
+        start -= 1;
+    }
+    let mut end = col + 1;
+    while end < line.len() && same(line[end]) {
+        end += 1;
+    }
+    if around && !at.is_whitespace() {
+        let trailing = line[end..].iter().take_while(|c| c.is_whitespace()).count();
+        if trailing > 0 {
+            end += trailing;
+        } else {
+            start -= line[..start]
+                .iter()
+                .rev()
+                .take_while(|c| c.is_whitespace())
+                .count();
+        }
+    }
+    Some((start, end))
+}
+
+/// The quoted string containing the cursor, or else the next one on the line.
+fn quoted(line: &[char], col: usize, quote: char, around: bool) -> Option<(usize, usize)> {
+    let quotes: Vec<usize> = (0..line.len()).filter(|&i| line[i] == quote).collect();
+    let (open, close) = quotes
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|&(_, close)| col <= close)?;
+    if around {
+        Some((open, close + 1))
+    } else {
+        Some((open + 1, close))
+    }
+}
+
+/// The innermost `open`/`close` pair around the cursor, skipping nested pairs.
+fn brackets(
+    lines: &[String],
+    (row, col): Position,
+    open: char,
+    close: char,
+    around: bool,
+) -> Option<(Position, Position)> {
+    let text: Vec<char> = lines.join("\n").chars().collect();
+    let cursor = lines[..row]
+        .iter()
+        .map(|line| line.chars().count() + 1)
+        .sum::<usize>()
+        + col;
+    let mut depth = 0;
+    let mut start = None;
+    // A cursor on the closing bracket belongs to that pair.
+    let from = if text.get(cursor) == Some(&close) {
+        cursor.checked_sub(1)?
+    } else {
+        cursor
+    };
+    for i in (0..=from.min(text.len().checked_sub(1)?)).rev() {
+        if text[i] == close {
+            depth += 1;
+        } else if text[i] == open {
+            if depth == 0 {
+                start = Some(i);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let start = start?;
+    depth = 0;
+    let mut end = None;
+    for (i, &c) in text.iter().enumerate().skip(start + 1) {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                end = Some(i);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    // An unbalanced close before the cursor can pair up with an open that encloses nothing.
+    let end = end.filter(|&end| end >= cursor)?;
+    let (start, end) = if around {
+        (start, end + 1)
+    } else {
+        (start + 1, end)
+    };
+    Some((position(&text, start), position(&text, end)))
+}
+
+fn position(text: &[char], offset: usize) -> Position {
+    let before = &text[..offset];
+    let row = before.iter().filter(|&&c| c == '\n').count();
+    let col = before.iter().rev().take_while(|&&c| c != '\n').count();
+    (row, col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_words_quotes_and_brackets() {
+        let lines = ["say \"hola amigo\" (uno (dos)", "tres) fin"].map(str::to_string);
+        assert_eq!(
+            text_object(&lines, (0, 6), 'w', false),
+            Some(((0, 5), (0, 9)))
+        );
+        assert_eq!(
+            text_object(&lines, (0, 6), 'w', true),
+            Some(((0, 5), (0, 10)))
+        );
+        assert_eq!(
+            text_object(&lines, (0, 1), '"', false),
+            Some(((0, 5), (0, 15)))
+        );
+        assert_eq!(
+            text_object(&lines, (0, 4), '"', true),
+            Some(((0, 4), (0, 16)))
+        );
+        // Nested pairs are skipped; the outer one spans the line break.
+        assert_eq!(
+            text_object(&lines, (0, 19), 'b', false),
+            Some(((0, 18), (1, 4)))
+        );
+        assert_eq!(
+            text_object(&lines, (0, 24), ')', true),
+            Some(((0, 22), (0, 27)))
+        );
+        assert_eq!(text_object(&lines, (1, 7), '(', false), None);
+    }
+}
@@ -1,5 +1,5 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use tui_textarea::{Input, Key, TextArea};
+use tui_textarea::{CursorMove, Input, Key, TextArea};
 
 pub fn textarea_text(textarea: &TextArea) -> String {
     textarea.lines().join("\n")
@@ -33,6 +33,178 @@ pub fn textarea_input_from_key(key: KeyEvent) -> Input {
     }
 }
 
+/// The first row and column shown the last time the textarea was drawn. tui-textarea keeps
+/// its viewport private, but moving a copy's cursor into view from the left edge of each
+/// visible row lands on it.
+pub fn scroll_offset(textarea: &TextArea, height: u16) -> (usize, usize) {
+    let mut probe = textarea.clone();
+    probe.move_cursor(CursorMove::Jump(0, 0));
+    probe.move_cursor(CursorMove::InViewport);
+    let top = probe.cursor().0;
+    let bottom = (top + height as usize).min(textarea.lines().len());
+    // A row shorter than the horizontal scroll shows nothing, so the longest visible row
+    // gives the column.
+    let left = (top..bottom)
+        .map(|row| {
+            probe.move_cursor(CursorMove::Jump(row as u16, 0));
+            probe.move_cursor(CursorMove::InViewport);
+            probe.cursor().1
+        })
+        .max()
+        .unwrap_or(0);
+    (top, left)
+}
+
+/// How many edits each pane can undo unless the config says otherwise.
+pub const DEFAULT_UNDO_LEVELS: usize = 1000;
+
+/// Replaces the text and moves to the top. The old text stays in the undo history, unlike
+/// assigning a fresh textarea.
 pub fn set_textarea_text(textarea: &mut TextArea, text: &str) {
-    *textarea = TextArea::from(text.lines());
+    replace_textarea_text(textarea, text);
+    textarea.move_cursor(CursorMove::Jump(0, 0));
+}
+
+/// Replaces the text in place so the cursor (clamped to the new text) and the scroll position
+/// survive, unlike `set_textarea_text` which moves to the top.
+///
+/// Only the span between the common prefix and suffix is rewritten, as ordinary edits, so `u`
+/// steps back through earlier versions; output that only grew is a single undo step.
+pub fn replace_textarea_text(textarea: &mut TextArea, text: &str) {
+    let old: Vec<char> = textarea_text(textarea).chars().collect();
+    let new: Vec<char> = text
+        .lines()
+        .collect::<Vec<_>>()
+        .join("\n")
+        .chars()
+        .collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix + suffix == old.len() && old.len() == new.len() {
+        return;
+    }
+    let cursor = textarea.cursor();
+    textarea.cancel_selection();
+    jump_to(textarea, char_position(&old, prefix));
+    if prefix + suffix < old.len() {
+        textarea.start_selection();
+        jump_to(textarea, char_position(&old, old.len() - suffix));
+    }
+    let replacement: String = new[prefix..new.len() - suffix].iter().collect();
+    textarea.insert_str(replacement);
+    jump_to(textarea, cursor);
+}
+
+/// Selects from `start` up to, not including, `end`, leaving the cursor at `end`.
+pub fn select_range(textarea: &mut TextArea, start: (usize, usize), end: (usize, usize)) {
+    textarea.cancel_selection();
+    jump_to(textarea, start);
+    textarea.start_selection();
+    jump_to(textarea, end);
+}
+
+fn jump_to(textarea: &mut TextArea, (row, col): (usize, usize)) {
+    let clamp = |n: usize| n.min(u16::MAX as usize) as u16;
+    textarea.move_cursor(CursorMove::Jump(clamp(row), clamp(col)));
+}
+
+/// The (row, column) of a character offset into newline-joined text.
+fn char_position(text: &[char], offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let row = before.iter().filter(|&&c| c == '\n').count();
+    let col = before.iter().rev().take_while(|&&c| c != '\n').count();
+    (row, col)
+}
+
+/// The word under (or just before) the cursor, without surrounding punctuation.
+pub fn word_at_cursor(textarea: &TextArea) -> Option<String> {
+    let (row, col) = textarea.cursor();
+    let line: Vec<char> = textarea.lines().get(row)?.chars().collect();
+    let is_word = |c: char| c.is_alphanumeric() || c == '\'' || c == '-';
+    let mut start = col.min(line.len());
+    if start == line.len() || !is_word(line[start]) {
+        if start > 0 && is_word(line[start - 1]) {
+            start -= 1;
+        } else {
+            return None;
+        }
+    }
+    let mut end = start;
+    while start > 0 && is_word(line[start - 1]) {
+        start -= 1;
+    }
+    while end < line.len() && is_word(line[end]) {
+        end += 1;
+    }
+    Some(line[start..end].iter().collect())
+}
+
+/// Text covered by the visual selection, including the character under the cursor like Vim.
+pub fn selected_text(textarea: &TextArea) -> Option<String> {
+    let ((start_row, start_col), (end_row, end_col)) = textarea.selection_range()?;
+    let lines = textarea.lines();
+    let mut parts = Vec::new();
+    for row in start_row..=end_row {
+        let chars: Vec<char> = lines.get(row)?.chars().collect();
+        let from = if row == start_row { start_col } else { 0 };
+        let to = if row == end_row {
+            (end_col + 1).min(chars.len())
+        } else {
+            chars.len()
+        };
+        parts.push(chars[from.min(to)..to].iter().collect::<String>());
+    }
+    Some(parts.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replacing_text_keeps_the_cursor() {
+        let mut textarea = TextArea::from(["uno", "dos", "tres"]);
+        textarea.move_cursor(CursorMove::Jump(2, 1));
+        replace_textarea_text(&mut textarea, "one\ntwo\nthree\n");
+        assert_eq!(textarea.lines(), ["one", "two", "three"]);
+        assert_eq!(textarea.cursor(), (2, 1));
+        replace_textarea_text(&mut textarea, "one");
+        assert_eq!(textarea.cursor(), (0, 1));
+        // Earlier versions come back with undo; a rewrite takes a delete and an insert.
+        textarea.undo();
+        assert_eq!(textarea.lines(), ["one", "two", "three"]);
+        textarea.undo();
+        textarea.undo();
+        assert_eq!(textarea.lines(), ["uno", "dos", "tres"]);
+        // Setting the text moves to the top but can be undone just the same.
+        set_textarea_text(&mut textarea, "una\ndos");
+        assert_eq!(textarea.cursor(), (0, 0));
+        textarea.undo();
+        textarea.undo();
+        assert_eq!(textarea.lines(), ["uno", "dos", "tres"]);
+    }
+
+    #[test]
+    fn finds_where_the_textarea_scrolled_to() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let lines: Vec<String> = (0..10).map(|row| format!("line {}", row)).collect();
+        let mut textarea = TextArea::new(lines);
+        let mut terminal = Terminal::new(TestBackend::new(4, 3)).unwrap();
+        textarea.move_cursor(CursorMove::Jump(7, 6));
+        terminal
+            .draw(|frame| frame.render_widget(&textarea, frame.area()))
+            .unwrap();
+        assert_eq!(scroll_offset(&textarea, 3), (5, 3));
+        // Moving the cursor back into view doesn't scroll until the next draw.
+        textarea.move_cursor(CursorMove::Jump(6, 0));
+        assert_eq!(scroll_offset(&textarea, 3), (5, 3));
+        assert_eq!(scroll_offset(&TextArea::default(), 3), (0, 0));
+    }
 }
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::BorderType;
+
+/// Every color on screen: which pane is active, whether a translation is ready, pending or
+/// failed, how common a word is, and the chrome of popups and hints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    // Pane text.
+    pub text: Color,
+    // Border and cursor line of the pane being edited.
+    pub active: Color,
+    pub ok: Color,
+    pub pending: Color,
+    pub error: Color,
+    // Word frequency bands.
+    pub uncommon: Color,
+    pub rare: Color,
+    // Hints, timestamps and other secondary text.
+    pub muted: Color,
+    // Popup borders and titles.
+    pub border: Color,
+    // Prompts, the typing cursor in popups and glosses.
+    pub accent: Color,
+    // Tags and the debug overlay.
+    pub secondary: Color,
+    // The header and slide text.
+    pub title: Color,
+    // The highlighted row of a list.
+    pub selection: Color,
+    pub selected_text: Color,
+    // Also signal every state with a symbol, weight or border shape, never by color alone.
+    pub markers: bool,
+}
+
+// Okabe–Ito colors, chosen to stay distinct under red-green color blindness.
+const SKY_BLUE: Color = Color::Rgb(86, 180, 233);
+const BLUE: Color = Color::Rgb(0, 114, 178);
+const ORANGE: Color = Color::Rgb(230, 159, 0);
+const YELLOW: Color = Color::Rgb(240, 228, 66);
+const VERMILLION: Color = Color::Rgb(213, 94, 0);
+const REDDISH_PURPLE: Color = Color::Rgb(204, 121, 167);
+
+// Readable on white, unlike the terminal's own yellow.
+const DARK_YELLOW: Color = Color::Rgb(175, 95, 0);
+
+// Gruvbox's dark palette.
+const GRUVBOX_BG: Color = Color::Rgb(40, 40, 40);
+const GRUVBOX_FG: Color = Color::Rgb(235, 219, 178);
+const GRUVBOX_RED: Color = Color::Rgb(251, 73, 52);
+const GRUVBOX_GREEN: Color = Color::Rgb(184, 187, 38);
+const GRUVBOX_YELLOW: Color = Color::Rgb(250, 189, 47);
+const GRUVBOX_BLUE: Color = Color::Rgb(131, 165, 152);
+const GRUVBOX_PURPLE: Color = Color::Rgb(211, 134, 155);
+const GRUVBOX_AQUA: Color = Color::Rgb(142, 192, 124);
+const GRUVBOX_ORANGE: Color = Color::Rgb(254, 128, 25);
+const GRUVBOX_GRAY: Color = Color::Rgb(146, 131, 116);
+
+pub const THEMES: [Theme; 6] = [
+    Theme {
+        name: "default",
+        text: Color::LightBlue,
+        active: Color::Cyan,
+        ok: Color::Green,
+        pending: Color::Yellow,
+        error: Color::Red,
+        uncommon: Color::Yellow,
+        rare: Color::Magenta,
+        muted: Color::DarkGray,
+        border: Color::Cyan,
+        accent: Color::Yellow,
+        secondary: Color::Magenta,
+        title: Color::White,
+        selection: Color::Yellow,
+        selected_text: Color::Black,
+        markers: false,
+    },
+    // For terminals with a light background, where the default's light blue and yellow text
+    // wash out.
+    Theme {
+        name: "light",
+        text: Color::Black,
+        active: Color::Blue,
+        ok: Color::Green,
+        pending: DARK_YELLOW,
+        error: Color::Red,
+        uncommon: DARK_YELLOW,
+        rare: Color::Magenta,
+        muted: Color::DarkGray,
+        border: Color::Blue,
+        accent: DARK_YELLOW,
+        secondary: Color::Magenta,
+        title: Color::Black,
+        selection: Color::Blue,
+        selected_text: Color::White,
+        markers: false,
+    },
+    // Deuteranopes confuse reds and greens of similar lightness: states are blue, yellow and
+    // vermillion instead.
+    Theme {
+        name: "deuteranopia",
+        text: Color::White,
+        active: SKY_BLUE,
+        ok: BLUE,
+        pending: YELLOW,
+        error: VERMILLION,
+        uncommon: ORANGE,
+        rare: REDDISH_PURPLE,
+        muted: Color::Gray,
+        border: SKY_BLUE,
+        accent: YELLOW,
+        secondary: REDDISH_PURPLE,
+        title: Color::White,
+        selection: SKY_BLUE,
+        selected_text: Color::Black,
+        markers: true,
+    },
+    // Protanopes also see red as dark, so errors use the brighter orange and the word
+    // difficulty bands purple and dark blue.
+    Theme {
+        name: "protanopia",
+        text: Color::White,
+        active: YELLOW,
+        ok: SKY_BLUE,
+        pending: YELLOW,
+        error: ORANGE,
+        uncommon: REDDISH_PURPLE,
+        rare: BLUE,
+        muted: Color::Gray,
+        border: SKY_BLUE,
+        accent: YELLOW,
+        secondary: SKY_BLUE,
+        title: Color::White,
+        selection: YELLOW,
+        selected_text: Color::Black,
+        markers: true,
+    },
+    Theme {
+        name: "high-contrast",
+        text: Color::White,
+        active: Color::LightYellow,
+        ok: Color::LightGreen,
+        pending: Color::LightYellow,
+        error: Color::LightRed,
+        uncommon: Color::LightYellow,
+        rare: Color::LightMagenta,
+        muted: Color::Gray,
+        border: Color::White,
+        accent: Color::LightYellow,
+        secondary: Color::LightMagenta,
+        title: Color::White,
+        selection: Color::White,
+        selected_text: Color::Black,
+        markers: true,
+    },
+    Theme {
+        name: "gruvbox",
+        text: GRUVBOX_FG,
+        active: GRUVBOX_AQUA,
+        ok: GRUVBOX_GREEN,
+        pending: GRUVBOX_YELLOW,
+        error: GRUVBOX_RED,
+        uncommon: GRUVBOX_ORANGE,
+        rare: GRUVBOX_PURPLE,
+        muted: GRUVBOX_GRAY,
+        border: GRUVBOX_BLUE,
+        accent: GRUVBOX_YELLOW,
+        secondary: GRUVBOX_PURPLE,
+        title: GRUVBOX_FG,
+        selection: GRUVBOX_YELLOW,
+        selected_text: GRUVBOX_BG,
+        markers: false,
+    },
+];
+
+/// The `:theme` popup; moving through the list previews each theme on the panes.
+pub struct ThemePicker {
+    pub selected: usize,
+    // Put back when the picker is cancelled.
+    pub previous: Theme,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        THEMES[0]
+    }
+}
+
+impl Theme {
+    pub fn find(name: &str) -> Option<Self> {
+        THEMES
+            .into_iter()
+            .find(|theme| theme.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// A built-in theme by name, as set by `theme` in the config or `PTRUI_THEME`.
+    pub fn named(name: &str) -> Result<Self, String> {
+        Self::find(name).ok_or_else(|| {
+            let names: Vec<&str> = THEMES.iter().map(|theme| theme.name).collect();
+            format!(
+                "Unknown theme {:?}; expected one of {}",
+                name,
+                names.join(", ")
+            )
+        })
+    }
+
+    /// This theme with colors replaced from the config's `[colors]` table, keyed by field
+    /// name (`text = "black"`, `border = "#458588"`).
+    pub fn with_colors(mut self, colors: &BTreeMap<String, String>) -> Result<Self, String> {
+        for (name, value) in colors {
+            let color = Color::from_str(value.trim())
+                .map_err(|_| format!("Invalid color {:?} for {}", value, name))?;
+            let slot = match name.as_str() {
+                "text" => &mut self.text,
+                "active" => &mut self.active,
+                "ok" => &mut self.ok,
+                "pending" => &mut self.pending,
+                "error" => &mut self.error,
+                "uncommon" => &mut self.uncommon,
+                "rare" => &mut self.rare,
+                "muted" => &mut self.muted,
+                "border" => &mut self.border,
+                "accent" => &mut self.accent,
+                "secondary" => &mut self.secondary,
+                "title" => &mut self.title,
+                "selection" => &mut self.selection,
+                "selected_text" => &mut self.selected_text,
+                _ => return Err(format!("Unknown color {:?} in [colors]", name)),
+            };
+            *slot = color;
+        }
+        Ok(self)
+    }
+
+    /// The highlighted row of a picker or list.
+    pub fn selection_style(&self) -> Style {
+        Style::default()
+            .fg(self.selected_text)
+            .bg(self.selection)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Both panes draw their text in bold.
+    pub fn text_style(&self) -> Style {
+        Style::default().fg(self.text).add_modifier(Modifier::BOLD)
+    }
+
+    /// A status color, in bold when the theme doesn't rely on color alone.
+    pub fn state(&self, color: Color) -> Style {
+        let style = Style::default().fg(color);
+        if self.markers {
+            style.add_modifier(Modifier::BOLD)
+        } else {
+            style
+        }
+    }
+
+    /// `symbol` followed by a space for themes with markers, otherwise nothing.
+    pub fn marker(&self, symbol: &'static str) -> String {
+        if self.markers {
+            format!("{} ", symbol)
+        } else {
+            String::new()
+        }
+    }
+
+    /// Pane borders: the active pane is drawn thick and a pane in trouble double, so their
+    /// state survives a monochrome screen.
+    pub fn border_type(&self, active: bool, warning: bool) -> BorderType {
+        match (self.markers, active, warning) {
+            (false, ..) => BorderType::Plain,
+            (true, true, _) => BorderType::Thick,
+            (true, false, true) => BorderType::Double,
+            (true, false, false) => BorderType::Plain,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_themes_by_name() {
+        assert_eq!(Theme::find(" High-Contrast"), Some(THEMES[4]));
+        assert_eq!(Theme::find("solarized"), None);
+        assert!(
+            ["deuteranopia", "protanopia", "high-contrast"]
+                .into_iter()
+                .all(|name| Theme::find(name).is_some_and(|theme| theme.markers))
+        );
+        assert_eq!(THEMES[4].marker("✗"), "✗ ");
+        assert_eq!(Theme::default().marker("✗"), "");
+    }
+
+    #[test]
+    fn color_blind_themes_give_each_state_its_own_color() {
+        for name in ["deuteranopia", "protanopia"] {
+            let theme = Theme::find(name).unwrap();
+            let states = [
+                theme.ok,
+                theme.pending,
+                theme.error,
+                theme.uncommon,
+                theme.rare,
+            ];
+            for (index, color) in states.iter().enumerate() {
+                assert!(!states[index + 1..].contains(color), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn overrides_colors_from_the_config() {
+        let colors = BTreeMap::from([
+            ("text".to_string(), "black".to_string()),
+            ("border".to_string(), "#458588".to_string()),
+        ]);
+        let theme = Theme::default().with_colors(&colors).unwrap();
+        assert_eq!(theme.text, Color::Black);
+        assert_eq!(theme.border, Color::Rgb(69, 133, 136));
+        assert_eq!(theme.active, Color::Cyan);
+        let unknown = BTreeMap::from([("shadow".to_string(), "red".to_string())]);
+        assert!(Theme::default().with_colors(&unknown).is_err());
+        let invalid = BTreeMap::from([("text".to_string(), "reddish".to_string())]);
+        assert!(Theme::default().with_colors(&invalid).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::api::LOOKUP_DEADLINE;
+
+const DATAMUSE_URL: &str = "https://api.datamuse.com/words";
+const MAX_SUGGESTIONS: usize = 12;
+
+#[derive(Debug, Deserialize)]
+struct DatamuseWord {
+    word: String,
+}
+
+/// Alternative phrasings offered for a visual selection.
+pub struct SuggestionPicker {
+    pub original: String,
+    pub suggestions: Vec<String>,
+    pub selected: usize,
+}
+
+/// Asks the Datamuse thesaurus for words and phrases with a similar meaning.
+pub fn suggest_synonyms(
+    client: &reqwest::blocking::Client,
+    text: &str,
+    language_code: &str,
+) -> Result<Vec<String>, String> {
+    // Datamuse ships an English vocabulary and a Spanish one.
+    let vocabulary = match language_code {
+        "EN" => None,
+        "ES" => Some("es"),
+        _ => return Err("Synonym suggestions support English and Spanish only".to_string()),
+    };
+    let max = MAX_SUGGESTIONS.to_string();
+    let mut params = vec![("ml", text), ("max", max.as_str())];
+    if let Some(vocabulary) = vocabulary {
+        params.push(("v", vocabulary));
+    }
+    let url = Url::parse_with_params(DATAMUSE_URL, &params).map_err(|err| err.to_string())?;
+    let response = client
+        .get(url)
+        .timeout(LOOKUP_DEADLINE)
+        .send()
+        .map_err(|err| format!("Failed to call thesaurus: {}", err))?;
+    if !response.status().is_success() {
+        return Err(format!("Thesaurus error ({})", response.status()));
+    }
+    let words: Vec<DatamuseWord> = response
+        .json()
+        .map_err(|err| format!("Invalid thesaurus response: {}", err))?;
+    Ok(words
+        .into_iter()
+        .map(|word| word.word)
+        .filter(|word| !word.eq_ignore_ascii_case(text))
+        .collect())
+}
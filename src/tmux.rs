@@ -0,0 +1,18 @@
+use std::process::Command;
+
+/// Copies text into tmux's paste buffer so `prefix + ]` pastes it in any pane.
+pub fn set_buffer(text: &str) -> Result<(), String> {
+    if text.is_empty() {
+        return Err("Nothing to copy".to_string());
+    }
+    let output = Command::new("tmux")
+        .args(["set-buffer", "--", text])
+        .output()
+        .map_err(|err| format!("Failed to run tmux: {}", err))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("tmux set-buffer failed: {}", stderr.trim()))
+    }
+}
@@ -0,0 +1,60 @@
+/// Register presets that steer how a translation should sound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tone {
+    #[default]
+    Neutral,
+    Formal,
+    Casual,
+    Marketing,
+    Technical,
+    Eli5,
+}
+
+impl Tone {
+    pub const ALL: [Tone; 6] = [
+        Tone::Neutral,
+        Tone::Formal,
+        Tone::Casual,
+        Tone::Marketing,
+        Tone::Technical,
+        Tone::Eli5,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|tone| *tone == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Neutral => "neutral",
+            Self::Formal => "formal",
+            Self::Casual => "casual",
+            Self::Marketing => "marketing",
+            Self::Technical => "technical",
+            Self::Eli5 => "ELI5",
+        }
+    }
+
+    /// Instruction added to a chat model's prompt for this preset.
+    pub fn instruction(self) -> Option<&'static str> {
+        match self {
+            Self::Neutral => None,
+            Self::Formal => {
+                Some("Use a formal, polite register suitable for business correspondence.")
+            }
+            Self::Casual => Some("Use a casual, friendly register like a message between friends."),
+            Self::Marketing => Some("Make it persuasive and upbeat, like marketing copy."),
+            Self::Technical => {
+                Some("Use precise technical terminology and keep the wording concise.")
+            }
+            Self::Eli5 => Some("Use very simple words, as if explaining to a five-year-old."),
+        }
+    }
+}
+
+impl std::fmt::Display for Tone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
@@ -0,0 +1,146 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Child, Command, Stdio};
+
+/// Commands for speech input: one records the microphone, the other transcribes a file.
+pub struct TranscribeSettings {
+    // Recorder command line with an `{audio}` placeholder for the output file.
+    pub record_command: String,
+    // Transcriber command line with `{audio}` and optional `{lang}` placeholders; prints text.
+    pub whisper_command: String,
+}
+
+impl TranscribeSettings {
+    pub fn from_env() -> Self {
+        let record_command = env::var("PTRUI_RECORD_COMMAND")
+            .unwrap_or_else(|_| default_record_command().to_string());
+        let whisper_command = env::var("PTRUI_WHISPER_COMMAND")
+            .unwrap_or_else(|_| "whisper-cli -nt -np -l {lang} -f {audio}".to_string());
+        Self {
+            record_command,
+            whisper_command,
+        }
+    }
+}
+
+fn default_record_command() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "rec -q -r 16000 -c 1 -b 16 {audio}"
+    } else {
+        "arecord -q -f S16_LE -r 16000 -c 1 {audio}"
+    }
+}
+
+/// A microphone recording in progress.
+pub struct Recording {
+    child: Child,
+    pub path: PathBuf,
+}
+
+impl Recording {
+    /// Starts recording 16 kHz mono audio (what whisper expects) into a temporary file.
+    pub fn start(settings: &TranscribeSettings) -> Result<Self, String> {
+        let path = env::temp_dir().join(format!("ptrui-recording-{}.wav", process::id()));
+        let args = fill_placeholders(&settings.record_command, &path, "");
+        let Some((program, rest)) = args.split_first() else {
+            return Err("PTRUI_RECORD_COMMAND is empty".to_string());
+        };
+        let child = Command::new(program)
+            .args(rest)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| format!("Failed to start {}: {}", program, err))?;
+        Ok(Self { child, path })
+    }
+
+    /// Stops the recorder and returns the audio file. Recorders finalize the WAV header on
+    /// SIGINT, so that is tried before killing the process.
+    pub fn stop(mut self) -> Result<PathBuf, String> {
+        let interrupted = cfg!(unix)
+            && Command::new("kill")
+                .args(["-INT", &self.child.id().to_string()])
+                .status()
+                .is_ok_and(|status| status.success());
+        if !interrupted {
+            let _ = self.child.kill();
+        }
+        let _ = self.child.wait();
+        if !self.path.is_file() {
+            return Err("The recorder did not write any audio".to_string());
+        }
+        Ok(self.path.clone())
+    }
+}
+
+/// Runs the whisper command on an audio file and returns the transcript.
+pub fn transcribe(
+    settings: &TranscribeSettings,
+    audio: &Path,
+    language_code: &str,
+) -> Result<String, String> {
+    if !audio.is_file() {
+        return Err(format!("No audio file at {}", audio.display()));
+    }
+    let args = fill_placeholders(
+        &settings.whisper_command,
+        audio,
+        &language_code.to_ascii_lowercase(),
+    );
+    let Some((program, rest)) = args.split_first() else {
+        return Err("PTRUI_WHISPER_COMMAND is empty".to_string());
+    };
+    let output = Command::new(program)
+        .args(rest)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| format!("Failed to start {}: {}", program, err))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", program, stderr.trim()));
+    }
+    let transcript = clean_transcript(&String::from_utf8_lossy(&output.stdout));
+    if transcript.is_empty() {
+        return Err("No speech recognized".to_string());
+    }
+    Ok(transcript)
+}
+
+/// Removes a temporary recording once it has been transcribed.
+pub fn discard_recording(path: &Path) {
+    let _ = fs::remove_file(path);
+}
+
+fn fill_placeholders(command: &str, audio: &Path, language: &str) -> Vec<String> {
+    let audio = audio.to_string_lossy();
+    command
+        .split_whitespace()
+        .map(|token| token.replace("{audio}", &audio).replace("{lang}", language))
+        .collect()
+}
+
+/// Joins transcript lines and drops whisper's non-speech markers like `[BLANK_AUDIO]`.
+fn clean_transcript(output: &str) -> String {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter(|line| !(line.starts_with('[') && line.ends_with(']')))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleans_whisper_output() {
+        assert_eq!(
+            clean_transcript("\n [BLANK_AUDIO]\n Hola, ¿dónde está\n la estación?\n"),
+            "Hola, ¿dónde está la estación?"
+        );
+    }
+}
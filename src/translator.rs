@@ -0,0 +1,353 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+
+use crate::api::TranslateOptions;
+use crate::chunk::translate_chunked;
+use crate::provider::TranslationProvider;
+
+/// Text for the worker to translate, tagged with the source version it was taken from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationJob {
+    pub version: u64,
+    pub text: String,
+    pub source_lang: &'static str,
+    pub target_lang: &'static str,
+    pub options: TranslateOptions<'static>,
+}
+
+/// A finished job, handed back to the UI thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationDone {
+    pub version: u64,
+    pub result: Result<String, String>,
+}
+
+/// How far the worker has got through a long text sent in chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkProgress {
+    pub version: u64,
+    // Zero-based index of the chunk being translated.
+    pub chunk: usize,
+    pub total: usize,
+}
+
+/// Reports the chunk about to be translated; an error stops the job.
+pub type ProgressFn<'a> = &'a mut dyn FnMut(usize, usize) -> Result<(), String>;
+
+/// Runs translations on a worker thread so the UI keeps drawing and taking keys while a
+/// request is in flight. Only the newest generation is ever answered: queued jobs are
+/// coalesced, and older ones are skipped or their answers dropped.
+pub struct Translator {
+    jobs: Sender<TranslationJob>,
+    done: Receiver<TranslationDone>,
+    progress: Receiver<ChunkProgress>,
+    // Jobs below this source version are cancelled.
+    generation: Arc<AtomicU64>,
+}
+
+impl Translator {
+    pub fn spawn<F>(mut translate: F) -> Self
+    where
+        F: FnMut(&TranslationJob, ProgressFn) -> Result<String, String> + Send + 'static,
+    {
+        let (jobs, job_queue) = mpsc::channel::<TranslationJob>();
+        let (finished, done) = mpsc::channel();
+        let (progressed, progress) = mpsc::channel();
+        let generation = Arc::new(AtomicU64::new(0));
+        let current = Arc::clone(&generation);
+        thread::spawn(move || {
+            let cancelled = |job: &TranslationJob| job.version < current.load(Ordering::SeqCst);
+            while let Ok(mut job) = job_queue.recv() {
+                // Text typed while the last request was out only needs its newest version.
+                while let Ok(newer) = job_queue.try_recv() {
+                    job = newer;
+                }
+                if cancelled(&job) {
+                    continue;
+                }
+                // Chunks left over once a job is cancelled aren't worth sending.
+                let mut report = |chunk, total| {
+                    if cancelled(&job) {
+                        return Err("Translation cancelled".to_string());
+                    }
+                    let _ = progressed.send(ChunkProgress {
+                        version: job.version,
+                        chunk,
+                        total,
+                    });
+                    Ok(())
+                };
+                let result = translate(&job, &mut report);
+                // A blocking request can't be aborted, but its stale answer is never shown.
+                if cancelled(&job) {
+                    continue;
+                }
+                let done = TranslationDone {
+                    version: job.version,
+                    result,
+                };
+                if finished.send(done).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            jobs,
+            done,
+            progress,
+            generation,
+        }
+    }
+
+    /// A worker calling the configured provider, splitting texts longer than `chunk_chars`
+    /// into requests of that size.
+    pub fn for_provider(provider: Arc<dyn TranslationProvider>, chunk_chars: usize) -> Self {
+        Self::spawn(move |job, progress| {
+            translate_chunked(
+                &job.text,
+                chunk_chars,
+                |text| provider.translate(text, job.source_lang, job.target_lang, &job.options),
+                progress,
+            )
+        })
+    }
+
+    /// Cancels every job older than `generation`, queued or in flight.
+    pub fn cancel_before(&self, generation: u64) {
+        self.generation.fetch_max(generation, Ordering::SeqCst);
+    }
+
+    /// Queues a job, cancelling all older ones.
+    pub fn send(&self, job: TranslationJob) -> Result<(), String> {
+        self.cancel_before(job.version);
+        self.jobs
+            .send(job)
+            .map_err(|_| "The translation worker stopped".to_string())
+    }
+
+    /// The latest chunk progress reported, without waiting.
+    pub fn try_progress(&self) -> Option<ChunkProgress> {
+        self.progress.try_iter().last()
+    }
+
+    /// The next finished job, without waiting.
+    pub fn try_recv(&self) -> Option<Result<TranslationDone, String>> {
+        match self.done.try_recv() {
+            Ok(done) => Some(Ok(done)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(Err("The translation worker stopped".to_string()))
+            }
+        }
+    }
+}
+
+/// A translation the UI asked for besides the live one, e.g. a back-translation, tagged with
+/// what its answer is for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookupJob<T> {
+    pub tag: T,
+    pub text: String,
+    pub source_lang: &'static str,
+    pub target_lang: &'static str,
+    pub options: TranslateOptions<'static>,
+}
+
+/// A finished lookup with the tag it was sent with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookupDone<T> {
+    pub tag: T,
+    pub result: Result<String, String>,
+}
+
+/// Runs lookups on a second worker thread, so they neither block the UI nor cancel the live
+/// translation. Unlike `Translator`, every job is answered, in the order it was sent.
+pub struct Lookups<T> {
+    jobs: Sender<LookupJob<T>>,
+    done: Receiver<LookupDone<T>>,
+}
+
+impl<T: Send + 'static> Lookups<T> {
+    pub fn spawn<F>(mut translate: F) -> Self
+    where
+        F: FnMut(&LookupJob<T>) -> Result<String, String> + Send + 'static,
+    {
+        let (jobs, job_queue) = mpsc::channel::<LookupJob<T>>();
+        let (finished, done) = mpsc::channel();
+        thread::spawn(move || {
+            while let Ok(job) = job_queue.recv() {
+                let result = translate(&job);
+                let done = LookupDone {
+                    tag: job.tag,
+                    result,
+                };
+                if finished.send(done).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { jobs, done }
+    }
+
+    /// A worker calling the configured provider, in chunks like `Translator::for_provider`.
+    pub fn for_provider(provider: Arc<dyn TranslationProvider>, chunk_chars: usize) -> Self {
+        Self::spawn(move |job| {
+            translate_chunked(
+                &job.text,
+                chunk_chars,
+                |text| provider.translate(text, job.source_lang, job.target_lang, &job.options),
+                |_, _| Ok(()),
+            )
+        })
+    }
+
+    pub fn send(&self, job: LookupJob<T>) -> Result<(), String> {
+        self.jobs
+            .send(job)
+            .map_err(|_| "The lookup worker stopped".to_string())
+    }
+
+    /// The next finished lookup, without waiting.
+    pub fn try_recv(&self) -> Option<Result<LookupDone<T>, String>> {
+        match self.done.try_recv() {
+            Ok(done) => Some(Ok(done)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("The lookup worker stopped".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    fn job(version: u64, text: &str) -> TranslationJob {
+        TranslationJob {
+            version,
+            text: text.to_string(),
+            source_lang: "ES",
+            target_lang: "EN",
+            options: TranslateOptions::default(),
+        }
+    }
+
+    fn next_done(translator: &Translator) -> TranslationDone {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            match translator.try_recv() {
+                Some(done) => return done.unwrap(),
+                None if Instant::now() < deadline => thread::sleep(Duration::from_millis(5)),
+                None => panic!("no translation finished"),
+            }
+        }
+    }
+
+    #[test]
+    fn answers_only_the_newest_job() {
+        let (release, gate) = mpsc::channel::<()>();
+        let gate = Mutex::new(gate);
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&calls);
+        let translator = Translator::spawn(move |job, _| {
+            seen.lock().unwrap().push(job.version);
+            // Hold the first request in flight until the test has queued more.
+            if job.version == 1 {
+                gate.lock().unwrap().recv().unwrap();
+            }
+            Ok(job.text.to_uppercase())
+        });
+        translator.send(job(1, "h")).unwrap();
+        while calls.lock().unwrap().is_empty() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        translator.send(job(2, "ho")).unwrap();
+        translator.send(job(3, "hola")).unwrap();
+        release.send(()).unwrap();
+
+        assert_eq!(
+            next_done(&translator),
+            TranslationDone {
+                version: 3,
+                result: Ok("HOLA".to_string()),
+            }
+        );
+        assert_eq!(*calls.lock().unwrap(), [1, 3]);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(translator.try_recv(), None);
+    }
+
+    #[test]
+    fn cancel_before_drops_an_answer_in_flight() {
+        let (release, gate) = mpsc::channel::<()>();
+        let gate = Mutex::new(gate);
+        let (started, running) = mpsc::channel::<u64>();
+        let translator = Translator::spawn(move |job, _| {
+            started.send(job.version).unwrap();
+            if job.version == 1 {
+                gate.lock().unwrap().recv().unwrap();
+            }
+            Ok(job.text.to_uppercase())
+        });
+        translator.send(job(1, "hola")).unwrap();
+        assert_eq!(running.recv().unwrap(), 1);
+        translator.cancel_before(2);
+        release.send(()).unwrap();
+
+        // The worker finished the cancelled request; only the next job's answer comes back.
+        translator.send(job(2, "adiós")).unwrap();
+        assert_eq!(running.recv().unwrap(), 2);
+        assert_eq!(
+            next_done(&translator),
+            TranslationDone {
+                version: 2,
+                result: Ok("ADIÓS".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn cancel_before_stops_the_remaining_chunks() {
+        let (release, gate) = mpsc::channel::<()>();
+        let gate = Mutex::new(gate);
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&calls);
+        let translator = Translator::spawn(move |job, progress| {
+            translate_chunked(
+                &job.text,
+                4,
+                |text| {
+                    seen.lock().unwrap().push(text.to_string());
+                    // Hold the first chunk in flight until the test has cancelled the job.
+                    if job.version == 1 {
+                        gate.lock().unwrap().recv().unwrap();
+                    }
+                    Ok(text.to_uppercase())
+                },
+                progress,
+            )
+        });
+        translator.send(job(1, "uno\ndos\ntres")).unwrap();
+        while calls.lock().unwrap().is_empty() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        translator.cancel_before(2);
+        release.send(()).unwrap();
+
+        translator.send(job(2, "hola")).unwrap();
+        assert_eq!(next_done(&translator).version, 2);
+        assert_eq!(*calls.lock().unwrap(), ["uno", "hola"]);
+        let progress = translator.progress.try_iter().collect::<Vec<_>>();
+        assert_eq!(
+            progress,
+            [ChunkProgress {
+                version: 1,
+                chunk: 0,
+                total: 3,
+            }]
+        );
+    }
+}
@@ -1,108 +1,739 @@
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use std::time::Instant;
+
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::widgets::{
+    Bar, BarChart, BarGroup, Block, Borders, Clear, List, ListItem, ListState, Padding, Paragraph,
+    Sparkline, Wrap,
+};
+
+use tui_textarea::TextArea;
 
-use crate::app::{ActiveSide, App};
-use crate::languages::{LANGUAGES, filtered_language_indices};
+use crate::alternatives::AlternativePicker;
+use crate::app::{ActiveSide, App, SPINNER_INTERVAL, TextCounts};
+use crate::dates::format_timestamp;
+use crate::diff::{Change, DiffView};
+use crate::formality::{Formality, supports_formality};
+use crate::frequency::{FrequencyBand, FrequencyList, word_runs};
+use crate::gloss::GlossEntry;
+use crate::languages::{filtered_language_indices, language_name, languages};
+use crate::lock::PrivacyLock;
+use crate::presentation::Presentation;
+use crate::quality::QualityBand;
+use crate::review::Review;
+use crate::segment::align_sentences;
+use crate::textarea::scroll_offset;
+use crate::theme::{THEMES, Theme};
+use crate::usage::UsageSummary;
+use crate::wordofday::WordOfTheDay;
+
+// Rows of each extra target pane: its border and two lines of translation.
+const EXTRA_TARGET_HEIGHT: u16 = 4;
 
 pub fn draw_ui(frame: &mut ratatui::Frame, app: &App) {
-    // The screen is vertically split into a header, app, and controls.
+    // Nothing of the panes is drawn while locked, not even behind a popup.
+    if let Some(lock) = app.lock.as_ref().filter(|lock| lock.is_locked()) {
+        draw_lock_screen(frame, lock, &app.theme);
+        return;
+    }
+    if let Some(presentation) = &app.presentation {
+        draw_presentation(frame, app, presentation);
+        return;
+    }
+    if app.popup {
+        draw_popup_layout(frame, app);
+    } else {
+        draw_full_layout(frame, app);
+    }
+
+    if app.picker.is_some() {
+        draw_language_picker(frame, app);
+    }
+    if app.history_finder.is_some() {
+        draw_history_finder(frame, app);
+    }
+    if app.gloss.is_some() {
+        draw_gloss(frame, app);
+    }
+    if app.dictionary.is_some() {
+        draw_dictionary(frame, app);
+    }
+    if app.suggestions.is_some() {
+        draw_suggestions(frame, app);
+    }
+    if app.show_stats {
+        draw_stats(frame, app);
+    }
+    if let Some(review) = &app.review {
+        draw_review(frame, review, &app.theme);
+    }
+    if let Some(usage) = &app.usage {
+        draw_usage(frame, usage, &app.theme);
+    }
+    if let Some(lines) = &app.qr_code {
+        draw_qr_code(frame, lines, &app.theme);
+    }
+    if let Some(scroll) = app.registers_view {
+        draw_registers(frame, app, scroll);
+    }
+    if let Some(details) = &app.error_details {
+        draw_error_details(frame, &details.message, details.scroll, &app.theme);
+    }
+    if app.show_debug {
+        draw_debug(frame, app);
+    }
+    if let Some(line) = &app.command_line {
+        draw_command_line(frame, ":", line, &app.theme);
+    }
+    if let Some(query) = app.search_query() {
+        draw_command_line(frame, "/", query, &app.theme);
+    }
+    if app.export_prompt.is_some() {
+        draw_export_prompt(frame, app);
+    }
+    if app.session_picker.is_some() {
+        draw_session_picker(frame, app);
+    }
+    if app.theme_picker.is_some() {
+        draw_theme_picker(frame, app);
+    }
+    if let Some(word) = &app.word_of_the_day {
+        draw_word_of_the_day(frame, word, &app.theme);
+    }
+    if app.draft_prompt.is_some() {
+        draw_draft_prompt(frame, app);
+    }
+}
+
+fn draw_full_layout(frame: &mut ratatui::Frame, app: &App) {
+    // The screen is vertically split into a header, app, optional romanization, controls and
+    // the status bar.
+    let romanized = app.reading_aid_text();
+    let romanization_height = if romanized.is_some() { 5 } else { 0 };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(2)
         .constraints([
             Constraint::Length(3),
-            Constraint::Length(7),
+            // Stacked panes get the height two side-by-side ones would; extra targets add
+            // their own rows.
+            Constraint::Length(
+                if app.layout.stacked { 14 } else { 7 }
+                    + EXTRA_TARGET_HEIGHT * app.extra_targets.len() as u16,
+            ),
+            Constraint::Length(romanization_height),
             Constraint::Min(5),
+            Constraint::Length(1),
         ])
         .split(frame.area());
 
-    draw_header(frame, chunks[0]);
+    draw_header(frame, chunks[0], &app.theme);
     draw_translator(frame, chunks[1], app);
-    draw_help(frame, chunks[2], app);
+    if let Some(text) = romanized {
+        let (_, target_lang) = app.active_pair();
+        let title = app.reading_aid.title(target_lang);
+        draw_romanization(frame, chunks[2], title, text, &app.theme);
+    }
+    draw_help(frame, chunks[3]);
+    frame.render_widget(Paragraph::new(Line::from(status_bar_spans(app))), chunks[4]);
+    // Candidates open right under the pane they would replace.
+    if let Some(picker) = &app.alternatives {
+        draw_alternatives(frame, target_column(chunks[3], app), picker, &app.theme);
+    }
+}
 
-    if app.picker.is_some() {
-        draw_language_picker(frame, app);
+fn draw_popup_layout(frame: &mut ratatui::Frame, app: &App) {
+    // tmux popups are small: the panes take every row but one for the status line.
+    let romanized = app.reading_aid_text();
+    let romanization_height = if romanized.is_some() { 3 } else { 0 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(romanization_height),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    draw_translator(frame, chunks[0], app);
+    if let Some(text) = romanized {
+        let (_, target_lang) = app.active_pair();
+        let title = app.reading_aid.title(target_lang);
+        draw_romanization(frame, chunks[1], title, text, &app.theme);
     }
+    // No room below the panes here, so the candidates cover the target pane.
+    if let Some(picker) = &app.alternatives {
+        draw_alternatives(frame, target_column(chunks[0], app), picker, &app.theme);
+    }
+    let mut spans = status_spans(app);
+    spans.push(Span::styled(
+        "  |  Alt+b copy to tmux & close  Ctrl+c quit",
+        Style::default().fg(app.theme.muted),
+    ));
+    frame.render_widget(Paragraph::new(Line::from(spans)), chunks[2]);
 }
 
-fn draw_header(frame: &mut ratatui::Frame, area: Rect) {
+fn draw_presentation(frame: &mut ratatui::Frame, app: &App, presentation: &Presentation) {
+    let (left, right) = match presentation.current() {
+        Some(entry) => (
+            (
+                language_name(&entry.source_lang).to_string(),
+                entry.source_text.clone(),
+            ),
+            (
+                language_name(&entry.target_lang).to_string(),
+                entry.target_text.clone(),
+            ),
+        ),
+        None => {
+            let left = languages()
+                .get(app.left_language)
+                .unwrap_or(&languages()[0]);
+            let right = languages()
+                .get(app.right_language)
+                .unwrap_or(&languages()[0]);
+            (
+                (left.name.to_string(), app.input.lines().join("\n")),
+                (right.name.to_string(), app.output.lines().join("\n")),
+            )
+        }
+    };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(frame.area());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[0]);
+    for ((title, text), area) in [left, right].into_iter().zip(columns.iter()) {
+        draw_slide(frame, *area, &title, &text, &app.theme);
+    }
+
+    let position = match presentation.entries.len() {
+        0 => "live panes".to_string(),
+        count => format!("{}/{}", presentation.index + 1, count),
+    };
+    let advance = match presentation.advance_every {
+        Some(_) if presentation.paused => "  |  paused",
+        Some(_) => "  |  auto-advancing",
+        None => "",
+    };
+    let footer = Line::from(vec![
+        Span::styled(position, Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(advance),
+        Span::styled(
+            "  |  ←/→ step  Space pause  Esc exit",
+            Style::default().fg(app.theme.muted),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(footer).centered(), rows[1]);
+}
+
+/// One side of a slide: bold, centered text with padding so it reads from the back row.
+fn draw_slide(frame: &mut ratatui::Frame, area: Rect, title: &str, text: &str, theme: &Theme) {
+    let inner_width = area.width.saturating_sub(8).max(1) as usize;
+    let wrapped_lines: usize = text
+        .lines()
+        .map(|line| line.chars().count().div_ceil(inner_width).max(1))
+        .sum();
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let top = inner_height.saturating_sub(wrapped_lines) / 2;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            format!(" {} ", title),
+            Style::default()
+                .fg(theme.border)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_alignment(Alignment::Center)
+        .padding(Padding::new(3, 3, top as u16, 0))
+        .border_style(Style::default().fg(theme.muted));
+    let paragraph = Paragraph::new(text.to_string())
+        .block(block)
+        .style(
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_header(frame: &mut ratatui::Frame, area: Rect, theme: &Theme) {
     // Header shows app name and a small hint.
     let title = Line::from(vec![
         Span::styled("ptrui", Style::default().add_modifier(Modifier::BOLD)),
         Span::raw("  |  "),
-        Span::styled("tab to switch", Style::default().fg(Color::Green)),
+        Span::styled("tab to switch", Style::default().fg(theme.ok)),
     ]);
 
     let block = Block::default()
         .borders(Borders::ALL)
         .title(title)
-        .border_style(Style::default().fg(Color::White));
+        .border_style(Style::default().fg(theme.title));
 
     frame.render_widget(block, area);
 }
 
-fn draw_translator(frame: &mut ratatui::Frame, area: Rect, app: &App) {
-    // Two equal columns: English (left) and Spanish (right).
-    let columns = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(area);
+// Longer errors are cut short in the status line; Alt+e shows all of it.
+const ERROR_SUMMARY_CHARS: usize = 80;
+const SPINNER_FRAMES: [&str; 8] = ["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
 
-    let left_language = LANGUAGES.get(app.left_language).unwrap_or(&LANGUAGES[0]);
-    let right_language = LANGUAGES.get(app.right_language).unwrap_or(&LANGUAGES[0]);
+/// Applies titles, borders and cursor styles to the stored textareas before a draw, so rendering
+/// can borrow them instead of cloning whole documents every frame.
+pub fn style_panes(app: &mut App) {
+    let left_language = languages()
+        .get(app.left_language)
+        .unwrap_or(&languages()[0]);
+    let right_language = languages()
+        .get(app.right_language)
+        .unwrap_or(&languages()[0]);
+    let mut target_suffix = match app.length_limit {
+        Some(limit) => format!(" [{}/{}]", app.target_len(), limit),
+        None => String::new(),
+    };
+    if app.frequency.is_some() {
+        target_suffix.push_str(" [frequency]");
+    }
+    if app.quality.is_some() {
+        target_suffix.push_str(" [quality]");
+    }
+    let theme = app.theme;
+    let active_marker = theme.marker("▶");
+    let frozen = |side| if app.frozen(side) { " [frozen]" } else { "" };
+    let left_suffix = formality_suffix(app, ActiveSide::Left) + frozen(ActiveSide::Left);
+    let right_suffix = formality_suffix(app, ActiveSide::Right) + frozen(ActiveSide::Right);
     let left_title = match app.active {
-        ActiveSide::Left => format!("{} (active, {})", left_language.name, app.active_mode()),
-        ActiveSide::Right => left_language.name.to_string(),
+        ActiveSide::Left => format!(
+            "{}{}{} (active, {})",
+            active_marker,
+            left_language.name,
+            left_suffix,
+            app.active_mode()
+        ),
+        ActiveSide::Right => format!("{}{}{}", left_language.name, left_suffix, target_suffix),
     };
     let right_title = match app.active {
-        ActiveSide::Left => right_language.name.to_string(),
-        ActiveSide::Right => format!("{} (active, {})", right_language.name, app.active_mode()),
+        ActiveSide::Left => format!("{}{}{}", right_language.name, right_suffix, target_suffix),
+        ActiveSide::Right => format!(
+            "{}{}{} (active, {})",
+            active_marker,
+            right_language.name,
+            right_suffix,
+            app.active_mode()
+        ),
     };
-    let text_style = Style::default()
-        .fg(Color::LightBlue)
-        .add_modifier(Modifier::BOLD);
-    let left_block = Block::default()
-        .borders(Borders::ALL)
-        .title(left_title)
-        .border_style(match app.active {
-            ActiveSide::Left => Style::default().fg(Color::Cyan),
-            ActiveSide::Right => Style::default(),
-        });
-    let mut left = app.input.clone();
-    left.set_block(left_block);
-    left.set_style(text_style);
-    if app.active == ActiveSide::Left {
-        left.set_cursor_style(app.active_mode().cursor_style());
-        left.set_cursor_line_style(Style::default().fg(Color::Cyan));
+    // The translated side turns red when it no longer fits the length limit.
+    let overflow = app.length_overflow().is_some();
+    let cursor_style = app.active_mode().cursor_style();
+    let left_active = app.active == ActiveSide::Left;
+    style_pane(
+        &mut app.input,
+        left_title,
+        left_active,
+        overflow,
+        cursor_style,
+        &theme,
+    );
+    style_pane(
+        &mut app.output,
+        right_title,
+        !left_active,
+        overflow,
+        cursor_style,
+        &theme,
+    );
+}
+
+/// The formality translations into `side` use, marked when its language ignores it.
+fn formality_suffix(app: &App, side: ActiveSide) -> String {
+    let formality = app.formality(side);
+    if formality == Formality::Default {
+        return String::new();
+    }
+    if supports_formality(app.language_code(side)) {
+        format!(" [{}]", formality.label())
     } else {
-        left.set_cursor_style(text_style);
-        left.set_cursor_line_style(Style::default());
+        format!(" [{}: unsupported]", formality.label())
     }
-    frame.render_widget(&left, columns[0]);
+}
 
-    let right_block = Block::default()
-        .borders(Borders::ALL)
-        .title(right_title)
-        .border_style(match app.active {
-            ActiveSide::Right => Style::default().fg(Color::Cyan),
-            ActiveSide::Left => Style::default(),
-        });
-    let mut right = app.output.clone();
-    right.set_block(right_block);
-    right.set_style(text_style);
-    if app.active == ActiveSide::Right {
-        right.set_cursor_style(app.active_mode().cursor_style());
-        right.set_cursor_line_style(Style::default().fg(Color::Cyan));
+fn style_pane(
+    pane: &mut TextArea<'static>,
+    title: String,
+    active: bool,
+    overflow: bool,
+    cursor_style: Style,
+    theme: &Theme,
+) {
+    // Only the target pane, which is never the active one, can overflow.
+    let overflow = overflow && !active;
+    let border_style = if active {
+        theme.state(theme.active)
+    } else if overflow {
+        theme.state(theme.error)
+    } else {
+        Style::default()
+    };
+    pane.set_block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(theme.border_type(active, overflow))
+            .title(title)
+            .border_style(border_style),
+    );
+    let text_style = theme.text_style();
+    pane.set_style(text_style);
+    pane.set_search_style(theme.selection_style());
+    if active {
+        pane.set_cursor_style(cursor_style);
+        pane.set_cursor_line_style(Style::default().fg(theme.active));
+    } else {
+        pane.set_cursor_style(text_style);
+        pane.set_cursor_line_style(Style::default());
+    }
+}
+
+/// The part of `area` under the target pane: its column when the panes are side by side,
+/// all of it when they are stacked.
+fn target_column(area: Rect, app: &App) -> Rect {
+    let columns = if app.layout.stacked {
+        [area, area]
     } else {
-        right.set_cursor_style(text_style);
-        right.set_cursor_line_style(Style::default());
+        app.layout.panes(area)
+    };
+    match app.active {
+        ActiveSide::Left => columns[1],
+        ActiveSide::Right => columns[0],
     }
-    frame.render_widget(&right, columns[1]);
 }
 
-fn draw_help(frame: &mut ratatui::Frame, area: Rect, app: &App) {
+fn draw_alternatives(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    picker: &AlternativePicker,
+    theme: &Theme,
+) {
+    frame.render_widget(Clear, area);
+    let items: Vec<ListItem> = picker
+        .candidates
+        .iter()
+        .map(|candidate| {
+            let mut lines = vec![Line::from(Span::styled(
+                candidate.label.clone(),
+                Style::default().fg(theme.muted),
+            ))];
+            lines.extend(
+                candidate
+                    .text
+                    .lines()
+                    .map(|line| Line::from(line.to_string())),
+            );
+            ListItem::new(lines)
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(picker.selected));
+    let title = match picker.pending {
+        0 => "Alternatives  ↑/↓ Enter Esc".to_string(),
+        pending => format!("Alternatives ({} more coming)  ↑/↓ Enter Esc", pending),
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(theme.active)),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Renders both panes; `style_panes` must have run since the last state change.
+fn draw_translator(frame: &mut ratatui::Frame, area: Rect, app: &App) {
+    if let Some(scroll) = app.aligned {
+        draw_aligned(frame, area, app, scroll);
+        return;
+    }
+    if let Some(view) = &app.diff_view {
+        draw_diff(frame, area, view, &app.theme);
+        return;
+    }
+    // Source and target panes, side by side or stacked.
+    let mut columns = app.layout.panes(area);
+    frame.render_widget(&app.input, columns[0]);
+    if !app.extra_targets.is_empty() {
+        columns[1] = draw_extra_targets(frame, columns[1], app);
+    }
+    frame.render_widget(&app.output, columns[1]);
+
+    let target_area = match app.active {
+        ActiveSide::Left => columns[1],
+        ActiveSide::Right => columns[0],
+    };
+    let inner = Rect {
+        x: target_area.x + 1,
+        y: target_area.y + 1,
+        width: target_area.width.saturating_sub(2),
+        height: target_area.height.saturating_sub(2),
+    };
+    // Overlays follow the pane's scrolling, so they mark the rows on screen.
+    let target = app.target_textarea();
+    let (top, left) = scroll_offset(target, inner.height);
+    let target_lines = target.lines();
+
+    // Word-difficulty highlighting repaints the translated text inside its pane.
+    let (_, target_lang) = app.active_pair();
+    if let Some(highlight) = app
+        .frequency
+        .as_ref()
+        .filter(|highlight| highlight.language == target_lang)
+    {
+        let visible = &target_lines[top..target_lines.len().min(top + inner.height as usize)];
+        let lines = frequency_lines(visible, &highlight.list, &app.theme);
+        frame.render_widget(Clear, inner);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((0, left.min(u16::MAX as usize) as u16)),
+            inner,
+        );
+    }
+
+    // Quality estimates sit on the pane's left border, beside the rows they rate.
+    if let Some(rows) = app
+        .quality
+        .as_ref()
+        .and_then(|marks| marks.rows(target_lines))
+    {
+        for (row, band) in rows.range(top..top + inner.height as usize) {
+            let (marker, color) = match band {
+                QualityBand::Good => ("●", app.theme.ok),
+                QualityBand::Review => ("◐", app.theme.pending),
+                QualityBand::Poor => ("○", app.theme.error),
+            };
+            let marker = Span::styled(
+                marker,
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            );
+            let cell = Rect {
+                x: target_area.x,
+                y: inner.y + (row - top) as u16,
+                width: 1,
+                height: 1,
+            };
+            frame.render_widget(marker, cell);
+        }
+    }
+}
+
+/// Draws the extra targets stacked under the target pane and returns the area left for it.
+fn draw_extra_targets(frame: &mut ratatui::Frame, area: Rect, app: &App) -> Rect {
+    let mut constraints = vec![Constraint::Fill(1)];
+    constraints.extend(
+        app.extra_targets
+            .iter()
+            .map(|_| Constraint::Length(EXTRA_TARGET_HEIGHT)),
+    );
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+    for (target, &row) in app.extra_targets.iter().zip(&rows[1..]) {
+        let language = languages().get(target.language).unwrap_or(&languages()[0]);
+        let (text, style) = match &target.error {
+            Some(message) => (message.as_str(), Style::default().fg(app.theme.error)),
+            None => (target.text.as_str(), app.theme.text_style()),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{} ({})", language.name, language.code))
+            .border_style(Style::default().fg(app.theme.border));
+        let paragraph = Paragraph::new(text)
+            .style(style)
+            .block(block)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, row);
+    }
+    rows[0]
+}
+
+/// The active text and its translation as numbered sentence pairs, in place of the panes.
+fn draw_aligned(frame: &mut ratatui::Frame, area: Rect, app: &App, scroll: u16) {
+    let (source_lang, target_lang) = app.active_pair();
+    let source = app.active_text();
+    let target = app.target_textarea().lines().join("\n");
+    let pairs = align_sentences(&source, &target);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "Aligned {} → {}  j/k scroll, Esc close",
+            source_lang, target_lang
+        ))
+        .border_style(Style::default().fg(app.theme.active));
+    let width = block.inner(area).width as usize;
+    let paragraph = Paragraph::new(aligned_lines(&pairs, width, &app.theme))
+        .block(block)
+        .scroll((scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_diff(frame: &mut ratatui::Frame, area: Rect, view: &DiffView, theme: &Theme) {
+    let (added, removed) = view.counts();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "{}: +{} −{} words  j/k scroll, Esc close",
+            view.title, added, removed
+        ))
+        .border_style(Style::default().fg(theme.active));
+    let paragraph = Paragraph::new(diff_lines(&view.changes, theme))
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((view.scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+/// The edited text with insertions underlined and deletions struck through, so the
+/// changes read without relying on color.
+fn diff_lines(changes: &[Change], theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::default()];
+    for change in changes {
+        let (text, style) = match change {
+            Change::Same(text) => (text, theme.text_style()),
+            Change::Added(text) => (
+                text,
+                Style::default()
+                    .fg(theme.ok)
+                    .add_modifier(Modifier::UNDERLINED),
+            ),
+            Change::Removed(text) => (
+                text,
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::CROSSED_OUT),
+            ),
+        };
+        for (index, part) in text.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(Line::default());
+            }
+            if !part.is_empty()
+                && let Some(line) = lines.last_mut()
+            {
+                line.spans.push(Span::styled(part.to_string(), style));
+            }
+        }
+    }
+    lines
+}
+
+/// One row per sentence pair: its number, the source wrapped into the left column and the
+/// translation into the right, continuing on as many lines as the longer side needs.
+fn aligned_lines(pairs: &[[&str; 2]], width: usize, theme: &Theme) -> Vec<Line<'static>> {
+    const NUMBER_WIDTH: usize = 4;
+    const DIVIDER: &str = " │ ";
+    let column = (width.saturating_sub(NUMBER_WIDTH + Span::raw(DIVIDER).width()) / 2).max(1);
+    let mut lines = Vec::new();
+    for (index, [source, target]) in pairs.iter().enumerate() {
+        let left = wrap_to(source, column);
+        let right = wrap_to(target, column);
+        for row in 0..left.len().max(right.len()) {
+            let number = if row == 0 {
+                format!("{:>3} ", index + 1)
+            } else {
+                " ".repeat(NUMBER_WIDTH)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(number, Style::default().fg(theme.muted)),
+                Span::styled(
+                    pad_to(left.get(row).map_or("", String::as_str), column),
+                    theme.text_style(),
+                ),
+                Span::styled(DIVIDER, Style::default().fg(theme.muted)),
+                Span::raw(right.get(row).cloned().unwrap_or_default()),
+            ]));
+        }
+    }
+    lines
+}
+
+/// Greedy word wrap to `width` columns, breaking words that are wider than a whole line.
+fn wrap_to(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let fits = Span::raw(line.as_str()).width() + 1 + Span::raw(word).width() <= width;
+        if !line.is_empty() && !fits {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        for c in word.chars() {
+            if !line.is_empty() && Span::raw(line.as_str()).width() + 1 > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Colors each word by frequency band: common keeps the pane style, uncommon and rare get the
+/// theme's colors, and rare words are underlined (uncommon ones italic when color isn't enough).
+fn frequency_lines<'a>(lines: &'a [String], list: &FrequencyList, theme: &Theme) -> Vec<Line<'a>> {
+    let text_style = theme.text_style();
+    let uncommon_style = if theme.markers {
+        Style::default()
+            .fg(theme.uncommon)
+            .add_modifier(Modifier::ITALIC)
+    } else {
+        Style::default().fg(theme.uncommon)
+    };
+    lines
+        .iter()
+        .map(|line| {
+            let spans: Vec<Span> = word_runs(line)
+                .into_iter()
+                .map(|(run, is_word)| {
+                    let style = match (is_word, is_word.then(|| list.band(run))) {
+                        (true, Some(FrequencyBand::Uncommon)) => uncommon_style,
+                        (true, Some(FrequencyBand::Rare)) => Style::default()
+                            .fg(theme.rare)
+                            .add_modifier(Modifier::UNDERLINED),
+                        _ => text_style,
+                    };
+                    Span::styled(run, style)
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn draw_romanization(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    title: &str,
+    text: String,
+    theme: &Theme,
+) {
+    let paragraph = Paragraph::new(text)
+        .style(Style::default().fg(theme.accent))
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_help(frame: &mut ratatui::Frame, area: Rect) {
     let lines = vec![
         Line::from(vec![
             Span::styled("Ctrl+c", Style::default().add_modifier(Modifier::BOLD)),
@@ -114,7 +745,7 @@ fn draw_help(frame: &mut ratatui::Frame, area: Rect, app: &App) {
         ]),
         Line::from(vec![
             Span::styled("Ctrl+l", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw("  change right language"),
+            Span::raw("  change right language (Tab: extra targets)"),
         ]),
         Line::from(vec![
             Span::styled("Ctrl+n", Style::default().add_modifier(Modifier::BOLD)),
@@ -124,24 +755,171 @@ fn draw_help(frame: &mut ratatui::Frame, area: Rect, app: &App) {
             Span::styled("Ctrl+r", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("  clear active"),
         ]),
+        Line::from(vec![
+            Span::styled("Ctrl+t", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  retranslate, skipping the cache"),
+        ]),
+        Line::from(vec![
+            Span::styled("Ctrl+o", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  copy translation to the clipboard"),
+        ]),
+        Line::from(vec![
+            Span::styled("Ctrl+e", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  edit active pane in $EDITOR"),
+        ]),
+        Line::from(vec![
+            Span::styled("Ctrl+s", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  save session (:w)"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+l", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  stack/unstack panes (Alt+=/Alt+- resize)"),
+        ]),
+        Line::from(vec![
+            Span::styled("Ctrl+g", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  debug overlay"),
+        ]),
         Line::from(vec![
             Span::styled("Tab", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("  switch side"),
         ]),
         Line::from(vec![
-            Span::styled("Vim", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw("  i/a/o insert, Esc normal, hjkl move"),
+            Span::styled("Alt+t", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  cycle tone"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+f", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  cycle target formality"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+n", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  alternative translations"),
+        ]),
+        Line::from(vec![
+            Span::styled(":fetch URL", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  load a web page"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+i", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  sentence-aligned view"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+D", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  diff your edits against the machine translation"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+B", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  back-translate and diff with the original"),
+        ]),
+        Line::from(vec![
+            Span::styled("/", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  search the active pane (n/N next/previous)"),
+        ]),
+        Line::from(vec![
+            Span::styled(":registers", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  show vim registers (\"ay yanks into a)"),
+        ]),
+        Line::from(vec![
+            Span::styled(":theme", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  pick a color theme"),
+        ]),
+        Line::from(vec![
+            Span::styled(":sessions", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  saved sessions"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                ":present [secs]",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  presentation mode"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+x", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  export pair"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "Ctrl+y/Alt+h",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  search history"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+g", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  word-by-word gloss"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+d", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  dictionary for word/selection"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+p", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  synonyms for visual selection"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+w", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  highlight word frequency"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+a", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  send pair to flashcard deck"),
         ]),
         Line::from(vec![
-            Span::styled("Status", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw("  "),
-            match &app.error {
-                Some(message) => Span::styled(message.as_str(), Style::default().fg(Color::Red)),
-                None if app.pending_translation => {
-                    Span::styled("translating...", Style::default().fg(Color::Yellow))
-                }
-                None => Span::styled("ready", Style::default().fg(Color::Green)),
-            },
+            Span::styled("Alt+u", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  practice stats and streak"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+q", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  round-trip quality estimate"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+m", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  toggle profanity masking"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+s", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  speak translation"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+o", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  compare in a web translator"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+e", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  show the full error message"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+c", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  show translation as a QR code"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+v", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  push-to-talk: start/stop speech input"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+k", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  swap pane text (no retranslation)"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+y", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  apply a translation held back by your edits"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+z", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  freeze pane: hold translations into it (:freeze)"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+b", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  copy translation to the tmux buffer"),
+        ]),
+        Line::from(vec![
+            Span::styled("Alt+r", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  cycle romanization / readings (JA/ZH/KO/RU)"),
+        ]),
+        Line::from(vec![
+            Span::styled("Vim", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  i/a/o insert, R replace, Esc normal, hjkl move"),
         ]),
     ];
 
@@ -152,6 +930,146 @@ fn draw_help(frame: &mut ratatui::Frame, area: Rect, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
+/// The bottom bar: vim mode, each pane's word and character counts, the API's remaining quota
+/// and the last request's latency, then the current state.
+fn status_bar_spans(app: &App) -> Vec<Span<'_>> {
+    let theme = &app.theme;
+    let counts = |side: ActiveSide, counts: TextCounts| {
+        format!(
+            "{} {}w {}c",
+            app.language_code(side),
+            counts.words,
+            counts.chars
+        )
+    };
+    let mut spans = vec![
+        Span::styled(format!(" {} ", app.active_mode()), theme.selection_style()),
+        Span::raw(" "),
+        Span::raw(counts(ActiveSide::Left, app.left_counts)),
+        Span::styled(" · ", Style::default().fg(theme.muted)),
+        Span::raw(counts(ActiveSide::Right, app.right_counts)),
+    ];
+    if let Some(quota) = app.quota {
+        spans.push(Span::styled("  |  ", Style::default().fg(theme.muted)));
+        let style = if quota.remaining() * 10 < quota.limit {
+            theme.state(theme.error)
+        } else {
+            Style::default()
+        };
+        spans.push(Span::styled(quota.label(), style));
+    }
+    if let Some(latency) = app.last_latency {
+        spans.push(Span::styled("  |  ", Style::default().fg(theme.muted)));
+        spans.push(Span::raw(format!("{} ms", latency.as_millis())));
+    }
+    spans.push(Span::styled("  |  ", Style::default().fg(theme.muted)));
+    spans.extend(status_spans(app));
+    spans
+}
+
+/// Current state, tone and masking, shared by the status bar and the popup status line.
+fn status_spans(app: &App) -> Vec<Span<'_>> {
+    let theme = &app.theme;
+    vec![
+        match &app.error {
+            Some(message) => Span::styled(
+                format!("{}{}", theme.marker("✗"), error_summary(message)),
+                theme.state(theme.error),
+            ),
+            None if app.toast.is_some() => Span::styled(
+                format!(
+                    "{}{}",
+                    theme.marker("✓"),
+                    app.toast
+                        .as_ref()
+                        .map(|(message, _)| message.as_str())
+                        .unwrap_or_default()
+                ),
+                theme.state(theme.ok),
+            ),
+            None if app.recording.is_some() => Span::styled(
+                "● recording (Alt+v to stop)",
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            None if app.offline_since.is_some() => Span::styled(
+                format!(
+                    "{}offline: translation queued until the API is reachable",
+                    theme.marker("⚠")
+                ),
+                theme.state(theme.pending),
+            ),
+            None if app.held_translation.is_some() => Span::styled(
+                format!(
+                    "{}new translation held to keep your edits (Alt+y replaces them)",
+                    theme.marker("⚠")
+                ),
+                theme.state(theme.pending),
+            ),
+            None if app.translating_since.is_some() => Span::styled(
+                format!(
+                    "{} translating{}...",
+                    spinner_frame(app.translating_since.unwrap_or_else(Instant::now)),
+                    app.chunk_progress
+                        .map(|(chunk, total)| format!(" chunk {}/{}", chunk + 1, total))
+                        .unwrap_or_default()
+                ),
+                theme.state(theme.pending),
+            ),
+            None if app.pending_translation => Span::styled(
+                format!("{}translating...", theme.marker("…")),
+                theme.state(theme.pending),
+            ),
+            None if app.back_translating.is_some() => Span::styled(
+                format!("{}translating back...", theme.marker("…")),
+                theme.state(theme.pending),
+            ),
+            None if app.notice.is_some() => Span::styled(
+                format!(
+                    "{}{}",
+                    theme.marker("✓"),
+                    app.notice.as_deref().unwrap_or_default()
+                ),
+                theme.state(theme.ok),
+            ),
+            None if app.length_overflow().is_some() => Span::styled(
+                format!(
+                    "{}translation is {} chars over the limit",
+                    theme.marker("✗"),
+                    app.length_overflow().unwrap_or_default()
+                ),
+                theme.state(theme.error),
+            ),
+            None => Span::styled(format!("{}ready", theme.marker("✓")), theme.state(theme.ok)),
+        },
+        Span::raw("  |  tone: "),
+        Span::styled(app.tone.label(), Style::default().fg(app.theme.secondary)),
+        Span::raw(if app.profanity.is_some() {
+            "  |  profanity masked"
+        } else {
+            ""
+        }),
+    ]
+}
+
+fn spinner_frame(since: Instant) -> &'static str {
+    let turns = since.elapsed().as_millis() / SPINNER_INTERVAL.as_millis();
+    SPINNER_FRAMES[turns as usize % SPINNER_FRAMES.len()]
+}
+
+/// The first line of an error, cut short with a pointer to the details popup when it is long
+/// (API error bodies can be whole HTML pages).
+fn error_summary(message: &str) -> String {
+    let first_line = message.lines().next().unwrap_or_default().trim();
+    let truncated = first_line.chars().count() > ERROR_SUMMARY_CHARS;
+    if !truncated && !message.trim().contains('\n') {
+        return first_line.to_string();
+    }
+    let summary: String = first_line.chars().take(ERROR_SUMMARY_CHARS).collect();
+    format!("{}… (Alt+e for details)", summary.trim_end())
+}
+
 fn draw_language_picker(frame: &mut ratatui::Frame, app: &App) {
     let Some(picker) = &app.picker else {
         return;
@@ -167,7 +1085,7 @@ fn draw_language_picker(frame: &mut ratatui::Frame, app: &App) {
     let block = Block::default()
         .borders(Borders::ALL)
         .title(title)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(app.theme.border));
     frame.render_widget(block, area);
 
     let inner = Rect {
@@ -198,8 +1116,14 @@ fn draw_language_picker(frame: &mut ratatui::Frame, app: &App) {
     let items: Vec<ListItem> = indices
         .iter()
         .map(|&index| {
-            let language = LANGUAGES.get(index).unwrap_or(&LANGUAGES[0]);
-            ListItem::new(format!("{} ({})", language.name, language.code))
+            let language = languages().get(index).unwrap_or(&languages()[0]);
+            let extra = picker.side == ActiveSide::Right
+                && app
+                    .extra_targets
+                    .iter()
+                    .any(|target| target.language == index);
+            let mark = if extra { " [+]" } else { "" };
+            ListItem::new(format!("{} ({}){}", language.name, language.code, mark))
         })
         .collect();
 
@@ -210,27 +1134,754 @@ fn draw_language_picker(frame: &mut ratatui::Frame, app: &App) {
     }
 
     let list = List::new(items)
-        .highlight_style(
+        .highlight_style(app.theme.selection_style())
+        .highlight_symbol(">> ");
+    frame.render_stateful_widget(list, rows[1], &mut state);
+
+    let mut keys = vec![
+        Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" select  "),
+        Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" cancel  "),
+        Span::styled("Up/Down", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" navigate"),
+    ];
+    if picker.side == ActiveSide::Right {
+        keys.push(Span::styled(
+            "  Tab",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        keys.push(Span::raw(" add/remove extra target"));
+    }
+    let footer = Paragraph::new(Line::from(keys)).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, rows[2]);
+}
+
+fn draw_gloss(frame: &mut ratatui::Frame, app: &App) {
+    let Some(entries) = &app.gloss else {
+        return;
+    };
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Gloss (Esc to close)")
+        .border_style(Style::default().fg(app.theme.border));
+    let width = area.width.saturating_sub(2) as usize;
+    let paragraph = Paragraph::new(interlinear_lines(entries, width, &app.theme)).block(block);
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_dictionary(frame: &mut ratatui::Frame, app: &App) {
+    let Some(panel) = &app.dictionary else {
+        return;
+    };
+    let area = centered_rect(70, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let mut lines = Vec::new();
+    if panel.entries.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No Wiktionary entry for this language.",
+            Style::default().fg(app.theme.muted),
+        )));
+    }
+    for entry in &panel.entries {
+        lines.push(Line::from(Span::styled(
+            entry.part_of_speech.clone(),
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for (index, definition) in entry.definitions.iter().enumerate() {
+            lines.push(Line::from(format!("  {}. {}", index + 1, definition)));
+        }
+        lines.push(Line::default());
+    }
+
+    let title = format!(
+        "Dictionary: {} ({})  j/k scroll, Esc close",
+        panel.word, panel.language
+    );
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(app.theme.border)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((panel.scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_suggestions(frame: &mut ratatui::Frame, app: &App) {
+    let Some(picker) = &app.suggestions else {
+        return;
+    };
+    let area = centered_rect(50, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = picker
+        .suggestions
+        .iter()
+        .map(|suggestion| ListItem::new(suggestion.as_str()))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(picker.selected));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Replace \"{}\"  Enter accept, Esc cancel",
+                    picker.original
+                ))
+                .border_style(Style::default().fg(app.theme.border)),
+        )
+        .highlight_style(app.theme.selection_style())
+        .highlight_symbol(">> ");
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_stats(frame: &mut ratatui::Frame, app: &App) {
+    let area = centered_rect(60, 60, frame.area());
+    frame.render_widget(Clear, area);
+    let lines: Vec<Line> = app
+        .stats
+        .summary_lines()
+        .into_iter()
+        .map(Line::from)
+        .collect();
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Practice stats (Esc to close)")
+            .border_style(Style::default().fg(app.theme.border)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_review(frame: &mut ratatui::Frame, review: &Review, theme: &Theme) {
+    let Some(card) = review.current() else {
+        return;
+    };
+    let area = centered_rect(60, 50, frame.area());
+    frame.render_widget(Clear, area);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            card.source.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::default(),
+    ];
+    if review.revealed {
+        lines.push(Line::from(Span::styled(
+            card.target.clone(),
+            Style::default().fg(theme.accent),
+        )));
+        if let Some(pronunciation) = &card.pronunciation {
+            lines.push(Line::from(Span::styled(
+                pronunciation.clone(),
+                Style::default().fg(theme.muted),
+            )));
+        }
+    } else {
+        lines.push(Line::from(Span::styled(
+            "Space shows the answer",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "Review {}/{} {} → {}  Space reveal/next, h/l step, Esc close",
+                review.index + 1,
+                review.cards.len(),
+                card.source_lang,
+                card.target_lang
+            ))
+            .border_style(Style::default().fg(theme.border)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_command_line(frame: &mut ratatui::Frame, prompt: &str, line: &str, theme: &Theme) {
+    let screen = frame.area();
+    let area = Rect {
+        x: screen.x,
+        y: screen.bottom().saturating_sub(1),
+        width: screen.width,
+        height: 1.min(screen.height),
+    };
+    frame.render_widget(Clear, area);
+    let text = Line::from(vec![
+        Span::styled(prompt, Style::default().fg(theme.accent)),
+        Span::raw(line),
+        Span::styled("█", Style::default().fg(theme.accent)),
+    ]);
+    frame.render_widget(Paragraph::new(text), area);
+}
+
+fn draw_export_prompt(frame: &mut ratatui::Frame, app: &App) {
+    let Some(path) = &app.export_prompt else {
+        return;
+    };
+    let area = centered_rect(60, 30, frame.area());
+    frame.render_widget(Clear, area);
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("Path: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(path.as_str()),
+            Span::styled("█", Style::default().fg(app.theme.accent)),
+        ]),
+        Line::from(vec![
+            Span::styled("Rows: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(if app.export_sentences {
+                "aligned by sentence"
+            } else {
+                "aligned by line"
+            }),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            ".md writes a markdown table, .json structured JSON, .html a standalone two-column \
+             page, anything else plain text",
+            Style::default().fg(app.theme.muted),
+        )),
+        Line::from(vec![
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" export  "),
+            Span::styled("Tab", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" lines/sentences  "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" cancel"),
+        ]),
+    ];
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Export current pair")
+            .border_style(Style::default().fg(app.theme.border)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_session_picker(frame: &mut ratatui::Frame, app: &App) {
+    let Some(picker) = &app.session_picker else {
+        return;
+    };
+    let area = centered_rect(70, 60, frame.area());
+    frame.render_widget(Clear, area);
+    let items: Vec<ListItem> = picker
+        .sessions
+        .iter()
+        .map(|session| {
+            let panes = &session.panes;
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    session.name.as_str(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(
+                        "  {}  {}↔{}  {}  ",
+                        format_timestamp(panes.saved_at),
+                        panes.left_language,
+                        panes.right_language,
+                        session.provider
+                    ),
+                    Style::default().fg(app.theme.muted),
+                ),
+                Span::raw(single_line(&panes.left_text)),
+            ]))
+        })
+        .collect();
+    let mut state = ListState::default();
+    if !picker.sessions.is_empty() {
+        state.select(Some(picker.selected.min(picker.sessions.len() - 1)));
+    }
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Sessions (Enter open, Ctrl+d delete, Esc close)")
+                .border_style(Style::default().fg(app.theme.border)),
+        )
+        .highlight_style(app.theme.selection_style())
+        .highlight_symbol(">> ");
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_theme_picker(frame: &mut ratatui::Frame, app: &App) {
+    let Some(picker) = &app.theme_picker else {
+        return;
+    };
+    let area = centered_rect(40, 50, frame.area());
+    frame.render_widget(Clear, area);
+    // Each row ends in a swatch of the theme's state colors.
+    let items: Vec<ListItem> = THEMES
+        .iter()
+        .map(|theme| {
+            let mut spans = vec![Span::raw(format!("{:<16}", theme.name))];
+            for color in [
+                theme.text,
+                theme.active,
+                theme.ok,
+                theme.pending,
+                theme.error,
+            ] {
+                spans.push(Span::styled("■ ", Style::default().fg(color)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(picker.selected));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Theme (Enter keep, Esc cancel)")
+                .border_style(Style::default().fg(app.theme.border)),
+        )
+        .highlight_style(app.theme.selection_style())
+        .highlight_symbol(">> ");
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_draft_prompt(frame: &mut ratatui::Frame, app: &App) {
+    let Some(draft) = &app.draft_prompt else {
+        return;
+    };
+    let area = centered_rect(60, 40, frame.area());
+    frame.render_widget(Clear, area);
+    let preview = |text: &str| single_line(text).chars().take(60).collect::<String>();
+    let lines = vec![
+        Line::from(format!(
+            "Unsaved draft from {} ({} → {})",
+            format_timestamp(draft.saved_at),
+            draft.left_language,
+            draft.right_language
+        )),
+        Line::from(""),
+        Line::from(Span::raw(preview(&draft.left_text))),
+        Line::from(Span::styled(
+            preview(&draft.right_text),
+            Style::default().fg(app.theme.text),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" restore  "),
+            Span::styled("n", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" discard"),
+        ]),
+    ];
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Restore draft?")
+            .border_style(Style::default().fg(app.theme.border)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_lock_screen(frame: &mut ratatui::Frame, lock: &PrivacyLock, theme: &Theme) {
+    let area = centered_rect(50, 30, frame.area());
+    let mut lines = vec![Line::from(Span::styled(
+        "ptrui is locked",
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    lines.push(Line::from(""));
+    if lock.needs_passphrase() {
+        lines.push(Line::from(format!(
+            "Passphrase: {}",
+            "•".repeat(lock.typed_len())
+        )));
+        if lock.failed {
+            lines.push(Line::from(Span::styled(
+                "Wrong passphrase",
+                Style::default().fg(theme.error),
+            )));
+        }
+        lines.push(Line::from(Span::styled(
+            "Enter to unlock, Ctrl+c to quit",
+            Style::default().fg(theme.muted),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            "Press any key to unlock",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    let paragraph = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.muted)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_word_of_the_day(frame: &mut ratatui::Frame, word: &WordOfTheDay, theme: &Theme) {
+    let area = centered_rect(50, 30, frame.area());
+    frame.render_widget(Clear, area);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            word.text.as_str(),
             Style::default()
-                .fg(Color::Black)
-                .bg(Color::Yellow)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    if let Some(translation) = &word.translation {
+        lines.push(Line::from(translation.as_str()));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        format!(
+            "{} · from your {}",
+            language_name(&word.language),
+            word.origin
+        ),
+        Style::default().fg(theme.muted),
+    )));
+    let paragraph = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Word of the day (any key to close)")
+                .border_style(Style::default().fg(theme.border)),
+        );
+    frame.render_widget(paragraph, area);
+}
+
+/// Small developer overlay in the top-right corner; doesn't capture keys.
+fn draw_debug(frame: &mut ratatui::Frame, app: &App) {
+    let stats = &app.cache.stats;
+    let lines = vec![
+        Line::from(format!(
+            "cache   {}/{} entries",
+            app.cache.len(),
+            app.cache.capacity
+        )),
+        Line::from(format!(
+            "hits    {} ({:.0}%)",
+            stats.hits,
+            stats.hit_rate() * 100.0
+        )),
+        Line::from(format!("misses  {}", stats.misses)),
+        Line::from(format!("evicted {}", stats.evictions)),
+        Line::from(format!("saved   {} chars", stats.chars_saved)),
+        Line::from(format!("pending {}", app.pending_translation)),
+    ];
+    let screen = frame.area();
+    let width = 30.min(screen.width);
+    let height = (lines.len() as u16 + 2).min(screen.height);
+    let area = Rect {
+        x: screen.right().saturating_sub(width),
+        y: screen.y,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, area);
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Debug (Ctrl+g)")
+            .border_style(Style::default().fg(app.theme.secondary)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+/// The active pane's own register (`""`) and the named ones, a line each.
+fn draw_registers(frame: &mut ratatui::Frame, app: &App, scroll: u16) {
+    let area = centered_rect(70, 50, frame.area());
+    frame.render_widget(Clear, area);
+    let unnamed = app.active_textarea().yank_text();
+    let registers = std::iter::once(('"', unnamed.as_str())).chain(
+        app.registers
+            .iter()
+            .map(|(name, text)| (*name, text.as_str())),
+    );
+    let lines: Vec<Line> = registers
+        .map(|(name, text)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("\"{}  ", name),
+                    Style::default().fg(app.theme.accent),
+                ),
+                Span::raw(text.replace('\n', "⏎")),
+            ])
+        })
+        .collect();
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Registers (Esc to close)")
+                .border_style(Style::default().fg(app.theme.border)),
+        )
+        .scroll((scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_error_details(frame: &mut ratatui::Frame, message: &str, scroll: u16, theme: &Theme) {
+    let area = centered_rect(80, 60, frame.area());
+    frame.render_widget(Clear, area);
+    let paragraph = Paragraph::new(message)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Error (j/k scroll, Esc to close)")
+                .border_style(Style::default().fg(theme.error)),
         )
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_qr_code(frame: &mut ratatui::Frame, lines: &[String], theme: &Theme) {
+    let screen = frame.area();
+    let code_width = lines.first().map_or(0, |line| line.chars().count()) as u16;
+    let width = (code_width + 2).max(24);
+    let height = lines.len() as u16 + 2;
+    if width > screen.width || height > screen.height {
+        let area = centered_rect(60, 20, screen);
+        frame.render_widget(Clear, area);
+        let message = Paragraph::new("Enlarge the terminal to show the QR code")
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("QR code (Esc to close)")
+                    .border_style(Style::default().fg(theme.border)),
+            )
+            .wrap(Wrap { trim: true });
+        frame.render_widget(message, area);
+        return;
+    }
+    let area = Rect {
+        x: screen.x + (screen.width - width) / 2,
+        y: screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, area);
+    let code: Vec<Line> = lines.iter().map(|line| Line::raw(line.as_str())).collect();
+    // Fixed colors: phones need light modules on a dark background, whatever the theme.
+    let paragraph = Paragraph::new(code)
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("QR (Esc)")
+                .border_style(Style::default().fg(theme.border)),
+        );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_usage(frame: &mut ratatui::Frame, usage: &UsageSummary, theme: &Theme) {
+    let area = centered_rect(80, 80, frame.area());
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Usage (Esc to close)")
+        .border_style(Style::default().fg(theme.border));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(6),
+            Constraint::Min(5),
+            Constraint::Min(5),
+        ])
+        .split(inner);
+
+    let daily_total: u64 = usage.daily_chars.iter().sum();
+    let sparkline = Sparkline::default()
+        .block(Block::default().borders(Borders::BOTTOM).title(format!(
+            "Characters per day, last {} days ({} total)",
+            usage.daily_chars.len(),
+            daily_total
+        )))
+        .data(&usage.daily_chars)
+        .style(Style::default().fg(theme.ok));
+    frame.render_widget(sparkline, rows[0]);
+
+    let charts = [
+        ("Characters by language pair", &usage.pairs, theme.text),
+        ("Translations by provider", &usage.providers, theme.accent),
+    ];
+    for ((title, data, color), area) in charts.into_iter().zip(rows.iter().skip(1)) {
+        let bars: Vec<Bar> = data
+            .iter()
+            .map(|(label, value)| Bar::default().label(label.as_str().into()).value(*value))
+            .collect();
+        let chart = BarChart::default()
+            .block(Block::default().borders(Borders::BOTTOM).title(title))
+            .data(BarGroup::default().bars(&bars))
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .bar_style(Style::default().fg(color));
+        frame.render_widget(chart, *area);
+    }
+}
+
+/// Lays out words with their glosses directly underneath, wrapping by column width.
+fn interlinear_lines(entries: &[GlossEntry], width: usize, theme: &Theme) -> Vec<Line<'static>> {
+    let word_style = Style::default().add_modifier(Modifier::BOLD);
+    let gloss_style = Style::default().fg(theme.accent);
+    let mut lines = Vec::new();
+    let mut words: Vec<Span> = Vec::new();
+    let mut glosses: Vec<Span> = Vec::new();
+    let mut used = 0usize;
+
+    for entry in entries {
+        let column = Span::raw(entry.word.as_str())
+            .width()
+            .max(Span::raw(entry.gloss.as_str()).width())
+            + 2;
+        if used > 0 && used + column > width {
+            lines.push(Line::from(std::mem::take(&mut words)));
+            lines.push(Line::from(std::mem::take(&mut glosses)));
+            lines.push(Line::default());
+            used = 0;
+        }
+        words.push(Span::styled(pad_to(&entry.word, column), word_style));
+        glosses.push(Span::styled(pad_to(&entry.gloss, column), gloss_style));
+        used += column;
+    }
+    if !words.is_empty() {
+        lines.push(Line::from(words));
+        lines.push(Line::from(glosses));
+    }
+    lines
+}
+
+fn pad_to(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(Span::raw(text).width());
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+fn draw_history_finder(frame: &mut ratatui::Frame, app: &App) {
+    let Some(finder) = &app.history_finder else {
+        return;
+    };
+    let area = centered_rect(80, 80, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Search history")
+        .border_style(Style::default().fg(app.theme.border));
+    frame.render_widget(block, area);
+
+    let inner = Rect {
+        x: area.x + 1,
+        y: area.y + 1,
+        width: area.width.saturating_sub(2),
+        height: area.height.saturating_sub(2),
+    };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(2),
+        ])
+        .split(inner);
+
+    let query = match &finder.tag_edit {
+        Some(tags) => Line::from(vec![
+            Span::styled("Tags: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(tags.as_str()),
+            Span::styled("█", Style::default().fg(app.theme.accent)),
+        ]),
+        None => Line::from(vec![
+            Span::styled("Search: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(finder.query.as_str()),
+        ]),
+    };
+    let query = Paragraph::new(query).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(query, rows[0]);
+
+    let items: Vec<ListItem> = finder
+        .matches
+        .iter()
+        .filter_map(|&index| finder.entries.get(index))
+        .map(|entry| {
+            let mut spans = vec![
+                Span::styled(
+                    if entry.pinned { "★ " } else { "  " },
+                    Style::default().fg(app.theme.accent),
+                ),
+                Span::styled(
+                    format!(
+                        "{}  {}→{}  ",
+                        format_timestamp(entry.created_at),
+                        entry.source_lang,
+                        entry.target_lang
+                    ),
+                    Style::default().fg(app.theme.muted),
+                ),
+            ];
+            for tag in &entry.tags {
+                spans.push(Span::styled(
+                    format!("#{} ", tag),
+                    Style::default().fg(app.theme.secondary),
+                ));
+            }
+            spans.extend([
+                Span::raw(single_line(&entry.source_text)),
+                Span::styled("  ⇒  ", Style::default().fg(app.theme.muted)),
+                Span::styled(
+                    single_line(&entry.target_text),
+                    Style::default().fg(app.theme.text),
+                ),
+            ]);
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let mut state = ListState::default();
+    if !finder.matches.is_empty() {
+        state.select(Some(finder.selected.min(finder.matches.len() - 1)));
+    }
+    let list = List::new(items)
+        .highlight_style(app.theme.selection_style())
         .highlight_symbol(">> ");
     frame.render_stateful_widget(list, rows[1], &mut state);
 
     let footer = Paragraph::new(Line::from(vec![
         Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(" select  "),
+        Span::raw(" load  "),
         Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
         Span::raw(" cancel  "),
         Span::styled("Up/Down", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(" navigate"),
+        Span::raw(" navigate  "),
+        Span::styled("Ctrl+s", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" pin  "),
+        Span::styled("Ctrl+t", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" tags  "),
+        Span::styled("Ctrl+d", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" delete entry"),
     ]))
     .block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, rows[2]);
 }
 
+/// Collapses multi-line text for one-row list items.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -0,0 +1,158 @@
+//! Renders `draw_ui` into ratatui's `TestBackend` for key states and compares the screen
+//! against golden files in `src/snapshots/`. After an intended UI change, review and accept
+//! the new output with `cargo insta review` (or rerun with `INSTA_UPDATE=always`).
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use tui_textarea::TextArea;
+
+use crate::alternatives::{Alternative, AlternativePicker};
+use crate::app::App;
+use crate::config::Config;
+use crate::languages::find_language_index;
+use crate::theme::Theme;
+use crate::ui::{draw_ui, style_panes};
+
+fn render(app: &mut App, width: u16, height: u16) -> TestBackend {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    // The tests set pane text directly, skipping the edits that keep the counts current.
+    app.refresh_counts();
+    style_panes(app);
+    terminal.draw(|frame| draw_ui(frame, app)).unwrap();
+    terminal.backend().clone()
+}
+
+fn press(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    app.handle_key(KeyEvent::new(code, modifiers));
+}
+
+#[test]
+fn empty() {
+    insta::assert_snapshot!(render(&mut App::new(&Config::default()), 100, 40));
+}
+
+#[test]
+fn active_right() {
+    let mut app = App::new(&Config::default());
+    app.input = TextArea::from(["Good morning"]);
+    app.output = TextArea::from(["Buenos días"]);
+    press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+    insta::assert_snapshot!(render(&mut app, 100, 40));
+}
+
+#[test]
+fn picker_open() {
+    let mut app = App::new(&Config::default());
+    press(&mut app, KeyCode::Char('l'), KeyModifiers::CONTROL);
+    press(&mut app, KeyCode::Char('g'), KeyModifiers::NONE);
+    press(&mut app, KeyCode::Char('e'), KeyModifiers::NONE);
+    insta::assert_snapshot!(render(&mut app, 100, 40));
+}
+
+#[test]
+fn error() {
+    let mut app = App::new(&Config::default());
+    app.error = Some(format!(
+        "Translation API error (502 Bad Gateway): {}",
+        "<html><body>upstream unavailable</body></html> ".repeat(5)
+    ));
+    insta::assert_snapshot!(render(&mut app, 100, 40));
+}
+
+#[test]
+fn high_contrast() {
+    let mut app = App::new(&Config::default());
+    app.theme = Theme::find("high-contrast").unwrap();
+    app.input = TextArea::from(["Good morning"]);
+    app.error = Some("Translation API error (403 Forbidden)".to_string());
+    // Tall enough to show every control above the status bar.
+    insta::assert_snapshot!(render(&mut app, 100, 64));
+}
+
+#[test]
+fn small_terminal() {
+    let mut app = App::new(&Config::default());
+    app.input = TextArea::from(["Where is the station?"]);
+    insta::assert_snapshot!(render(&mut app, 40, 12));
+}
+
+#[test]
+fn alternatives() {
+    let mut app = App::new(&Config::default());
+    app.input = TextArea::from(["Thank you"]);
+    app.output = TextArea::from(["Gracias"]);
+    let candidate = |label: &str, text: &str| Alternative {
+        label: label.to_string(),
+        text: text.to_string(),
+    };
+    app.alternatives = Some(AlternativePicker {
+        candidates: vec![
+            candidate("current settings", "Gracias"),
+            candidate("formal", "Se lo agradezco"),
+        ],
+        selected: 1,
+        version: 0,
+        pending: 0,
+    });
+    insta::assert_snapshot!(render(&mut app, 100, 40));
+}
+
+#[test]
+fn stacked() {
+    let mut app = App::new(&Config {
+        layout: Some("stacked".to_string()),
+        split: Some(40),
+        ..Config::default()
+    });
+    app.input = TextArea::from(["Good morning"]);
+    app.output = TextArea::from(["Buenos días"]);
+    insta::assert_snapshot!(render(&mut app, 60, 40));
+}
+
+#[test]
+fn theme_picker() {
+    let mut app = App::new(&Config::default());
+    app.input = TextArea::from(["Good morning"]);
+    app.execute_command("theme");
+    app.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    insta::assert_snapshot!(render(&mut app, 100, 40));
+}
+
+#[test]
+fn registers() {
+    let mut app = App::new(&Config::default());
+    app.input = TextArea::from(["Good morning", "everyone"]);
+    for c in "\"ayj\"byy".chars() {
+        press(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    app.execute_command("registers");
+    insta::assert_snapshot!(render(&mut app, 80, 30));
+}
+
+#[test]
+fn extra_targets() {
+    let mut app = App::new(&Config::default());
+    app.input = TextArea::from(["Good morning"]);
+    app.output = TextArea::from(["Buenos días"]);
+    for (code, text) in [("FR", "Bonjour"), ("DE", "Guten Morgen")] {
+        app.toggle_extra_target(find_language_index(code).unwrap());
+        app.extra_targets.last_mut().unwrap().text = text.to_string();
+    }
+    insta::assert_snapshot!(render(&mut app, 80, 40));
+}
+
+#[test]
+fn aligned() {
+    let mut app = App::new(&Config::default());
+    app.input = TextArea::from([
+        "Good morning. The train to Madrid leaves from platform four in ten minutes.",
+        "Thanks!",
+    ]);
+    app.output = TextArea::from([
+        "Buenos días. El tren a Madrid sale del andén cuatro en diez minutos.",
+        "¡Gracias!",
+    ]);
+    app.handle_key(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::ALT));
+    insta::assert_snapshot!(render(&mut app, 80, 40));
+}
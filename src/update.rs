@@ -0,0 +1,143 @@
+use std::env::consts::{ARCH, OS};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+const REPOSITORY: &str = "gclarkjr5/ptrui";
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// `ptrui self check-update`: compares this build with the latest GitHub release and prints how
+/// to upgrade, downloading the release archive for this platform with `download`.
+pub fn check_update(download: bool) -> Result<(), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .user_agent(concat!("ptrui/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|err| format!("Failed to build HTTP client: {}", err))?;
+    let response = client
+        .get(format!(
+            "https://api.github.com/repos/{}/releases/latest",
+            REPOSITORY
+        ))
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .map_err(|err| format!("Failed to reach GitHub: {}", err))?;
+    if !response.status().is_success() {
+        return Err(format!("GitHub releases API error ({})", response.status()));
+    }
+    let release: Release = response
+        .json()
+        .map_err(|err| format!("Invalid GitHub release response: {}", err))?;
+    let latest = release.tag_name.trim_start_matches('v');
+    if !is_newer(latest, CURRENT_VERSION) {
+        println!("ptrui {} is up to date", CURRENT_VERSION);
+        return Ok(());
+    }
+
+    println!(
+        "ptrui {} is available (this is {}): {}",
+        latest, CURRENT_VERSION, release.html_url
+    );
+    println!(
+        "Upgrade with: cargo install --locked --git https://github.com/{} --tag {}",
+        REPOSITORY, release.tag_name
+    );
+    let asset = platform_target().and_then(|target| {
+        release
+            .assets
+            .iter()
+            .find(|asset| asset.name == asset_name(&release.tag_name, target))
+    });
+    let Some(asset) = asset else {
+        println!("No prebuilt binary for {}-{}", ARCH, OS);
+        return Ok(());
+    };
+    if !download {
+        println!("Or download: {}", asset.browser_download_url);
+        return Ok(());
+    }
+    let bytes = client
+        .get(&asset.browser_download_url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.bytes())
+        .map_err(|err| format!("Failed to download {}: {}", asset.name, err))?;
+    let path = PathBuf::from(&asset.name);
+    fs::write(&path, &bytes).map_err(|err| format!("Failed to write {:?}: {}", path, err))?;
+    println!(
+        "Downloaded {}; extract it and replace the ptrui binary on your PATH",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Whether `latest` is a higher `X.Y.Z` version than `current`; pre-release suffixes are
+/// ignored.
+fn is_newer(latest: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Option<Vec<u64>> {
+        version
+            .split(['-', '+'])
+            .next()?
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect()
+    };
+    match (parse(latest), parse(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// The target triple the release workflow builds for this platform.
+fn platform_target() -> Option<&'static str> {
+    match (ARCH, OS) {
+        ("x86_64", "linux") => Some("x86_64-unknown-linux-gnu"),
+        ("x86_64", "macos") => Some("x86_64-apple-darwin"),
+        ("aarch64", "macos") => Some("aarch64-apple-darwin"),
+        ("x86_64", "windows") => Some("x86_64-pc-windows-msvc"),
+        _ => None,
+    }
+}
+
+/// Archive names as packaged by `.github/workflows/release.yml`.
+fn asset_name(tag: &str, target: &str) -> String {
+    let extension = if target.contains("windows") {
+        "zip"
+    } else {
+        "tar.gz"
+    };
+    format!("ptrui-{}-{}.{}", tag, target, extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions_and_names_assets() {
+        assert!(is_newer("0.10.0", "0.9.3"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1.0-rc.1", "0.1.0"));
+        assert!(!is_newer("nightly", "0.1.0"));
+        assert_eq!(
+            asset_name("v0.2.0", "x86_64-pc-windows-msvc"),
+            "ptrui-v0.2.0-x86_64-pc-windows-msvc.zip"
+        );
+    }
+}
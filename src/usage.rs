@@ -0,0 +1,98 @@
+use crate::dates::format_day;
+
+/// Days covered by the per-day usage chart, ending today.
+pub const USAGE_DAYS: usize = 30;
+const BAR_WIDTH: u64 = 30;
+
+/// Translation volume aggregated from the history store.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageSummary {
+    // Day number of `daily_chars[0]`.
+    pub first_day: i64,
+    // Source characters translated per day, oldest first.
+    pub daily_chars: Vec<u64>,
+    // ("EN→ES", chars) busiest first.
+    pub pairs: Vec<(String, u64)>,
+    // (provider, translations) busiest first.
+    pub providers: Vec<(String, u64)>,
+}
+
+impl UsageSummary {
+    pub fn total_chars(&self) -> u64 {
+        self.pairs.iter().map(|(_, chars)| chars).sum()
+    }
+
+    /// Plain-text dashboard for `ptrui usage`.
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Characters translated, last {} days: {}",
+            self.daily_chars.len(),
+            self.daily_chars.iter().sum::<u64>()
+        )];
+        let max = self.daily_chars.iter().copied().max().unwrap_or_default();
+        for (offset, chars) in self.daily_chars.iter().enumerate() {
+            lines.push(format!(
+                "  {}  {:>7}  {}",
+                format_day(self.first_day + offset as i64),
+                chars,
+                bar(*chars, max)
+            ));
+        }
+        lines.push(String::new());
+        lines.push(format!(
+            "By language pair (all time, {} chars):",
+            self.total_chars()
+        ));
+        lines.extend(table_lines(&self.pairs));
+        lines.push(String::new());
+        lines.push("By provider (translations):".to_string());
+        lines.extend(table_lines(&self.providers));
+        lines
+    }
+}
+
+fn table_lines(rows: &[(String, u64)]) -> Vec<String> {
+    let max = rows
+        .iter()
+        .map(|(_, value)| *value)
+        .max()
+        .unwrap_or_default();
+    let width = rows.iter().map(|(label, _)| label.chars().count()).max();
+    rows.iter()
+        .map(|(label, value)| {
+            format!(
+                "  {:<width$}  {:>7}  {}",
+                label,
+                value,
+                bar(*value, max),
+                width = width.unwrap_or_default()
+            )
+        })
+        .collect()
+}
+
+fn bar(value: u64, max: u64) -> String {
+    if max == 0 {
+        return String::new();
+    }
+    "█".repeat((value * BAR_WIDTH).div_ceil(max) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_bars_to_the_busiest_row() {
+        let summary = UsageSummary {
+            first_day: 0,
+            daily_chars: vec![0, 10],
+            pairs: vec![("EN→ES".to_string(), 10), ("EN→JA".to_string(), 1)],
+            providers: vec![("api.deepl.com".to_string(), 3)],
+        };
+        let lines = summary.summary_lines();
+        assert_eq!(lines[1], "  1970-01-01        0  ");
+        assert!(lines[2].ends_with(&"█".repeat(30)));
+        assert_eq!(lines[6], "  EN→JA        1  ███");
+    }
+}
@@ -1,12 +1,22 @@
 use ratatui::style::{Color, Modifier, Style};
 use tui_textarea::{CursorMove, Input, Key, Scrolling, TextArea};
 
+use crate::text_object::text_object;
+use crate::textarea::select_range;
+
+// Counts stop growing here, so a mistyped `999999999p` can't stall the UI.
+const MAX_COUNT: usize = 10_000;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Mode {
     Normal,
     Insert,
     Visual,
     Operator(char),
+    // Typing a `/` pattern.
+    Search,
+    // `R`: typed characters overwrite the text.
+    Replace,
 }
 
 impl Mode {
@@ -16,6 +26,8 @@ impl Mode {
             Self::Insert => Color::LightBlue,
             Self::Visual => Color::LightYellow,
             Self::Operator(_) => Color::LightGreen,
+            Self::Search => Color::LightMagenta,
+            Self::Replace => Color::LightRed,
         };
         Style::default().fg(color).add_modifier(Modifier::REVERSED)
     }
@@ -28,6 +40,8 @@ impl std::fmt::Display for Mode {
             Self::Insert => write!(f, "INSERT"),
             Self::Visual => write!(f, "VISUAL"),
             Self::Operator(c) => write!(f, "OPERATOR({})", c),
+            Self::Search => write!(f, "SEARCH"),
+            Self::Replace => write!(f, "REPLACE"),
         }
     }
 }
@@ -35,12 +49,35 @@ impl std::fmt::Display for Mode {
 pub enum Transition {
     Nop,
     Mode(Mode),
+    // A yank or cut filled the pane's register; the mode is now this.
+    Yank(Mode),
     Pending(Input),
+    // A digit extended the count prefix to this.
+    Count(usize),
+    // The `/` prompt now reads this.
+    Query(String),
+    // `"a`: the next yank, cut or paste uses this register.
+    Register(char),
 }
 
 pub struct Vim {
     pub mode: Mode,
     pub pending: Input,
+    // Count typed before the next motion or operator; 0 when none.
+    pub count: usize,
+    // Count typed before the pending operator, multiplied into the motion's (`2d3w`).
+    pub operator_count: usize,
+    // The `/` prompt while in search mode.
+    pub query: String,
+    // Named register (`"a`) picked for the command being typed.
+    pub register: Option<char>,
+    // Whether the last key yanked or cut text into the pane's register.
+    pub yanked: bool,
+    // Keys of the command being typed, and whether they changed the text so far.
+    keys: Vec<Input>,
+    changed: bool,
+    // Keys of the last completed change, replayed by `.`.
+    last_change: Vec<Input>,
 }
 
 impl Vim {
@@ -48,43 +85,288 @@ impl Vim {
         Self {
             mode,
             pending: Input::default(),
+            count: 0,
+            operator_count: 0,
+            query: String::new(),
+            register: None,
+            yanked: false,
+            keys: Vec::new(),
+            changed: false,
+            last_change: Vec::new(),
+        }
+    }
+
+    /// Between commands: normal mode with nothing pending.
+    fn at_rest(&self) -> bool {
+        self.mode == Mode::Normal && self.pending == Input::default() && self.count == 0
+    }
+
+    /// Whether `input` runs a paste, rather than finishing another command like `rp`.
+    pub fn is_paste(&self, input: &Input) -> bool {
+        matches!(self.mode, Mode::Normal | Mode::Visual)
+            && self.pending == Input::default()
+            && input.key == Key::Char('p')
+            && !input.ctrl
+    }
+
+    /// Applies a key like `transition`, recording the keys of each command that changes the
+    /// text (an operator and its motion, or a whole insert session) so `.` can replay them.
+    /// Returns whether the text changed.
+    pub fn handle_input(&mut self, input: Input, textarea: &mut TextArea<'_>) -> bool {
+        let repeat = Input {
+            key: Key::Char('.'),
+            ..Input::default()
+        };
+        if input == repeat && self.mode == Mode::Normal && self.pending == Input::default() {
+            return self.repeat_change(textarea);
+        }
+        if self.at_rest() {
+            self.keys.clear();
+            self.changed = false;
+        }
+        // Undo and redo change the text but aren't changes to repeat.
+        let undo = self.mode == Mode::Normal
+            && matches!(
+                (&input.key, input.ctrl),
+                (Key::Char('u'), false) | (Key::Char('r'), true)
+            );
+        self.keys.push(input.clone());
+        let (transition, modified) = self.transition(input, textarea);
+        self.yanked = matches!(transition, Transition::Yank(_));
+        self.apply(transition);
+        self.changed |= modified && !undo;
+        if self.at_rest() && self.changed {
+            self.last_change = std::mem::take(&mut self.keys);
+            self.changed = false;
+        }
+        modified
+    }
+
+    /// `.`: replays the last change; a count given to `.` replaces the change's own.
+    fn repeat_change(&mut self, textarea: &mut TextArea<'_>) -> bool {
+        let mut keys = self.last_change.clone();
+        if self.count > 0 {
+            let digits = keys
+                .iter()
+                .take_while(|input| matches!(input.key, Key::Char('0'..='9')))
+                .count();
+            keys.splice(
+                ..digits,
+                self.count.to_string().chars().map(|digit| Input {
+                    key: Key::Char(digit),
+                    ..Input::default()
+                }),
+            );
+            self.count = 0;
+        }
+        let mut modified = false;
+        self.yanked = false;
+        for input in keys {
+            let (transition, changed) = self.transition(input, textarea);
+            self.yanked |= matches!(transition, Transition::Yank(_));
+            self.apply(transition);
+            modified |= changed;
         }
+        modified
     }
 
-    pub fn transition(&self, input: Input, textarea: &mut TextArea<'_>) -> Transition {
+    /// How many times the next motion or operator runs.
+    pub fn times(&self) -> usize {
+        self.count
+            .max(1)
+            .saturating_mul(self.operator_count.max(1))
+            .min(MAX_COUNT)
+    }
+
+    pub fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::Nop => {
+                self.count = 0;
+                self.pending = Input::default();
+                self.register = None;
+            }
+            Transition::Pending(input) => {
+                // Only `g`, `r` and `"` start two-key commands that take the count (`3gg`,
+                // `3rx`, `2"ayy`).
+                if !matches!(input.key, Key::Char('g' | 'r' | '"')) {
+                    self.count = 0;
+                }
+                self.pending = input;
+            }
+            Transition::Count(count) => self.count = count,
+            Transition::Query(query) => self.query = query,
+            Transition::Register(name) => {
+                self.register = Some(name);
+                self.pending = Input::default();
+            }
+            Transition::Yank(mode) => self.apply(Transition::Mode(mode)),
+            Transition::Mode(mode) => {
+                self.query.clear();
+                if !matches!(mode, Mode::Operator(_)) {
+                    self.register = None;
+                }
+                self.operator_count = match mode {
+                    Mode::Operator(_) => self.count,
+                    _ => 0,
+                };
+                self.mode = mode;
+                self.pending = Input::default();
+                self.count = 0;
+            }
+        }
+    }
+
+    /// Inserts pasted text as a single edit, so one `u` undoes it; returns whether the text
+    /// changed. Outside insert mode the paste is refused rather than read as commands.
+    pub fn paste(&self, text: &str, textarea: &mut TextArea<'_>) -> Result<bool, &'static str> {
+        if self.mode != Mode::Insert {
+            return Err("Paste refused outside insert mode; press i first");
+        }
+        Ok(textarea.insert_str(text.replace("\r\n", "\n").replace('\r', "\n")))
+    }
+
+    /// Applies a key; also returns whether the text changed, from the textarea's own edit
+    /// results, so callers never need to compare whole buffers.
+    pub fn transition(&self, input: Input, textarea: &mut TextArea<'_>) -> (Transition, bool) {
         if input.key == Key::Null {
-            return Transition::Nop;
+            return (Transition::Nop, false);
         }
 
+        let times = self.times();
         match self.mode {
             Mode::Normal | Mode::Visual | Mode::Operator(_) => {
+                // Stops early once the cursor can't go any further.
+                let mut repeat = |cursor_move: CursorMove| {
+                    for _ in 0..times {
+                        let cursor = textarea.cursor();
+                        textarea.move_cursor(cursor_move);
+                        if textarea.cursor() == cursor {
+                            break;
+                        }
+                    }
+                };
                 match input {
+                    Input {
+                        key: Key::Char(c),
+                        ctrl: false,
+                        alt: false,
+                        ..
+                    } if self.mode == Mode::Normal
+                        && self.pending.key == Key::Char('r')
+                        && !self.pending.ctrl =>
+                    {
+                        return (
+                            Transition::Mode(Mode::Normal),
+                            replace_chars(textarea, c, times),
+                        );
+                    }
+                    Input {
+                        key: Key::Char(name),
+                        ctrl: false,
+                        alt: false,
+                        ..
+                    } if self.pending.key == Key::Char('"')
+                        && matches!(self.mode, Mode::Normal | Mode::Visual) =>
+                    {
+                        if !name.is_ascii_alphabetic() {
+                            textarea.cancel_selection();
+                            return (Transition::Mode(Mode::Normal), false);
+                        }
+                        return (Transition::Register(name), false);
+                    }
+                    // A leading 0 isn't a count.
+                    Input {
+                        key: Key::Char(digit @ '0'..='9'),
+                        ctrl: false,
+                        alt: false,
+                        ..
+                    } if digit != '0' || self.count > 0 => {
+                        let count = self
+                            .count
+                            .saturating_mul(10)
+                            .saturating_add(digit as usize - '0' as usize)
+                            .min(MAX_COUNT);
+                        return (Transition::Count(count), false);
+                    }
+                    // `i` and `a` after an operator start a text object (`diw`, `ca(`).
+                    Input {
+                        key: Key::Char(object),
+                        ctrl: false,
+                        alt: false,
+                        ..
+                    } if matches!(self.mode, Mode::Operator(_))
+                        && matches!(self.pending.key, Key::Char('i' | 'a')) =>
+                    {
+                        let around = self.pending.key == Key::Char('a');
+                        match text_object(textarea.lines(), textarea.cursor(), object, around) {
+                            Some((start, end)) => select_range(textarea, start, end),
+                            None => {
+                                textarea.cancel_selection();
+                                return (Transition::Mode(Mode::Normal), false);
+                            }
+                        }
+                    }
+                    Input {
+                        key: Key::Char('i' | 'a'),
+                        ctrl: false,
+                        alt: false,
+                        ..
+                    } if matches!(self.mode, Mode::Operator(_)) => {
+                        return (Transition::Pending(input), false);
+                    }
+                    Input {
+                        key: Key::Char('/'),
+                        ..
+                    } if self.mode == Mode::Normal => {
+                        return (Transition::Mode(Mode::Search), false);
+                    }
+                    Input {
+                        key: Key::Char('n'),
+                        ctrl: false,
+                        ..
+                    } => {
+                        for _ in 0..times {
+                            textarea.search_forward(false);
+                        }
+                    }
+                    Input {
+                        key: Key::Char('N'),
+                        ctrl: false,
+                        ..
+                    } => {
+                        for _ in 0..times {
+                            textarea.search_back(false);
+                        }
+                    }
+                    Input { key: Key::Esc, .. } if self.mode == Mode::Normal => {
+                        set_search(textarea, "");
+                    }
                     Input {
                         key: Key::Char('h'),
                         ..
-                    } => textarea.move_cursor(CursorMove::Back),
+                    } => repeat(CursorMove::Back),
                     Input {
                         key: Key::Char('j'),
                         ..
-                    } => textarea.move_cursor(CursorMove::Down),
+                    } => repeat(CursorMove::Down),
                     Input {
                         key: Key::Char('k'),
                         ..
-                    } => textarea.move_cursor(CursorMove::Up),
+                    } => repeat(CursorMove::Up),
                     Input {
                         key: Key::Char('l'),
                         ..
-                    } => textarea.move_cursor(CursorMove::Forward),
+                    } => repeat(CursorMove::Forward),
                     Input {
                         key: Key::Char('w'),
                         ..
-                    } => textarea.move_cursor(CursorMove::WordForward),
+                    } => repeat(CursorMove::WordForward),
                     Input {
                         key: Key::Char('e'),
                         ctrl: false,
                         ..
                     } => {
-                        textarea.move_cursor(CursorMove::WordEnd);
+                        repeat(CursorMove::WordEnd);
                         if matches!(self.mode, Mode::Operator(_)) {
                             textarea.move_cursor(CursorMove::Forward);
                         }
@@ -93,7 +375,7 @@ impl Vim {
                         key: Key::Char('b'),
                         ctrl: false,
                         ..
-                    } => textarea.move_cursor(CursorMove::WordBack),
+                    } => repeat(CursorMove::WordBack),
                     Input {
                         key: Key::Char('^'),
                         ..
@@ -106,53 +388,55 @@ impl Vim {
                         key: Key::Char('D'),
                         ..
                     } => {
-                        textarea.delete_line_by_end();
-                        return Transition::Mode(Mode::Normal);
+                        return (
+                            Transition::Yank(Mode::Normal),
+                            textarea.delete_line_by_end(),
+                        );
                     }
                     Input {
                         key: Key::Char('C'),
                         ..
                     } => {
-                        textarea.delete_line_by_end();
+                        let modified = textarea.delete_line_by_end();
                         textarea.cancel_selection();
-                        return Transition::Mode(Mode::Insert);
+                        return (Transition::Yank(Mode::Insert), modified);
                     }
                     Input {
                         key: Key::Char('p'),
                         ..
                     } => {
-                        textarea.paste();
-                        return Transition::Mode(Mode::Normal);
+                        let modified = repeat_edit(times, || textarea.paste());
+                        return (Transition::Mode(Mode::Normal), modified);
                     }
                     Input {
                         key: Key::Char('u'),
                         ctrl: false,
                         ..
                     } => {
-                        textarea.undo();
-                        return Transition::Mode(Mode::Normal);
+                        let modified = repeat_edit(times, || textarea.undo());
+                        return (Transition::Mode(Mode::Normal), modified);
                     }
                     Input {
                         key: Key::Char('r'),
                         ctrl: true,
                         ..
                     } => {
-                        textarea.redo();
-                        return Transition::Mode(Mode::Normal);
+                        let modified = repeat_edit(times, || textarea.redo());
+                        return (Transition::Mode(Mode::Normal), modified);
                     }
                     Input {
                         key: Key::Char('x'),
                         ..
                     } => {
-                        textarea.delete_next_char();
-                        return Transition::Mode(Mode::Normal);
+                        let modified = repeat_edit(times, || textarea.delete_next_char());
+                        return (Transition::Mode(Mode::Normal), modified);
                     }
                     Input {
                         key: Key::Char('i'),
                         ..
                     } => {
                         textarea.cancel_selection();
-                        return Transition::Mode(Mode::Insert);
+                        return (Transition::Mode(Mode::Insert), false);
                     }
                     Input {
                         key: Key::Char('a'),
@@ -160,7 +444,7 @@ impl Vim {
                     } => {
                         textarea.cancel_selection();
                         textarea.move_cursor(CursorMove::Forward);
-                        return Transition::Mode(Mode::Insert);
+                        return (Transition::Mode(Mode::Insert), false);
                     }
                     Input {
                         key: Key::Char('A'),
@@ -168,7 +452,7 @@ impl Vim {
                     } => {
                         textarea.cancel_selection();
                         textarea.move_cursor(CursorMove::End);
-                        return Transition::Mode(Mode::Insert);
+                        return (Transition::Mode(Mode::Insert), false);
                     }
                     Input {
                         key: Key::Char('o'),
@@ -176,7 +460,7 @@ impl Vim {
                     } => {
                         textarea.move_cursor(CursorMove::End);
                         textarea.insert_newline();
-                        return Transition::Mode(Mode::Insert);
+                        return (Transition::Mode(Mode::Insert), true);
                     }
                     Input {
                         key: Key::Char('O'),
@@ -185,7 +469,13 @@ impl Vim {
                         textarea.move_cursor(CursorMove::Head);
                         textarea.insert_newline();
                         textarea.move_cursor(CursorMove::Up);
-                        return Transition::Mode(Mode::Insert);
+                        return (Transition::Mode(Mode::Insert), true);
+                    }
+                    Input {
+                        key: Key::Char('R'),
+                        ..
+                    } if self.mode == Mode::Normal => {
+                        return (Transition::Mode(Mode::Replace), false);
                     }
                     Input {
                         key: Key::Char('I'),
@@ -193,18 +483,13 @@ impl Vim {
                     } => {
                         textarea.cancel_selection();
                         textarea.move_cursor(CursorMove::Head);
-                        return Transition::Mode(Mode::Insert);
+                        return (Transition::Mode(Mode::Insert), false);
                     }
                     Input {
                         key: Key::Char('e'),
                         ctrl: true,
                         ..
                     } => textarea.scroll((1, 0)),
-                    Input {
-                        key: Key::Char('y'),
-                        ctrl: true,
-                        ..
-                    } => textarea.scroll((-1, 0)),
                     Input {
                         key: Key::Char('d'),
                         ctrl: true,
@@ -231,7 +516,7 @@ impl Vim {
                         ..
                     } if self.mode == Mode::Normal => {
                         textarea.start_selection();
-                        return Transition::Mode(Mode::Visual);
+                        return (Transition::Mode(Mode::Visual), false);
                     }
                     Input {
                         key: Key::Char('V'),
@@ -241,7 +526,7 @@ impl Vim {
                         textarea.move_cursor(CursorMove::Head);
                         textarea.start_selection();
                         textarea.move_cursor(CursorMove::End);
-                        return Transition::Mode(Mode::Visual);
+                        return (Transition::Mode(Mode::Visual), false);
                     }
                     Input { key: Key::Esc, .. }
                     | Input {
@@ -250,7 +535,7 @@ impl Vim {
                         ..
                     } if self.mode == Mode::Visual => {
                         textarea.cancel_selection();
-                        return Transition::Mode(Mode::Normal);
+                        return (Transition::Mode(Mode::Normal), false);
                     }
                     Input {
                         key: Key::Char('g'),
@@ -265,13 +550,13 @@ impl Vim {
                         }
                     ) =>
                     {
-                        textarea.move_cursor(CursorMove::Top)
+                        go_to_line(textarea, self.count, CursorMove::Top)
                     }
                     Input {
                         key: Key::Char('G'),
                         ctrl: false,
                         ..
-                    } => textarea.move_cursor(CursorMove::Bottom),
+                    } => go_to_line(textarea, self.count, CursorMove::Bottom),
                     Input {
                         key: Key::Char(c),
                         ctrl: false,
@@ -279,10 +564,13 @@ impl Vim {
                     } if self.mode == Mode::Operator(c) => {
                         textarea.move_cursor(CursorMove::Head);
                         textarea.start_selection();
-                        let cursor = textarea.cursor();
-                        textarea.move_cursor(CursorMove::Down);
-                        if cursor == textarea.cursor() {
-                            textarea.move_cursor(CursorMove::End);
+                        for _ in 0..times {
+                            let cursor = textarea.cursor();
+                            textarea.move_cursor(CursorMove::Down);
+                            if cursor == textarea.cursor() {
+                                textarea.move_cursor(CursorMove::End);
+                                break;
+                            }
                         }
                     }
                     Input {
@@ -291,7 +579,7 @@ impl Vim {
                         ..
                     } if self.mode == Mode::Normal => {
                         textarea.start_selection();
-                        return Transition::Mode(Mode::Operator(op));
+                        return (Transition::Mode(Mode::Operator(op)), false);
                     }
                     Input {
                         key: Key::Char('y'),
@@ -300,7 +588,7 @@ impl Vim {
                     } if self.mode == Mode::Visual => {
                         textarea.move_cursor(CursorMove::Forward);
                         textarea.copy();
-                        return Transition::Mode(Mode::Normal);
+                        return (Transition::Yank(Mode::Normal), false);
                     }
                     Input {
                         key: Key::Char('d'),
@@ -308,8 +596,7 @@ impl Vim {
                         ..
                     } if self.mode == Mode::Visual => {
                         textarea.move_cursor(CursorMove::Forward);
-                        textarea.cut();
-                        return Transition::Mode(Mode::Normal);
+                        return (Transition::Yank(Mode::Normal), textarea.cut());
                     }
                     Input {
                         key: Key::Char('c'),
@@ -317,40 +604,161 @@ impl Vim {
                         ..
                     } if self.mode == Mode::Visual => {
                         textarea.move_cursor(CursorMove::Forward);
-                        textarea.cut();
-                        return Transition::Mode(Mode::Insert);
+                        return (Transition::Yank(Mode::Insert), textarea.cut());
                     }
-                    input => return Transition::Pending(input),
+                    input => return (Transition::Pending(input), false),
                 }
 
                 match self.mode {
                     Mode::Operator('y') => {
                         textarea.copy();
-                        Transition::Mode(Mode::Normal)
-                    }
-                    Mode::Operator('d') => {
-                        textarea.cut();
-                        Transition::Mode(Mode::Normal)
+                        (Transition::Yank(Mode::Normal), false)
                     }
-                    Mode::Operator('c') => {
-                        textarea.cut();
-                        Transition::Mode(Mode::Insert)
-                    }
-                    _ => Transition::Nop,
+                    Mode::Operator('d') => (Transition::Yank(Mode::Normal), textarea.cut()),
+                    Mode::Operator('c') => (Transition::Yank(Mode::Insert), textarea.cut()),
+                    _ => (Transition::Nop, false),
                 }
             }
+            Mode::Search => match input {
+                Input { key: Key::Esc, .. }
+                | Input {
+                    key: Key::Char('c'),
+                    ctrl: true,
+                    ..
+                } => {
+                    set_search(textarea, "");
+                    (Transition::Mode(Mode::Normal), false)
+                }
+                Input {
+                    key: Key::Enter, ..
+                } => {
+                    textarea.search_forward(false);
+                    (Transition::Mode(Mode::Normal), false)
+                }
+                // Backspace on an empty prompt leaves search mode, like Vim.
+                Input {
+                    key: Key::Backspace,
+                    ..
+                } if self.query.is_empty() => (Transition::Mode(Mode::Normal), false),
+                Input {
+                    key: Key::Backspace,
+                    ..
+                } => {
+                    let mut query = self.query.clone();
+                    query.pop();
+                    set_search(textarea, &query);
+                    (Transition::Query(query), false)
+                }
+                Input {
+                    key: Key::Char(c),
+                    ctrl: false,
+                    alt: false,
+                    ..
+                } => {
+                    let query = format!("{}{}", self.query, c);
+                    set_search(textarea, &query);
+                    (Transition::Query(query), false)
+                }
+                _ => (Transition::Nop, false),
+            },
+            Mode::Replace => match input {
+                Input { key: Key::Esc, .. }
+                | Input {
+                    key: Key::Char('c'),
+                    ctrl: true,
+                    ..
+                } => (Transition::Mode(Mode::Normal), false),
+                Input {
+                    key: Key::Char(c),
+                    ctrl: false,
+                    alt: false,
+                    ..
+                } => {
+                    let (row, col) = textarea.cursor();
+                    if col < textarea.lines()[row].chars().count() {
+                        textarea.delete_next_char();
+                    }
+                    textarea.insert_char(c);
+                    (Transition::Mode(Mode::Replace), true)
+                }
+                // Backspace only steps back; the overwritten text stays as typed.
+                Input {
+                    key: Key::Backspace,
+                    ..
+                } => {
+                    textarea.move_cursor(CursorMove::Back);
+                    (Transition::Mode(Mode::Replace), false)
+                }
+                input => {
+                    let modified = textarea.input(input);
+                    (Transition::Mode(Mode::Replace), modified)
+                }
+            },
             Mode::Insert => match input {
                 Input { key: Key::Esc, .. }
                 | Input {
                     key: Key::Char('c'),
                     ctrl: true,
                     ..
-                } => Transition::Mode(Mode::Normal),
+                } => (Transition::Mode(Mode::Normal), false),
                 input => {
-                    textarea.input(input);
-                    Transition::Mode(Mode::Insert)
+                    let modified = textarea.input(input);
+                    (Transition::Mode(Mode::Insert), modified)
                 }
             },
         }
     }
 }
+
+/// Highlights matches of `query` as typed: literal text, ignoring case unless it has
+/// capitals. An empty query clears the highlight.
+fn set_search(textarea: &mut TextArea<'_>, query: &str) {
+    let pattern = match query {
+        "" => String::new(),
+        _ if query.chars().any(char::is_uppercase) => regex::escape(query),
+        _ => format!("(?i){}", regex::escape(query)),
+    };
+    // An escaped pattern always compiles.
+    let _ = textarea.set_search_pattern(pattern);
+}
+
+/// `r`: overwrites `times` characters from the cursor with `c`, leaving the cursor on the
+/// last one. Does nothing when the line is too short, like Vim.
+fn replace_chars(textarea: &mut TextArea<'_>, c: char, times: usize) -> bool {
+    let (row, col) = textarea.cursor();
+    if col + times > textarea.lines()[row].chars().count() {
+        return false;
+    }
+    textarea.cancel_selection();
+    textarea.start_selection();
+    for _ in 0..times {
+        textarea.move_cursor(CursorMove::Forward);
+    }
+    textarea.insert_str(c.to_string().repeat(times));
+    textarea.move_cursor(CursorMove::Back);
+    true
+}
+
+/// `gg` and `G`: a count picks the line (1-based), else they go to `default`.
+fn go_to_line(textarea: &mut TextArea<'_>, count: usize, default: CursorMove) {
+    match count {
+        0 => textarea.move_cursor(default),
+        line => {
+            let row = (line - 1).min(u16::MAX as usize) as u16;
+            textarea.move_cursor(CursorMove::Jump(row, 0));
+        }
+    }
+}
+
+/// Runs an edit up to `times` times, stopping at the first that changes nothing; whether
+/// any run changed the text.
+fn repeat_edit(times: usize, mut edit: impl FnMut() -> bool) -> bool {
+    let mut modified = false;
+    for _ in 0..times {
+        if !edit() {
+            break;
+        }
+        modified = true;
+    }
+    modified
+}
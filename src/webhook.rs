@@ -0,0 +1,145 @@
+use std::env;
+use std::sync::mpsc::{self, SyncSender};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{Map, Value, json};
+
+use crate::dates::unix_now;
+
+/// Payload fields a webhook can receive; `PTRUI_WEBHOOK_FIELDS` picks a subset.
+const FIELDS: &[&str] = &[
+    "event",
+    "timestamp",
+    "source_lang",
+    "target_lang",
+    "provider",
+    "chars",
+    "source_text",
+    "target_text",
+];
+// Payloads waiting for delivery; more are dropped while the endpoint is this far behind.
+const QUEUE_SIZE: usize = 32;
+
+/// A completed translation, as reported to the webhook.
+pub struct TranslationEvent<'a> {
+    pub source_text: &'a str,
+    pub target_text: &'a str,
+    pub source_lang: &'a str,
+    pub target_lang: &'a str,
+    pub provider: &'a str,
+}
+
+/// Posts a JSON payload to a configured URL after each completed translation.
+pub struct Webhook {
+    pub url: String,
+    pub fields: Vec<String>,
+    // Feeds the delivery thread.
+    deliveries: SyncSender<Map<String, Value>>,
+}
+
+impl Webhook {
+    /// `PTRUI_WEBHOOK_URL` opts in; `PTRUI_WEBHOOK_FIELDS` (comma separated) limits the payload.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let Some(url) = env::var("PTRUI_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+        let fields = match env::var("PTRUI_WEBHOOK_FIELDS") {
+            Ok(value) => parse_fields(&value)?,
+            Err(_) => FIELDS.iter().map(|field| field.to_string()).collect(),
+        };
+        Self::new(url.trim(), fields).map(Some)
+    }
+
+    /// Starts the thread that delivers payloads to `url` one at a time.
+    pub fn new(url: &str, fields: Vec<String>) -> Result<Self, String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|err| format!("Failed to build HTTP client: {}", err))?;
+        let (deliveries, queue) = mpsc::sync_channel::<Map<String, Value>>(QUEUE_SIZE);
+        let target = url.to_string();
+        thread::spawn(move || {
+            for payload in queue {
+                let _ = client.post(&target).json(&payload).send();
+            }
+        });
+        Ok(Self {
+            url: url.to_string(),
+            fields,
+            deliveries,
+        })
+    }
+
+    pub fn payload(&self, event: &TranslationEvent) -> Map<String, Value> {
+        let all = json!({
+            "event": "translation.completed",
+            "timestamp": unix_now(),
+            "source_lang": event.source_lang,
+            "target_lang": event.target_lang,
+            "provider": event.provider,
+            "chars": event.source_text.chars().count(),
+            "source_text": event.source_text,
+            "target_text": event.target_text,
+        });
+        let Value::Object(mut all) = all else {
+            return Map::new();
+        };
+        all.retain(|key, _| self.fields.iter().any(|field| field == key));
+        all
+    }
+
+    /// Delivers in the background so a slow endpoint never stalls typing. Delivery is best
+    /// effort: failures are dropped rather than retried, as are events once the queue is full.
+    pub fn send(&self, event: &TranslationEvent) {
+        let _ = self.deliveries.try_send(self.payload(event));
+    }
+}
+
+fn parse_fields(value: &str) -> Result<Vec<String>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            if FIELDS.contains(&field) {
+                Ok(field.to_string())
+            } else {
+                Err(format!(
+                    "Unknown PTRUI_WEBHOOK_FIELDS entry {:?}; expected some of {}",
+                    field,
+                    FIELDS.join(", ")
+                ))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_keeps_only_selected_fields() {
+        let webhook = Webhook::new(
+            "http://localhost/hook",
+            parse_fields("source_lang, target_lang,chars").unwrap(),
+        )
+        .unwrap();
+        let payload = webhook.payload(&TranslationEvent {
+            source_text: "hola",
+            target_text: "hello",
+            source_lang: "ES",
+            target_lang: "EN",
+            provider: "api.deepl.com",
+        });
+        assert_eq!(
+            Value::Object(payload),
+            json!({ "source_lang": "ES", "target_lang": "EN", "chars": 4 })
+        );
+        assert!(parse_fields("source_text,api_key").is_err());
+    }
+}
@@ -0,0 +1,224 @@
+use reqwest::Url;
+
+// Elements whose content is never part of the article text.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "nav", "header", "footer", "aside", "form",
+    "button", "select", "figure",
+];
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "br",
+    "li",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+    "pre",
+    "tr",
+    "section",
+    "article",
+    "main",
+    "dd",
+    "dt",
+];
+// Blocks with fewer words are treated as navigation, bylines or button labels.
+const MIN_BLOCK_WORDS: usize = 4;
+
+/// Downloads a web page and returns its readable text, one paragraph per line.
+pub fn fetch_readable_text(
+    client: &reqwest::blocking::Client,
+    url: &str,
+) -> Result<String, String> {
+    let url = Url::parse(url).map_err(|err| format!("Invalid URL {}: {}", url, err))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Unsupported URL scheme: {}", url.scheme()));
+    }
+    let response = client
+        .get(url.clone())
+        .header(
+            reqwest::header::USER_AGENT,
+            concat!("ptrui/", env!("CARGO_PKG_VERSION")),
+        )
+        .send()
+        .map_err(|err| format!("Failed to fetch {}: {}", url, err))?;
+    if !response.status().is_success() {
+        return Err(format!("Fetch error ({})", response.status()));
+    }
+    let html = response
+        .text()
+        .map_err(|err| format!("Failed to read {}: {}", url, err))?;
+    let text = extract_readable_text(&html);
+    if text.is_empty() {
+        return Err(format!("No readable text found at {}", url));
+    }
+    Ok(text)
+}
+
+/// Readability-style extraction: prefers `<article>`/`<main>`, drops page chrome and short blocks.
+pub fn extract_readable_text(html: &str) -> String {
+    // ASCII lowercasing keeps byte offsets, so tag searches can index into the original.
+    let lower = html.to_ascii_lowercase();
+    let (start, end) = ["article", "main", "body"]
+        .iter()
+        .find_map(|tag| element_range(&lower, tag))
+        .unwrap_or((0, html.len()));
+    let html = &html[start..end];
+    let lower = &lower[start..end];
+
+    let mut blocks = vec![String::new()];
+    let mut skip_depth: Vec<String> = Vec::new();
+    let mut rest = 0;
+    while let Some(open) = lower[rest..].find('<').map(|i| rest + i) {
+        if skip_depth.is_empty() {
+            push_text(&mut blocks, &html[rest..open]);
+        }
+        if lower[open..].starts_with("<!--") {
+            rest = lower[open..]
+                .find("-->")
+                .map_or(lower.len(), |i| open + i + 3);
+            continue;
+        }
+        let Some(close) = lower[open..].find('>').map(|i| open + i) else {
+            rest = lower.len();
+            break;
+        };
+        let tag = &lower[open + 1..close];
+        let closing = tag.starts_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        rest = close + 1;
+
+        if SKIPPED_ELEMENTS.contains(&name.as_str()) && !tag.ends_with('/') {
+            if closing {
+                if let Some(index) = skip_depth.iter().rposition(|open| *open == name) {
+                    skip_depth.truncate(index);
+                }
+            } else {
+                skip_depth.push(name);
+            }
+            continue;
+        }
+        if skip_depth.is_empty() && BLOCK_ELEMENTS.contains(&name.as_str()) {
+            blocks.push(String::new());
+        }
+    }
+    if skip_depth.is_empty() {
+        push_text(&mut blocks, &html[rest..]);
+    }
+
+    blocks
+        .into_iter()
+        .map(|block| block.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|block| block.split_whitespace().count() >= MIN_BLOCK_WORDS)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Byte range of the first `<tag ...>...</tag>` element's content.
+fn element_range(lower: &str, tag: &str) -> Option<(usize, usize)> {
+    let open = format!("<{}", tag);
+    let start = lower.match_indices(&open).find_map(|(index, _)| {
+        let after = lower[index + open.len()..].chars().next()?;
+        (after == '>' || after.is_whitespace())
+            .then(|| lower[index..].find('>').map(|close| index + close + 1))
+            .flatten()
+    })?;
+    let end = lower[start..]
+        .rfind(&format!("</{}", tag))
+        .map_or(lower.len(), |i| start + i);
+    Some((start, end))
+}
+
+fn push_text(blocks: &mut [String], raw: &str) {
+    if let Some(block) = blocks.last_mut() {
+        block.push_str(&decode_entities(raw));
+    }
+}
+
+/// Decodes the named entities common in article text plus numeric references.
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "mdash" => Some('—'),
+                "ndash" => Some('–'),
+                "hellip" => Some('…'),
+                "laquo" => Some('«'),
+                "raquo" => Some('»'),
+                "rsquo" => Some('’'),
+                "lsquo" => Some('‘'),
+                "rdquo" => Some('”'),
+                "ldquo" => Some('“'),
+                "aacute" => Some('á'),
+                "eacute" => Some('é'),
+                "iacute" => Some('í'),
+                "oacute" => Some('ó'),
+                "uacute" => Some('ú'),
+                "ntilde" => Some('ñ'),
+                "uuml" => Some('ü'),
+                "ccedil" => Some('ç'),
+                "agrave" => Some('à'),
+                "egrave" => Some('è'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            }?;
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_article_paragraphs() {
+        let html = r#"<html><head><title>T</title><style>p{}</style></head><body>
+            <nav><a href="/">Home</a> <a href="/news">News</a></nav>
+            <ARTICLE class="story"><h1>Short title</h1>
+            <p>El gato duerme en la casa&nbsp;todo el d&iacute;a.</p>
+            <!-- <p>commented out paragraph text here</p> -->
+            <script>var ignored = "this script text";</script>
+            <p>Caf&#233; y pan &amp; mantequilla para desayunar.</p>
+            <footer>Share this article on social media now</footer>
+            </article></body></html>"#;
+        assert_eq!(
+            extract_readable_text(html),
+            "El gato duerme en la casa todo el día.\nCafé y pan & mantequilla para desayunar."
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::env;
+
+use crate::frequency::FrequencyList;
+use crate::phrasebook::PhraseEntry;
+
+/// A word or phrase to study, shown in a small panel at startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordOfTheDay {
+    pub text: String,
+    pub language: String,
+    // Filled in later for frequency-list words, which come without one.
+    pub translation: Option<String>,
+    pub origin: &'static str,
+}
+
+/// `PTRUI_WORD_OF_THE_DAY=1` opts into the startup panel.
+pub fn word_of_the_day_enabled() -> bool {
+    env::var("PTRUI_WORD_OF_THE_DAY")
+        .is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes"))
+}
+
+/// Today's pick for `language`: a saved phrase in that language if there are any, else an
+/// uncommon word from its frequency list. The choice looks random but stays the same all day.
+pub fn pick_word_of_the_day(
+    phrases: &[PhraseEntry],
+    frequency: Option<&FrequencyList>,
+    language: &str,
+    day: i64,
+) -> Option<WordOfTheDay> {
+    let saved: Vec<(&str, &str)> = phrases
+        .iter()
+        .filter_map(|entry| {
+            if entry.target_lang.eq_ignore_ascii_case(language) {
+                Some((entry.target.as_str(), entry.source.as_str()))
+            } else if entry.source_lang.eq_ignore_ascii_case(language) {
+                Some((entry.source.as_str(), entry.target.as_str()))
+            } else {
+                None
+            }
+        })
+        .collect();
+    if !saved.is_empty() {
+        let (text, translation) = saved[day_index(day, saved.len())];
+        return Some(WordOfTheDay {
+            text: text.to_string(),
+            language: language.to_string(),
+            translation: Some(translation.to_string()),
+            origin: "phrasebook",
+        });
+    }
+    let words = frequency?.uncommon_words();
+    if words.is_empty() {
+        return None;
+    }
+    Some(WordOfTheDay {
+        text: words[day_index(day, words.len())].to_string(),
+        language: language.to_string(),
+        translation: None,
+        origin: "frequency list",
+    })
+}
+
+/// Scatters consecutive days across the list instead of walking it in order.
+fn day_index(day: i64, len: usize) -> usize {
+    let mixed = (day as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32;
+    (mixed % len as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_saved_phrases_in_the_language() {
+        let phrases = [
+            PhraseEntry::new("good morning", "buenos días", "EN", "ES", None),
+            PhraseEntry::new("thank you", "danke", "EN", "DE", None),
+        ];
+        let word = pick_word_of_the_day(&phrases, None, "DE", 20_000).unwrap();
+        assert_eq!(word.text, "danke");
+        assert_eq!(word.translation.as_deref(), Some("thank you"));
+
+        let list = FrequencyList::parse("el\nmariposa\n");
+        let word = pick_word_of_the_day(&[], Some(&list), "ES", 20_000).unwrap();
+        assert_eq!(word.text, "mariposa");
+        assert_eq!(word.translation, None);
+        assert_eq!(pick_word_of_the_day(&[], None, "ES", 20_000), None);
+    }
+}